@@ -6,8 +6,6 @@
 // - Extract timing and agent statistics
 // - Detect patterns in logs
 
-use chrono::{Duration, Utc};
-
 // We can't use the types directly in examples since they're in a binary crate
 // This is a standalone example showing the API usage patterns
 
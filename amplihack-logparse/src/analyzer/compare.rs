@@ -0,0 +1,171 @@
+// Session comparison
+//
+// Diffs two sessions to help track down regressions:
+// - Entry count and duration deltas
+// - Agents that appear in only one session
+// - Invocation count changes for shared agents
+
+use crate::analyzer::{Analyzer, TimingAnalyzer};
+use crate::types::{LogEntry, LogSession};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Change in invocation count for an agent present in both sessions
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvocationChange {
+    pub agent: String,
+    pub count_a: usize,
+    pub count_b: usize,
+}
+
+impl InvocationChange {
+    /// Signed change from session A to session B
+    pub fn delta(&self) -> i64 {
+        self.count_b as i64 - self.count_a as i64
+    }
+}
+
+/// Differences between two sessions (B relative to A)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionDiff {
+    /// Entries in B minus entries in A
+    pub entry_count_delta: i64,
+
+    /// Duration of B minus duration of A, in seconds
+    pub duration_delta_secs: f64,
+
+    /// Agents seen only in session A (sorted)
+    pub agents_only_in_a: Vec<String>,
+
+    /// Agents seen only in session B (sorted)
+    pub agents_only_in_b: Vec<String>,
+
+    /// Shared agents whose invocation count changed (sorted by agent)
+    pub invocation_changes: Vec<InvocationChange>,
+}
+
+/// Compare two sessions
+///
+/// Demonstrates:
+/// - Borrowing two sessions at once
+/// - BTreeMap/BTreeSet for deterministic ordering
+pub fn compare_sessions(a: &LogSession, b: &LogSession) -> SessionDiff {
+    let timing = TimingAnalyzer::new();
+    let duration_a = timing.analyze(a).map(|t| t.total_duration_secs).unwrap_or(0.0);
+    let duration_b = timing.analyze(b).map(|t| t.total_duration_secs).unwrap_or(0.0);
+
+    let counts_a = invocation_counts(&a.entries);
+    let counts_b = invocation_counts(&b.entries);
+
+    let agents_a: BTreeSet<&String> = counts_a.keys().collect();
+    let agents_b: BTreeSet<&String> = counts_b.keys().collect();
+
+    let invocation_changes = agents_a
+        .intersection(&agents_b)
+        .filter_map(|agent| {
+            let count_a = counts_a[*agent];
+            let count_b = counts_b[*agent];
+            (count_a != count_b).then(|| InvocationChange {
+                agent: (*agent).clone(),
+                count_a,
+                count_b,
+            })
+        })
+        .collect();
+
+    SessionDiff {
+        entry_count_delta: b.entries.len() as i64 - a.entries.len() as i64,
+        duration_delta_secs: duration_b - duration_a,
+        agents_only_in_a: agents_a.difference(&agents_b).map(|s| (*s).clone()).collect(),
+        agents_only_in_b: agents_b.difference(&agents_a).map(|s| (*s).clone()).collect(),
+        invocation_changes,
+    }
+}
+
+/// Count invocations per agent name
+fn invocation_counts(entries: &[LogEntry]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+
+    for agent in entries.iter().filter_map(|e| e.agent_name.as_ref()) {
+        *counts.entry(agent.clone()).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EntryType;
+    use chrono::{Duration, Utc};
+    use std::collections::HashMap;
+
+    fn session(id: &str, agents: &[&str], span_secs: i64) -> LogSession {
+        let now = Utc::now();
+        let step = span_secs / agents.len().max(1) as i64;
+
+        let mut entries: Vec<LogEntry> = agents
+            .iter()
+            .enumerate()
+            .map(|(idx, agent)| LogEntry {
+                timestamp: now + Duration::seconds(idx as i64 * step),
+                entry_type: EntryType::AgentInvocation,
+                message: format!("{} invoked", agent),
+                agent_name: Some(agent.to_string()),
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
+
+        entries.push(LogEntry {
+            timestamp: now + Duration::seconds(span_secs),
+            entry_type: EntryType::Info,
+            message: "done".to_string(),
+            agent_name: None,
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        });
+
+        LogSession {
+            id: id.to_string(),
+            entries,
+            start_time: now,
+            end_time: Some(now + Duration::seconds(span_secs)),
+        }
+    }
+
+    #[test]
+    fn test_compare_sessions() {
+        let a = session("a", &["architect", "builder", "builder", "reviewer"], 60);
+        let b = session("b", &["architect", "builder", "tester", "tester", "tester"], 100);
+
+        let diff = compare_sessions(&a, &b);
+
+        assert_eq!(diff.entry_count_delta, 1);
+        assert_eq!(diff.duration_delta_secs, 40.0);
+        assert_eq!(diff.agents_only_in_a, vec!["reviewer"]);
+        assert_eq!(diff.agents_only_in_b, vec!["tester"]);
+        assert_eq!(
+            diff.invocation_changes,
+            vec![InvocationChange {
+                agent: "builder".to_string(),
+                count_a: 2,
+                count_b: 1,
+            }]
+        );
+        assert_eq!(diff.invocation_changes[0].delta(), -1);
+    }
+
+    #[test]
+    fn test_compare_identical_sessions() {
+        let a = session("a", &["architect"], 10);
+        let diff = compare_sessions(&a, &a.clone());
+
+        assert_eq!(diff.entry_count_delta, 0);
+        assert_eq!(diff.duration_delta_secs, 0.0);
+        assert!(diff.agents_only_in_a.is_empty());
+        assert!(diff.agents_only_in_b.is_empty());
+        assert!(diff.invocation_changes.is_empty());
+    }
+}
@@ -6,9 +6,38 @@
 // - Borrowing and lifetimes for zero-copy analysis
 // - Result types for robust error handling
 
+pub mod compare;
+
 use crate::error::ParseResult;
-use crate::types::{AgentStats, LogEntry, LogSession, TimingStats};
-use std::collections::HashMap;
+use crate::types::{AgentStats, EntryType, LogEntry, LogSession, TimingStats};
+use chrono::{DateTime, Datelike, DurationRound, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Default errors-per-second rate that counts as an error burst
+pub const DEFAULT_ERROR_BURST_THRESHOLD: f64 = 5.0;
+
+/// Default gap between entries (seconds) that counts as a long gap
+pub const DEFAULT_LONG_GAP_THRESHOLD: f64 = 300.0;
+
+/// Default wait (seconds) after the first entry that counts as a stalled start
+pub const DEFAULT_STALLED_START_THRESHOLD: f64 = 120.0;
+
+/// Default invocation count that counts as high agent activity
+pub const DEFAULT_AGENT_ACTIVITY_THRESHOLD: usize = 10;
+
+/// Default number of consecutive errors examined for an error burst
+pub const DEFAULT_BURST_WINDOW: usize = 3;
+
+/// Default sliding window (seconds) for sustained activity detection
+pub const DEFAULT_ACTIVITY_WINDOW_SECS: f64 = 60.0;
+
+/// Default invocations per window that must be exceeded for sustained activity
+pub const DEFAULT_ACTIVITY_WINDOW_THRESHOLD: usize = 5;
+
+/// Default occurrences of one error message that must be exceeded to be
+/// reported as repeated
+pub const DEFAULT_REPEATED_ERROR_THRESHOLD: usize = 3;
 
 /// Trait for analyzers that can process log sessions
 ///
@@ -29,6 +58,133 @@ pub trait Analyzer {
     fn name(&self) -> &str;
 }
 
+/// Structured field naming the session an entry belongs to
+pub const SESSION_ID_FIELD: &str = "session_id";
+
+/// Session id used for entries without a `session_id` field
+pub const UNTAGGED_SESSION_ID: &str = "aggregate";
+
+/// Split interleaved entries into one session per `session_id` field
+///
+/// Entries without the field are grouped into a single
+/// `UNTAGGED_SESSION_ID` session, so untagged logs yield exactly one
+/// session. Sessions are ordered by id; entries keep their input order.
+///
+/// Demonstrates:
+/// - Taking ownership of a Vec and moving entries into groups
+/// - BTreeMap for deterministic ordering
+pub fn split_into_sessions(entries: Vec<LogEntry>) -> Vec<LogSession> {
+    let mut groups: BTreeMap<String, Vec<LogEntry>> = BTreeMap::new();
+
+    for entry in entries {
+        let id = entry
+            .fields
+            .get(SESSION_ID_FIELD)
+            .cloned()
+            .unwrap_or_else(|| UNTAGGED_SESSION_ID.to_string());
+        groups.entry(id).or_default().push(entry);
+    }
+
+    groups
+        .into_iter()
+        .map(|(id, entries)| LogSession::from_entries(&id, entries))
+        .collect()
+}
+
+/// Default message marking the start of a session for `split_by_markers`
+pub const SESSION_START_MARKER: &str = "=== SESSION START ===";
+
+/// Default message marking the end of a session for `split_by_markers`
+pub const SESSION_END_MARKER: &str = "=== SESSION END ===";
+
+/// Split a flat entry stream into sessions bounded by marker messages
+///
+/// An entry whose message contains `start_pat` begins a new session and
+/// one containing `end_pat` closes the current one; markers belong to the
+/// session they bound. Entries outside any start/end pair (such as those
+/// before the first start marker) form implicit sessions of their own.
+///
+/// Sessions get sequential ids (`session-1`, `session-2`, ...), zero-padded
+/// so they sort in order.
+///
+/// Demonstrates:
+/// - Accumulating groups with std::mem::take
+pub fn split_by_markers(entries: Vec<LogEntry>, start_pat: &str, end_pat: &str) -> Vec<LogSession> {
+    let mut groups: Vec<Vec<LogEntry>> = Vec::new();
+    let mut current = Vec::new();
+
+    for entry in entries {
+        if entry.message.contains(start_pat) && !current.is_empty() {
+            groups.push(std::mem::take(&mut current));
+        }
+
+        let is_end = entry.message.contains(end_pat);
+        current.push(entry);
+
+        if is_end {
+            groups.push(std::mem::take(&mut current));
+        }
+    }
+
+    if !current.is_empty() {
+        groups.push(current);
+    }
+
+    let width = groups.len().to_string().len();
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(idx, entries)| {
+            LogSession::from_entries(&format!("session-{:0width$}", idx + 1), entries)
+        })
+        .collect()
+}
+
+/// Combine sessions into one chronologically ordered session
+///
+/// Entries are stably sorted by timestamp, so entries sharing a timestamp
+/// keep their input order. The merged id joins the input ids with `+`,
+/// and the start/end times are recomputed from the merged entries.
+///
+/// Demonstrates:
+/// - Consuming a Vec of owned values and moving their contents
+/// - Stable sorting by key
+pub fn merge_sessions(sessions: Vec<LogSession>) -> LogSession {
+    let id = sessions
+        .iter()
+        .map(|s| s.id.as_str())
+        .collect::<Vec<_>>()
+        .join("+");
+
+    let mut entries: Vec<LogEntry> = sessions.into_iter().flat_map(|s| s.entries).collect();
+    entries.sort_by_key(|e| e.timestamp);
+
+    LogSession::from_entries(&id, entries)
+}
+
+/// Combine per-session agent statistics into one entry per agent
+///
+/// Stats sharing a name are merged with `AgentStats::merge`; the result is
+/// sorted by agent name.
+///
+/// Demonstrates:
+/// - Consuming nested Vecs and moving their contents
+/// - BTreeMap entry API for ordered aggregation
+pub fn aggregate_agent_stats(stats_per_session: Vec<Vec<AgentStats>>) -> Vec<AgentStats> {
+    let mut by_agent: BTreeMap<String, AgentStats> = BTreeMap::new();
+
+    for stats in stats_per_session.into_iter().flatten() {
+        match by_agent.get_mut(&stats.name) {
+            Some(merged) => merged.merge(&stats),
+            None => {
+                by_agent.insert(stats.name.clone(), stats);
+            }
+        }
+    }
+
+    by_agent.into_values().collect()
+}
+
 /// Analyzer for timing statistics
 ///
 /// Demonstrates:
@@ -56,28 +212,77 @@ impl TimingAnalyzer {
         Some(duration)
     }
 
-    /// Calculate average time between entries
+    /// Calculate gaps between consecutive entries in seconds
     ///
     /// Demonstrates:
     /// - Iterator windows for pairwise operations
-    /// - Fold for aggregation
-    /// - Borrowing
-    fn avg_time_between_entries(entries: &[LogEntry]) -> f64 {
-        if entries.len() < 2 {
-            return 0.0;
-        }
-
+    /// - Collecting into an owned Vec
+    fn gaps_secs(entries: &[LogEntry]) -> Vec<f64> {
         // Use windows to get consecutive pairs
-        let total_ms: i64 = entries
+        entries
             .windows(2)
             .map(|window| {
                 let delta = window[1].timestamp - window[0].timestamp;
-                delta.num_milliseconds()
+                delta.num_milliseconds() as f64 / 1000.0
             })
-            .sum();
+            .collect()
+    }
+
+    /// Calculate average time between entries
+    ///
+    /// Demonstrates:
+    /// - Fold for aggregation
+    /// - Borrowing
+    fn avg_time_between_entries(gaps: &[f64]) -> f64 {
+        if gaps.is_empty() {
+            return 0.0;
+        }
+
+        gaps.iter().sum::<f64>() / gaps.len() as f64
+    }
+
+    /// Calculate population standard deviation of gaps
+    fn stddev(gaps: &[f64], mean: f64) -> f64 {
+        if gaps.is_empty() {
+            return 0.0;
+        }
+
+        let variance = gaps.iter().map(|gap| (gap - mean).powi(2)).sum::<f64>() / gaps.len() as f64;
+        variance.sqrt()
+    }
+
+    /// Average duration per entry type over entries that record one
+    ///
+    /// Demonstrates:
+    /// - Accumulating (sum, count) pairs in a HashMap
+    fn per_type_avg_duration(entries: &[LogEntry]) -> HashMap<EntryType, f64> {
+        let mut totals: HashMap<EntryType, (u64, usize)> = HashMap::new();
+
+        for entry in entries {
+            if let Some(ms) = entry.duration_ms {
+                let (sum, count) = totals.entry(entry.entry_type).or_insert((0, 0));
+                *sum += ms;
+                *count += 1;
+            }
+        }
+
+        totals
+            .into_iter()
+            .map(|(entry_type, (sum, count))| (entry_type, sum as f64 / count as f64))
+            .collect()
+    }
+
+    /// Nearest-rank percentile over sorted gaps
+    ///
+    /// Demonstrates:
+    /// - Slice indexing with bounds clamping
+    fn percentile(sorted_gaps: &[f64], pct: f64) -> f64 {
+        if sorted_gaps.is_empty() {
+            return 0.0;
+        }
 
-        let count = entries.len() - 1;
-        (total_ms as f64 / 1000.0) / count as f64
+        let rank = (pct / 100.0 * sorted_gaps.len() as f64).ceil() as usize;
+        sorted_gaps[rank.clamp(1, sorted_gaps.len()) - 1]
     }
 }
 
@@ -94,13 +299,21 @@ impl Analyzer for TimingAnalyzer {
         let total_duration_secs =
             Self::calculate_duration(&session.entries).unwrap_or(0.0);
 
-        let avg_time_between_entries =
-            Self::avg_time_between_entries(&session.entries);
+        let mut gaps = Self::gaps_secs(&session.entries);
+        let avg_time_between_entries = Self::avg_time_between_entries(&gaps);
+        let stddev_gap_secs = Self::stddev(&gaps, avg_time_between_entries);
+
+        gaps.sort_by(|a, b| a.total_cmp(b));
 
         Ok(TimingStats {
             total_duration_secs,
             entry_count: session.entries.len(),
             avg_time_between_entries,
+            p50_gap_secs: Self::percentile(&gaps, 50.0),
+            p95_gap_secs: Self::percentile(&gaps, 95.0),
+            p99_gap_secs: Self::percentile(&gaps, 99.0),
+            stddev_gap_secs,
+            per_type_avg_duration: Self::per_type_avg_duration(&session.entries),
         })
     }
 
@@ -147,6 +360,8 @@ impl AgentAnalyzer {
                 .entry(agent_name.clone())
                 .or_insert_with(|| AgentStats::new(agent_name.clone()));
 
+            stats.record_seen(entry.timestamp);
+
             // Add duration if available
             if let Some(duration_ms) = entry.duration_ms {
                 stats.add_duration(duration_ms);
@@ -206,8 +421,22 @@ impl Analyzer for AgentAnalyzer {
     }
 }
 
+/// Normalize a message for grouping repeats
+///
+/// Trims whitespace and, if requested, trailing numbers such as attempt
+/// counters so `"retry failed 1"` and `"retry failed 2"` compare equal.
+pub fn normalize_message(message: &str, strip_trailing_numbers: bool) -> &str {
+    let message = message.trim();
+
+    if strip_trailing_numbers {
+        message.trim_end_matches(|c: char| c.is_ascii_digit() || c.is_whitespace())
+    } else {
+        message
+    }
+}
+
 /// Pattern types detected in logs
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LogPattern {
     /// Rapid error sequence (multiple errors in short time)
     ErrorBurst { count: usize, duration_secs: f64 },
@@ -215,19 +444,65 @@ pub enum LogPattern {
     /// Long gap between entries
     LongGap { duration_secs: f64 },
 
+    /// Nothing logged for a long time after the first entry, e.g. a
+    /// session that hung right after starting
+    StalledStart { after_secs: f64 },
+
     /// High agent activity
     AgentActivity { agent: String, count: usize },
 
+    /// Many invocations of one agent packed into a short time window
+    SustainedActivity {
+        agent: String,
+        count: usize,
+        window_secs: f64,
+    },
+
+    /// The same error message logged many times, e.g. by a flaky retry loop
+    RepeatedError { message: String, count: usize },
+
+    /// Timestamps going backwards between consecutive entries
+    ClockSkew { prev: DateTime<Utc>, next: DateTime<Utc> },
+
     /// Session without agent usage
     NoAgentActivity,
 }
 
+impl LogPattern {
+    /// How urgently the pattern needs attention; higher is more severe
+    ///
+    /// Error patterns rank above timing problems, which rank above
+    /// informational activity patterns.
+    pub fn severity(&self) -> u8 {
+        match self {
+            LogPattern::ErrorBurst { .. } => 7,
+            LogPattern::RepeatedError { .. } => 6,
+            LogPattern::ClockSkew { .. } => 5,
+            LogPattern::StalledStart { .. } => 4,
+            LogPattern::LongGap { .. } => 3,
+            LogPattern::SustainedActivity { .. } => 2,
+            LogPattern::AgentActivity { .. } => 1,
+            LogPattern::NoAgentActivity => 0,
+        }
+    }
+}
+
 /// Pattern detection results
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatternAnalysis {
     pub patterns: Vec<LogPattern>,
 }
 
+impl PatternAnalysis {
+    /// Number of out-of-order timestamps, which make gap statistics unreliable
+    pub fn clock_skew_count(&self) -> usize {
+        self.patterns
+            .iter()
+            .filter(|p| matches!(p, LogPattern::ClockSkew { .. }))
+            .count()
+    }
+}
+
 /// Analyzer for detecting patterns in logs
 ///
 /// Demonstrates:
@@ -237,21 +512,39 @@ pub struct PatternAnalyzer {
     /// Threshold for error burst detection (errors per second)
     error_burst_threshold: f64,
 
+    /// Consecutive errors examined per burst window (at least 2)
+    burst_window: usize,
+
     /// Threshold for long gap detection (seconds)
     long_gap_threshold: f64,
 
+    /// Wait after the first entry that counts as a stalled start (seconds)
+    stalled_start_threshold: f64,
+
     /// Threshold for agent activity (invocation count)
     agent_activity_threshold: usize,
+
+    /// Sliding window for sustained activity detection (seconds)
+    activity_window_secs: f64,
+
+    /// Invocations per window that must be exceeded for sustained activity
+    activity_window_threshold: usize,
+
+    /// Occurrences of one error message that must be exceeded
+    repeated_error_threshold: usize,
+
+    /// Strip trailing numbers (attempt counters, ids) before comparing errors
+    strip_trailing_numbers: bool,
 }
 
 impl PatternAnalyzer {
     /// Create a new pattern analyzer with default thresholds
     pub fn new() -> Self {
-        Self {
-            error_burst_threshold: 5.0,
-            long_gap_threshold: 300.0,
-            agent_activity_threshold: 10,
-        }
+        Self::with_thresholds(
+            DEFAULT_ERROR_BURST_THRESHOLD,
+            DEFAULT_LONG_GAP_THRESHOLD,
+            DEFAULT_AGENT_ACTIVITY_THRESHOLD,
+        )
     }
 
     /// Create with custom thresholds
@@ -262,11 +555,48 @@ impl PatternAnalyzer {
     ) -> Self {
         Self {
             error_burst_threshold,
+            burst_window: DEFAULT_BURST_WINDOW,
             long_gap_threshold,
+            stalled_start_threshold: DEFAULT_STALLED_START_THRESHOLD,
             agent_activity_threshold,
+            activity_window_secs: DEFAULT_ACTIVITY_WINDOW_SECS,
+            activity_window_threshold: DEFAULT_ACTIVITY_WINDOW_THRESHOLD,
+            repeated_error_threshold: DEFAULT_REPEATED_ERROR_THRESHOLD,
+            strip_trailing_numbers: false,
         }
     }
 
+    /// Set how many consecutive errors make up a burst window
+    ///
+    /// Sizes below 2 have no meaningful rate and are raised to 2.
+    pub fn with_burst_window(mut self, burst_window: usize) -> Self {
+        self.burst_window = burst_window.max(2);
+        self
+    }
+
+    /// Set how long (seconds) a session may go quiet after its first entry
+    /// before it counts as a stalled start
+    pub fn with_stalled_start(mut self, threshold_secs: f64) -> Self {
+        self.stalled_start_threshold = threshold_secs;
+        self
+    }
+
+    /// Set the sliding window and per-window invocation threshold used for
+    /// sustained activity detection
+    pub fn with_activity_window(mut self, window_secs: f64, threshold: usize) -> Self {
+        self.activity_window_secs = window_secs;
+        self.activity_window_threshold = threshold;
+        self
+    }
+
+    /// Set the repeated error threshold, and whether trailing numbers are
+    /// ignored so `"retry failed 1"` and `"retry failed 2"` group together
+    pub fn with_repeated_errors(mut self, threshold: usize, strip_trailing_numbers: bool) -> Self {
+        self.repeated_error_threshold = threshold;
+        self.strip_trailing_numbers = strip_trailing_numbers;
+        self
+    }
+
     /// Detect error bursts
     ///
     /// Demonstrates:
@@ -275,8 +605,9 @@ impl PatternAnalyzer {
     /// - Pattern matching
     fn detect_error_bursts(&self, entries: &[LogEntry]) -> Vec<LogPattern> {
         let mut patterns = Vec::new();
+        let n = self.burst_window;
 
-        if entries.len() < 2 {
+        if entries.len() < n {
             return patterns;
         }
 
@@ -284,30 +615,53 @@ impl PatternAnalyzer {
         let error_entries: Vec<_> = entries
             .iter()
             .enumerate()
-            .filter(|(_, e)| matches!(e.entry_type, crate::types::EntryType::Error))
+            .filter(|(_, e)| matches!(e.entry_type, EntryType::Error))
             .collect();
 
-        if error_entries.len() < 2 {
+        if error_entries.len() < n {
             return patterns;
         }
 
-        // Check for bursts (3+ errors within short time)
-        for window in error_entries.windows(3) {
+        // Check for bursts (n errors within short time), coalescing
+        // overlapping qualifying windows into a single run
+        let mut run: Option<(usize, usize)> = None;
+
+        for (i, window) in error_entries.windows(n).enumerate() {
             let first_time = window.first().unwrap().1.timestamp;
             let last_time = window.last().unwrap().1.timestamp;
             let duration_secs = (last_time - first_time).num_milliseconds() as f64 / 1000.0;
 
-            if duration_secs > 0.0 && (3.0 / duration_secs) >= self.error_burst_threshold {
-                patterns.push(LogPattern::ErrorBurst {
-                    count: 3,
-                    duration_secs,
-                });
+            if duration_secs > 0.0 && (n as f64 / duration_secs) >= self.error_burst_threshold {
+                let end = i + n - 1;
+                run = match run {
+                    Some((start, prev_end)) if i <= prev_end => Some((start, end)),
+                    Some(finished) => {
+                        patterns.push(Self::error_burst(&error_entries, finished));
+                        Some((i, end))
+                    }
+                    None => Some((i, end)),
+                };
             }
         }
 
+        if let Some(finished) = run {
+            patterns.push(Self::error_burst(&error_entries, finished));
+        }
+
         patterns
     }
 
+    /// Build an `ErrorBurst` spanning error entries `start..=end`
+    fn error_burst(error_entries: &[(usize, &LogEntry)], (start, end): (usize, usize)) -> LogPattern {
+        let first_time = error_entries[start].1.timestamp;
+        let last_time = error_entries[end].1.timestamp;
+
+        LogPattern::ErrorBurst {
+            count: end - start + 1,
+            duration_secs: (last_time - first_time).num_milliseconds() as f64 / 1000.0,
+        }
+    }
+
     /// Detect long gaps between entries
     ///
     /// Demonstrates:
@@ -329,6 +683,23 @@ impl PatternAnalyzer {
             .collect()
     }
 
+    /// Detect a session that went quiet right after its first entry
+    ///
+    /// Unlike long gaps, only the wait after the first entry counts. A
+    /// session with a single entry is measured against its end time
+    /// instead, so a run known to have lasted much longer is still caught.
+    fn detect_stalled_start(&self, session: &LogSession) -> Option<LogPattern> {
+        let first = session.entries.first()?.timestamp;
+        let next = match session.entries.get(1) {
+            Some(entry) => entry.timestamp,
+            None => session.end_time?,
+        };
+        let after_secs = (next - first).num_milliseconds() as f64 / 1000.0;
+
+        (after_secs > self.stalled_start_threshold)
+            .then_some(LogPattern::StalledStart { after_secs })
+    }
+
     /// Detect high agent activity
     ///
     /// Demonstrates:
@@ -349,6 +720,92 @@ impl PatternAnalyzer {
             .collect()
     }
 
+    /// Detect agents invoked more than the threshold within one window
+    ///
+    /// Reports at most one pattern per agent, carrying the busiest
+    /// window's invocation count.
+    ///
+    /// Demonstrates:
+    /// - Two-pointer sliding window over sorted timestamps
+    /// - BTreeMap for deterministic output order
+    fn detect_sustained_activity(&self, entries: &[LogEntry]) -> Vec<LogPattern> {
+        let mut by_agent: BTreeMap<&str, Vec<DateTime<Utc>>> = BTreeMap::new();
+
+        for entry in entries {
+            if let Some(agent) = entry.agent_name.as_deref() {
+                by_agent.entry(agent).or_default().push(entry.timestamp);
+            }
+        }
+
+        let window_ms = (self.activity_window_secs * 1000.0) as i64;
+        let mut patterns = Vec::new();
+
+        for (agent, mut times) in by_agent {
+            times.sort();
+
+            let mut start = 0;
+            let mut busiest = 0;
+
+            for end in 0..times.len() {
+                while (times[end] - times[start]).num_milliseconds() > window_ms {
+                    start += 1;
+                }
+                busiest = busiest.max(end - start + 1);
+            }
+
+            if busiest > self.activity_window_threshold {
+                patterns.push(LogPattern::SustainedActivity {
+                    agent: agent.to_string(),
+                    count: busiest,
+                    window_secs: self.activity_window_secs,
+                });
+            }
+        }
+
+        patterns
+    }
+
+    /// Detect error messages repeated more than the threshold
+    ///
+    /// Unlike error bursts this ignores timing, so retries spread across
+    /// a whole session are still caught.
+    ///
+    /// Demonstrates:
+    /// - Grouping by a normalized key
+    /// - BTreeMap for deterministic output order
+    fn detect_repeated_errors(&self, entries: &[LogEntry]) -> Vec<LogPattern> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+
+        for entry in entries.iter().filter(|e| matches!(e.entry_type, EntryType::Error)) {
+            let message = normalize_message(&entry.message, self.strip_trailing_numbers);
+            *counts.entry(message).or_insert(0) += 1;
+        }
+
+        counts
+            .into_iter()
+            .filter(|(_, count)| *count > self.repeated_error_threshold)
+            .map(|(message, count)| LogPattern::RepeatedError {
+                message: message.to_string(),
+                count,
+            })
+            .collect()
+    }
+
+    /// Detect consecutive entries whose timestamps go backwards
+    ///
+    /// Merged or rotated logs can interleave out of order; every
+    /// backwards step is reported.
+    fn detect_clock_skew(&self, entries: &[LogEntry]) -> Vec<LogPattern> {
+        entries
+            .windows(2)
+            .filter(|window| window[1].timestamp < window[0].timestamp)
+            .map(|window| LogPattern::ClockSkew {
+                prev: window[0].timestamp,
+                next: window[1].timestamp,
+            })
+            .collect()
+    }
+
     /// Check if session has no agent activity
     fn detect_no_agent_activity(&self, entries: &[LogEntry]) -> Option<LogPattern> {
         let has_agents = entries.iter().any(|e| e.agent_name.is_some());
@@ -376,12 +833,19 @@ impl Analyzer for PatternAnalyzer {
         // Detect various patterns
         patterns.extend(self.detect_error_bursts(&session.entries));
         patterns.extend(self.detect_long_gaps(&session.entries));
+        patterns.extend(self.detect_stalled_start(session));
         patterns.extend(self.detect_agent_activity(&session.entries));
+        patterns.extend(self.detect_sustained_activity(&session.entries));
+        patterns.extend(self.detect_repeated_errors(&session.entries));
+        patterns.extend(self.detect_clock_skew(&session.entries));
 
         if let Some(pattern) = self.detect_no_agent_activity(&session.entries) {
             patterns.push(pattern);
         }
 
+        // Most severe first; the stable sort keeps detection order within a level
+        patterns.sort_by_key(|p| std::cmp::Reverse(p.severity()));
+
         Ok(PatternAnalysis { patterns })
     }
 
@@ -390,279 +854,2541 @@ impl Analyzer for PatternAnalyzer {
     }
 }
 
-/// Composite analyzer that runs multiple analyzers
+/// Histogram of agent durations
+///
+/// Each bucket is `(lower_ms, upper_ms, count)`; the lower bound is
+/// inclusive and the upper bound exclusive, except for the final bucket
+/// which is open-ended (`upper_ms == u64::MAX`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DurationHistogram {
+    pub buckets: Vec<(u64, u64, usize)>,
+}
+
+/// Analyzer that buckets entry durations into a histogram
 ///
 /// Demonstrates:
-/// - Trait objects (Box<dyn Analyzer>)
-/// - Polymorphism
-pub struct CompositeAnalyzer {
-    analyzers: Vec<Box<dyn Analyzer<Output = String>>>,
+/// - Configurable analyzer owning a Vec
+/// - Binary search over sorted boundaries
+pub struct DurationAnalyzer {
+    /// Sorted, de-duplicated bucket boundaries in milliseconds
+    boundaries: Vec<u64>,
 }
 
-impl CompositeAnalyzer {
+impl DurationAnalyzer {
+    /// Create a duration analyzer with exponential buckets
+    /// (0-10, 10-100, 100-1000, ... ms)
     pub fn new() -> Self {
-        Self {
-            analyzers: Vec::new(),
-        }
+        Self::with_buckets(vec![0, 10, 100, 1_000, 10_000, 100_000])
     }
 
-    pub fn add_analyzer<A>(&mut self, analyzer: A)
-    where
-        A: Analyzer<Output = String> + 'static,
-    {
-        self.analyzers.push(Box::new(analyzer));
+    /// Create with custom bucket boundaries (in milliseconds)
+    ///
+    /// Durations below the first boundary are not counted.
+    pub fn with_buckets(mut boundaries: Vec<u64>) -> Self {
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        Self { boundaries }
     }
 
-    pub fn run_all(&self, session: &LogSession) -> Vec<(String, ParseResult<String>)> {
-        self.analyzers
-            .iter()
-            .map(|analyzer| {
-                let name = analyzer.name().to_string();
-                let result = analyzer.analyze(session);
-                (name, result)
-            })
-            .collect()
+    /// Find the bucket index for a duration, if it falls in any bucket
+    fn bucket_index(&self, duration_ms: u64) -> Option<usize> {
+        match self.boundaries.binary_search(&duration_ms) {
+            Ok(idx) => Some(idx),
+            Err(0) => None,
+            Err(idx) => Some(idx - 1),
+        }
     }
 }
 
-impl Default for CompositeAnalyzer {
+impl Default for DurationAnalyzer {
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::types::EntryType;
-    use chrono::{Duration, Utc};
-
-    fn create_test_session() -> LogSession {
-        let now = Utc::now();
+impl Analyzer for DurationAnalyzer {
+    type Output = DurationHistogram;
 
-        let entries = vec![
-            LogEntry {
-                timestamp: now,
-                entry_type: EntryType::Info,
-                message: "Start".to_string(),
-                agent_name: None,
-                duration_ms: None,
-            },
-            LogEntry {
-                timestamp: now + Duration::seconds(10),
-                entry_type: EntryType::AgentInvocation,
-                message: "Agent called".to_string(),
-                agent_name: Some("test-agent".to_string()),
-                duration_ms: Some(100),
-            },
-            LogEntry {
-                timestamp: now + Duration::seconds(20),
-                entry_type: EntryType::AgentInvocation,
-                message: "Agent called again".to_string(),
-                agent_name: Some("test-agent".to_string()),
-                duration_ms: Some(200),
-            },
-            LogEntry {
-                timestamp: now + Duration::seconds(30),
-                entry_type: EntryType::Info,
-                message: "End".to_string(),
-                agent_name: None,
-                duration_ms: None,
-            },
-        ];
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        let mut counts = vec![0usize; self.boundaries.len()];
 
-        LogSession {
-            id: "test-session".to_string(),
-            entries,
-            start_time: now,
-            end_time: Some(now + Duration::seconds(30)),
+        for duration_ms in session.entries.iter().filter_map(|e| e.duration_ms) {
+            if let Some(idx) = self.bucket_index(duration_ms) {
+                counts[idx] += 1;
+            }
         }
-    }
 
-    #[test]
-    fn test_timing_analyzer() {
-        let analyzer = TimingAnalyzer::new();
-        let session = create_test_session();
+        let buckets = self
+            .boundaries
+            .iter()
+            .enumerate()
+            .map(|(idx, &lower)| {
+                let upper = self.boundaries.get(idx + 1).copied().unwrap_or(u64::MAX);
+                (lower, upper, counts[idx])
+            })
+            .collect();
 
-        let result = analyzer.analyze(&session);
-        assert!(result.is_ok());
+        Ok(DurationHistogram { buckets })
+    }
 
-        let stats = result.unwrap();
-        assert_eq!(stats.entry_count, 4);
+    fn name(&self) -> &str {
+        "DurationAnalyzer"
+    }
+}
+
+/// Error and total entry counts for a one-minute window
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorRateBucket {
+    /// Start of the minute (seconds truncated)
+    pub minute_start: DateTime<Utc>,
+
+    /// Number of `EntryType::Error` entries in this minute
+    pub error_count: usize,
+
+    /// Number of entries of any type in this minute
+    pub total_count: usize,
+}
+
+/// Analyzer producing a per-minute error time series
+///
+/// Demonstrates:
+/// - BTreeMap for chronologically ordered aggregation
+/// - Timestamp truncation with DurationRound
+pub struct ErrorRateAnalyzer;
+
+impl ErrorRateAnalyzer {
+    /// Create a new error rate analyzer
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ErrorRateAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for ErrorRateAnalyzer {
+    type Output = Vec<ErrorRateBucket>;
+
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        let mut buckets: BTreeMap<DateTime<Utc>, (usize, usize)> = BTreeMap::new();
+
+        for entry in &session.entries {
+            let minute_start = entry
+                .timestamp
+                .duration_trunc(chrono::Duration::minutes(1))
+                .unwrap_or(entry.timestamp);
+
+            let (errors, total) = buckets.entry(minute_start).or_insert((0, 0));
+            if entry.entry_type == EntryType::Error {
+                *errors += 1;
+            }
+            *total += 1;
+        }
+
+        Ok(buckets
+            .into_iter()
+            .map(|(minute_start, (error_count, total_count))| ErrorRateBucket {
+                minute_start,
+                error_count,
+                total_count,
+            })
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "ErrorRateAnalyzer"
+    }
+}
+
+/// Centered simple moving average of `error_count` across buckets
+///
+/// Each output value averages the `window` buckets centered on the
+/// corresponding input bucket (for an even window the extra bucket is taken
+/// from the left). Near the edges the window shrinks to the buckets that
+/// exist, so the output has the same length as the input. A window of 0 is
+/// treated as 1, which returns the raw counts.
+///
+/// Demonstrates:
+/// - Slice windows computed with saturating index arithmetic
+pub fn smooth_error_rate(buckets: &[ErrorRateBucket], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    let before = window / 2;
+    let after = (window - 1) / 2;
+
+    (0..buckets.len())
+        .map(|i| {
+            let start = i.saturating_sub(before);
+            let end = (i + after + 1).min(buckets.len());
+            let span = &buckets[start..end];
+            let sum: usize = span.iter().map(|b| b.error_count).sum();
+            sum as f64 / span.len() as f64
+        })
+        .collect()
+}
+
+/// Estimated token cost for a single agent
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentCost {
+    pub agent: String,
+
+    /// Sum of input and output tokens
+    pub total_tokens: u64,
+
+    /// Estimated cost in US dollars
+    pub estimated_cost_usd: f64,
+}
+
+/// Analyzer estimating per-agent cost from `tokens_in`/`tokens_out` fields
+///
+/// Demonstrates:
+/// - Configurable rates with a sensible default
+/// - Parsing structured fields with Option chaining
+pub struct CostAnalyzer {
+    /// Cost per input token in USD
+    input_rate: f64,
+
+    /// Cost per output token in USD
+    output_rate: f64,
+}
+
+impl CostAnalyzer {
+    /// Create a cost analyzer with default rates
+    /// ($3 per million input tokens, $15 per million output tokens)
+    pub fn new() -> Self {
+        Self::with_rates(3.0 / 1_000_000.0, 15.0 / 1_000_000.0)
+    }
+
+    /// Create with custom per-token rates in USD
+    pub fn with_rates(input_rate: f64, output_rate: f64) -> Self {
+        Self {
+            input_rate,
+            output_rate,
+        }
+    }
+
+    /// Read a numeric token field, treating missing or invalid values as zero
+    fn token_field(entry: &LogEntry, key: &str) -> u64 {
+        entry
+            .fields
+            .get(key)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0)
+    }
+}
+
+impl Default for CostAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for CostAnalyzer {
+    type Output = Vec<AgentCost>;
+
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        // (tokens_in, tokens_out) per agent
+        let mut totals: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+
+        for entry in &session.entries {
+            let Some(agent) = entry.agent_name.as_deref() else {
+                continue;
+            };
+
+            let (tokens_in, tokens_out) = totals.entry(agent).or_insert((0, 0));
+            *tokens_in += Self::token_field(entry, "tokens_in");
+            *tokens_out += Self::token_field(entry, "tokens_out");
+        }
+
+        Ok(totals
+            .into_iter()
+            .map(|(agent, (tokens_in, tokens_out))| AgentCost {
+                agent: agent.to_string(),
+                total_tokens: tokens_in + tokens_out,
+                estimated_cost_usd: tokens_in as f64 * self.input_rate
+                    + tokens_out as f64 * self.output_rate,
+            })
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "CostAnalyzer"
+    }
+}
+
+/// A single decision record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionSummary {
+    pub timestamp: DateTime<Utc>,
+    pub message: String,
+}
+
+/// Analyzer collecting `EntryType::Decision` entries in chronological order
+///
+/// Demonstrates:
+/// - Iterator filter/map into owned summaries
+/// - Stable sorting by key
+pub struct DecisionAnalyzer;
+
+impl DecisionAnalyzer {
+    /// Create a new decision analyzer
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for DecisionAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for DecisionAnalyzer {
+    type Output = Vec<DecisionSummary>;
+
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        let mut decisions: Vec<DecisionSummary> = session
+            .entries
+            .iter()
+            .filter(|e| e.entry_type == EntryType::Decision)
+            .map(|e| DecisionSummary {
+                timestamp: e.timestamp,
+                message: e.message.clone(),
+            })
+            .collect();
+
+        decisions.sort_by_key(|d| d.timestamp);
+        Ok(decisions)
+    }
+
+    fn name(&self) -> &str {
+        "DecisionAnalyzer"
+    }
+}
+
+/// A distinct warning message and how often it occurred
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WarningSummary {
+    pub message: String,
+    pub count: usize,
+}
+
+/// Analyzer grouping `EntryType::Warning` entries by normalized message
+///
+/// Output is sorted by count, most frequent first, then by message.
+///
+/// Demonstrates:
+/// - Sharing normalization with PatternAnalyzer's repeated errors
+/// - Sorting by a descending key with an ascending tie-breaker
+pub struct WarningAnalyzer {
+    strip_trailing_numbers: bool,
+}
+
+impl WarningAnalyzer {
+    /// Create a warning analyzer that groups exact (trimmed) messages
+    pub fn new() -> Self {
+        Self {
+            strip_trailing_numbers: false,
+        }
+    }
+
+    /// Also ignore trailing numbers when grouping
+    pub fn with_strip_trailing_numbers(strip_trailing_numbers: bool) -> Self {
+        Self {
+            strip_trailing_numbers,
+        }
+    }
+}
+
+impl Default for WarningAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for WarningAnalyzer {
+    type Output = Vec<WarningSummary>;
+
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+
+        for entry in session.entries.iter().filter(|e| e.entry_type == EntryType::Warning) {
+            let message = normalize_message(&entry.message, self.strip_trailing_numbers);
+            *counts.entry(message).or_insert(0) += 1;
+        }
+
+        let mut warnings: Vec<WarningSummary> = counts
+            .into_iter()
+            .map(|(message, count)| WarningSummary {
+                message: message.to_string(),
+                count,
+            })
+            .collect();
+
+        warnings.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.message.cmp(&b.message)));
+        Ok(warnings)
+    }
+
+    fn name(&self) -> &str {
+        "WarningAnalyzer"
+    }
+}
+
+/// Default z-score above which an invocation counts as an outlier
+pub const DEFAULT_OUTLIER_Z_SCORE: f64 = 3.0;
+
+/// An agent invocation whose duration is a statistical outlier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DurationOutlier {
+    pub agent: String,
+    pub timestamp: DateTime<Utc>,
+    pub duration_ms: u64,
+
+    /// Standard deviations above the agent's mean duration
+    pub z_score: f64,
+}
+
+/// Analyzer flagging unusually slow agent invocations
+///
+/// Durations are compared against the mean and (population) standard
+/// deviation of the same agent's durations. Agents with fewer than
+/// `MIN_SAMPLES` timed invocations are skipped.
+///
+/// Demonstrates:
+/// - Grouping borrowed entries per key
+/// - Two-pass statistics (mean, then variance)
+pub struct OutlierAnalyzer {
+    /// Z-score an invocation must exceed to be flagged
+    z_threshold: f64,
+}
+
+impl OutlierAnalyzer {
+    /// Minimum timed invocations per agent before outliers are reported
+    pub const MIN_SAMPLES: usize = 3;
+
+    /// Create an outlier analyzer with the default z-score threshold
+    pub fn new() -> Self {
+        Self::with_threshold(DEFAULT_OUTLIER_Z_SCORE)
+    }
+
+    /// Create with a custom z-score threshold
+    pub fn with_threshold(z_threshold: f64) -> Self {
+        Self { z_threshold }
+    }
+}
+
+impl Default for OutlierAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for OutlierAnalyzer {
+    type Output = Vec<DurationOutlier>;
+
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        let mut by_agent: BTreeMap<&str, Vec<&LogEntry>> = BTreeMap::new();
+
+        for entry in &session.entries {
+            if let (Some(agent), Some(_)) = (entry.agent_name.as_deref(), entry.duration_ms) {
+                by_agent.entry(agent).or_default().push(entry);
+            }
+        }
+
+        let mut outliers = Vec::new();
+
+        for (agent, entries) in by_agent {
+            if entries.len() < Self::MIN_SAMPLES {
+                continue;
+            }
+
+            let durations: Vec<f64> = entries
+                .iter()
+                .filter_map(|e| e.duration_ms)
+                .map(|ms| ms as f64)
+                .collect();
+            let n = durations.len() as f64;
+            let mean = durations.iter().sum::<f64>() / n;
+            let variance = durations.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / n;
+            let stddev = variance.sqrt();
+
+            // Identical durations have no outliers
+            if stddev == 0.0 {
+                continue;
+            }
+
+            for entry in entries {
+                let duration_ms = entry.duration_ms.unwrap_or_default();
+                let z_score = (duration_ms as f64 - mean) / stddev;
+
+                if z_score > self.z_threshold {
+                    outliers.push(DurationOutlier {
+                        agent: agent.to_string(),
+                        timestamp: entry.timestamp,
+                        duration_ms,
+                        z_score,
+                    });
+                }
+            }
+        }
+
+        outliers.sort_by_key(|o| o.timestamp);
+        Ok(outliers)
+    }
+
+    fn name(&self) -> &str {
+        "OutlierAnalyzer"
+    }
+}
+
+/// Session health summary on a 0-100 scale
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthScore {
+    pub score: f64,
+    pub error_count: usize,
+    pub warning_count: usize,
+}
+
+/// Analyzer scoring session health by penalizing errors and warnings
+///
+/// The score starts at 100, loses `error_penalty` per `Error` entry and
+/// `warning_penalty` per `Warning` entry, and is clamped to `[0, 100]`.
+///
+/// Demonstrates:
+/// - Configurable weights with a sensible default
+/// - Folding counts in a single pass
+pub struct HealthAnalyzer {
+    /// Points subtracted per error entry
+    error_penalty: f64,
+
+    /// Points subtracted per warning entry
+    warning_penalty: f64,
+}
+
+impl HealthAnalyzer {
+    /// Maximum (perfectly healthy) score
+    pub const MAX_SCORE: f64 = 100.0;
+
+    /// Create a health analyzer with default penalties
+    /// (10 points per error, 2 points per warning)
+    pub fn new() -> Self {
+        Self::with_penalties(10.0, 2.0)
+    }
+
+    /// Create with custom per-entry penalties
+    pub fn with_penalties(error_penalty: f64, warning_penalty: f64) -> Self {
+        Self {
+            error_penalty,
+            warning_penalty,
+        }
+    }
+}
+
+impl Default for HealthAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for HealthAnalyzer {
+    type Output = HealthScore;
+
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        let (error_count, warning_count) =
+            session
+                .entries
+                .iter()
+                .fold((0, 0), |(errors, warnings), entry| match entry.entry_type {
+                    EntryType::Error => (errors + 1, warnings),
+                    EntryType::Warning => (errors, warnings + 1),
+                    _ => (errors, warnings),
+                });
+
+        let penalty =
+            error_count as f64 * self.error_penalty + warning_count as f64 * self.warning_penalty;
+
+        Ok(HealthScore {
+            score: (Self::MAX_SCORE - penalty).clamp(0.0, Self::MAX_SCORE),
+            error_count,
+            warning_count,
+        })
+    }
+
+    fn name(&self) -> &str {
+        "HealthAnalyzer"
+    }
+}
+
+/// Agent usage and errors for one calendar day (UTC)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyTrend {
+    pub date: NaiveDate,
+
+    /// Entries attributed to an agent
+    pub total_invocations: usize,
+
+    /// Distinct agent names seen that day
+    pub unique_agents: usize,
+
+    pub error_count: usize,
+}
+
+/// Analyzer grouping activity by UTC calendar date
+///
+/// Only days with at least one entry are reported, sorted ascending.
+///
+/// Demonstrates:
+/// - BTreeMap keyed by NaiveDate for chronological output
+/// - HashSet for distinct counts
+pub struct TrendAnalyzer;
+
+impl TrendAnalyzer {
+    /// Create a new trend analyzer
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Compute daily trends across several sessions
+    pub fn analyze_sessions(&self, sessions: &[LogSession]) -> Vec<DailyTrend> {
+        Self::daily_trends(sessions.iter().flat_map(|s| s.entries.iter()))
+    }
+
+    fn daily_trends<'a>(entries: impl Iterator<Item = &'a LogEntry>) -> Vec<DailyTrend> {
+        // (invocations, agents, errors) per day
+        let mut days: BTreeMap<NaiveDate, (usize, HashSet<&str>, usize)> = BTreeMap::new();
+
+        for entry in entries {
+            let (invocations, agents, errors) = days.entry(entry.timestamp.date_naive()).or_default();
+
+            if let Some(agent) = entry.agent_name.as_deref() {
+                *invocations += 1;
+                agents.insert(agent);
+            }
+            if entry.entry_type == EntryType::Error {
+                *errors += 1;
+            }
+        }
+
+        days.into_iter()
+            .map(|(date, (total_invocations, agents, error_count))| DailyTrend {
+                date,
+                total_invocations,
+                unique_agents: agents.len(),
+                error_count,
+            })
+            .collect()
+    }
+}
+
+impl Default for TrendAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for TrendAnalyzer {
+    type Output = Vec<DailyTrend>;
+
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        Ok(Self::daily_trends(session.entries.iter()))
+    }
+
+    fn name(&self) -> &str {
+        "TrendAnalyzer"
+    }
+}
+
+/// Entry counts by UTC weekday (rows, Monday first) and hour (columns)
+pub type ActivityHeatmap = [[usize; 24]; 7];
+
+/// Analyzer counting entries per weekday and hour of day (UTC)
+///
+/// Demonstrates:
+/// - Fixed-size 2D arrays as a zero-allocation matrix
+/// - chrono's Datelike/Timelike accessors
+pub struct ActivityHeatmapAnalyzer;
+
+impl ActivityHeatmapAnalyzer {
+    /// Create a new heatmap analyzer
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ActivityHeatmapAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for ActivityHeatmapAnalyzer {
+    type Output = ActivityHeatmap;
+
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        let mut heatmap = [[0; 24]; 7];
+
+        for entry in &session.entries {
+            let day = entry.timestamp.weekday().num_days_from_monday() as usize;
+            heatmap[day][entry.timestamp.hour() as usize] += 1;
+        }
+
+        Ok(heatmap)
+    }
+
+    fn name(&self) -> &str {
+        "ActivityHeatmapAnalyzer"
+    }
+}
+
+/// Counts of consecutive `(previous, next)` entry-type pairs
+pub type TransitionCounts = HashMap<(EntryType, EntryType), usize>;
+
+/// Analyzer counting which entry types follow which
+///
+/// Demonstrates:
+/// - Iterator windows over consecutive pairs
+/// - Tuple keys in a HashMap
+pub struct TransitionAnalyzer;
+
+impl TransitionAnalyzer {
+    /// Create a new transition analyzer
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TransitionAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for TransitionAnalyzer {
+    type Output = TransitionCounts;
+
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        let mut transitions = TransitionCounts::new();
+
+        for pair in session.entries.windows(2) {
+            *transitions
+                .entry((pair[0].entry_type, pair[1].entry_type))
+                .or_insert(0) += 1;
+        }
+
+        Ok(transitions)
+    }
+
+    fn name(&self) -> &str {
+        "TransitionAnalyzer"
+    }
+}
+
+/// Default window (seconds) within which two agent invocations co-occur
+pub const DEFAULT_CO_OCCURRENCE_WINDOW_SECS: f64 = 5.0;
+
+/// Two distinct agents and how often they were invoked close together
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentPair {
+    /// Alphabetically first agent of the pair
+    pub a: String,
+    pub b: String,
+    pub count: usize,
+}
+
+/// Analyzer counting agents invoked within a short window of each other
+///
+/// Every pair of invocations of two different agents at most
+/// `window_secs` apart counts once towards that (unordered) agent pair.
+///
+/// Demonstrates:
+/// - Sorting borrowed data, then scanning forward within a window
+/// - Normalizing unordered pairs as (min, max) keys
+pub struct CoOccurrenceAnalyzer {
+    window_secs: f64,
+}
+
+impl CoOccurrenceAnalyzer {
+    /// Create a co-occurrence analyzer with the default 5 second window
+    pub fn new() -> Self {
+        Self::with_window(DEFAULT_CO_OCCURRENCE_WINDOW_SECS)
+    }
+
+    /// Create with a custom window in seconds
+    pub fn with_window(window_secs: f64) -> Self {
+        Self { window_secs }
+    }
+}
+
+impl Default for CoOccurrenceAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for CoOccurrenceAnalyzer {
+    type Output = Vec<AgentPair>;
+
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        let mut invocations: Vec<(DateTime<Utc>, &str)> = session
+            .entries
+            .iter()
+            .filter_map(|e| e.agent_name.as_deref().map(|agent| (e.timestamp, agent)))
+            .collect();
+        invocations.sort_by_key(|(timestamp, _)| *timestamp);
+
+        let window_ms = (self.window_secs * 1000.0) as i64;
+        let mut counts: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+
+        for (i, &(time_a, agent_a)) in invocations.iter().enumerate() {
+            for &(time_b, agent_b) in &invocations[i + 1..] {
+                if (time_b - time_a).num_milliseconds() > window_ms {
+                    break;
+                }
+                if agent_a != agent_b {
+                    *counts.entry((agent_a.min(agent_b), agent_a.max(agent_b))).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut pairs: Vec<AgentPair> = counts
+            .into_iter()
+            .map(|((a, b), count)| AgentPair {
+                a: a.to_string(),
+                b: b.to_string(),
+                count,
+            })
+            .collect();
+
+        // Stable sort keeps alphabetical order among equal counts
+        pairs.sort_by_key(|pair| std::cmp::Reverse(pair.count));
+        Ok(pairs)
+    }
+
+    fn name(&self) -> &str {
+        "CoOccurrenceAnalyzer"
+    }
+}
+
+/// Message keywords that mark an agent invocation as failed
+pub const DEFAULT_FAILURE_KEYWORDS: &[&str] = &["timeout", "failed", "error", "cancelled"];
+
+/// Default number of example messages kept per failing agent
+pub const DEFAULT_FAILURE_SAMPLES: usize = 3;
+
+/// Failures attributed to one agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentFailure {
+    pub agent: String,
+    pub failure_count: usize,
+
+    /// The first few failing messages, in log order
+    pub sample_messages: Vec<String>,
+}
+
+/// Analyzer tallying agent entries whose message mentions a failure keyword
+///
+/// Matching is a case-insensitive substring search. Output is sorted by
+/// failure count, most failures first, then by agent name.
+///
+/// Demonstrates:
+/// - Lowercasing once per entry for case-insensitive matching
+/// - Capping per-group samples while still counting everything
+pub struct FailureAnalyzer {
+    /// Lowercased keywords
+    keywords: Vec<String>,
+    max_samples: usize,
+}
+
+impl FailureAnalyzer {
+    /// Create a failure analyzer with the default keywords
+    pub fn new() -> Self {
+        Self::with_keywords(DEFAULT_FAILURE_KEYWORDS, DEFAULT_FAILURE_SAMPLES)
+    }
+
+    /// Create with custom keywords and sample limit
+    pub fn with_keywords(keywords: &[&str], max_samples: usize) -> Self {
+        Self {
+            keywords: keywords.iter().map(|k| k.to_lowercase()).collect(),
+            max_samples,
+        }
+    }
+
+    fn is_failure(&self, message: &str) -> bool {
+        let message = message.to_lowercase();
+        self.keywords.iter().any(|keyword| message.contains(keyword.as_str()))
+    }
+}
+
+impl Default for FailureAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for FailureAnalyzer {
+    type Output = Vec<AgentFailure>;
+
+    fn analyze(&self, session: &LogSession) -> ParseResult<Self::Output> {
+        let mut by_agent: BTreeMap<&str, AgentFailure> = BTreeMap::new();
+
+        for entry in &session.entries {
+            let Some(agent) = entry.agent_name.as_deref() else {
+                continue;
+            };
+
+            if !self.is_failure(&entry.message) {
+                continue;
+            }
+
+            let failure = by_agent.entry(agent).or_insert_with(|| AgentFailure {
+                agent: agent.to_string(),
+                failure_count: 0,
+                sample_messages: Vec::new(),
+            });
+
+            failure.failure_count += 1;
+            if failure.sample_messages.len() < self.max_samples {
+                failure.sample_messages.push(entry.message.clone());
+            }
+        }
+
+        let mut failures: Vec<AgentFailure> = by_agent.into_values().collect();
+
+        // Stable sort keeps alphabetical order among equal counts
+        failures.sort_by_key(|f| std::cmp::Reverse(f.failure_count));
+        Ok(failures)
+    }
+
+    fn name(&self) -> &str {
+        "FailureAnalyzer"
+    }
+}
+
+/// Render analyzer output as a human-readable report
+///
+/// Implemented for each analyzer output so heterogeneous analyzers can be
+/// run together by `CompositeAnalyzer`.
+pub trait ToReport {
+    fn to_report(&self) -> String;
+}
+
+impl ToReport for String {
+    fn to_report(&self) -> String {
+        self.clone()
+    }
+}
+
+impl ToReport for TimingStats {
+    fn to_report(&self) -> String {
+        format!(
+            "Total duration: {:.2}s, entries: {}, avg gap: {:.2}s, p95 gap: {:.2}s",
+            self.total_duration_secs,
+            self.entry_count,
+            self.avg_time_between_entries,
+            self.p95_gap_secs
+        )
+    }
+}
+
+impl ToReport for Vec<AgentStats> {
+    fn to_report(&self) -> String {
+        if self.is_empty() {
+            return "No agent invocations found".to_string();
+        }
+
+        self.iter()
+            .map(|stats| {
+                format!(
+                    "{}: {} invocations, {:.2}ms avg",
+                    stats.name, stats.invocation_count, stats.avg_duration_ms
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl ToReport for PatternAnalysis {
+    fn to_report(&self) -> String {
+        if self.patterns.is_empty() {
+            return "No significant patterns detected".to_string();
+        }
+
+        self.patterns
+            .iter()
+            .map(|pattern| format!("{:?}", pattern))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Object-safe analyzer that produces a textual report
+///
+/// Demonstrates:
+/// - Blanket implementations over another trait
+/// - Erasing associated types so trait objects can be mixed
+pub trait ReportableAnalyzer {
+    /// Analyze a session and render the result as a report
+    fn analyze_to_report(&self, session: &LogSession) -> ParseResult<String>;
+
+    /// Get analyzer name for reporting
+    fn report_name(&self) -> &str;
+}
+
+impl<A> ReportableAnalyzer for A
+where
+    A: Analyzer,
+    A::Output: ToReport,
+{
+    fn analyze_to_report(&self, session: &LogSession) -> ParseResult<String> {
+        self.analyze(session).map(|output| output.to_report())
+    }
+
+    fn report_name(&self) -> &str {
+        self.name()
+    }
+}
+
+/// Composite analyzer that runs multiple analyzers
+///
+/// Demonstrates:
+/// - Trait objects (Box<dyn ReportableAnalyzer>)
+/// - Polymorphism over analyzers with different outputs
+pub struct CompositeAnalyzer {
+    analyzers: Vec<Box<dyn ReportableAnalyzer>>,
+}
+
+impl CompositeAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            analyzers: Vec::new(),
+        }
+    }
+
+    pub fn add_analyzer<A>(&mut self, analyzer: A)
+    where
+        A: ReportableAnalyzer + 'static,
+    {
+        self.analyzers.push(Box::new(analyzer));
+    }
+
+    pub fn run_all(&self, session: &LogSession) -> Vec<(String, ParseResult<String>)> {
+        self.analyzers
+            .iter()
+            .map(|analyzer| {
+                let name = analyzer.report_name().to_string();
+                let result = analyzer.analyze_to_report(session);
+                (name, result)
+            })
+            .collect()
+    }
+}
+
+impl Default for CompositeAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn create_test_session() -> LogSession {
+        let now = Utc::now();
+
+        let entries = vec![
+            LogEntry {
+                timestamp: now,
+                entry_type: EntryType::Info,
+                message: "Start".to_string(),
+                agent_name: None,
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            },
+            LogEntry {
+                timestamp: now + Duration::seconds(10),
+                entry_type: EntryType::AgentInvocation,
+                message: "Agent called".to_string(),
+                agent_name: Some("test-agent".to_string()),
+                duration_ms: Some(100),
+                fields: HashMap::new(),
+                byte_offset: None,
+            },
+            LogEntry {
+                timestamp: now + Duration::seconds(20),
+                entry_type: EntryType::AgentInvocation,
+                message: "Agent called again".to_string(),
+                agent_name: Some("test-agent".to_string()),
+                duration_ms: Some(200),
+                fields: HashMap::new(),
+                byte_offset: None,
+            },
+            LogEntry {
+                timestamp: now + Duration::seconds(30),
+                entry_type: EntryType::Info,
+                message: "End".to_string(),
+                agent_name: None,
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            },
+        ];
+
+        LogSession {
+            id: "test-session".to_string(),
+            entries,
+            start_time: now,
+            end_time: Some(now + Duration::seconds(30)),
+        }
+    }
+
+    #[test]
+    fn test_timing_analyzer() {
+        let analyzer = TimingAnalyzer::new();
+        let session = create_test_session();
+
+        let result = analyzer.analyze(&session);
+        assert!(result.is_ok());
+
+        let stats = result.unwrap();
+        assert_eq!(stats.entry_count, 4);
         assert_eq!(stats.total_duration_secs, 30.0);
         assert_eq!(stats.avg_time_between_entries, 10.0);
+        assert_eq!(stats.p50_gap_secs, 10.0);
+        assert_eq!(stats.p95_gap_secs, 10.0);
+        assert_eq!(stats.p99_gap_secs, 10.0);
+        assert_eq!(stats.stddev_gap_secs, 0.0);
+    }
+
+    #[test]
+    fn test_timing_analyzer_skewed_gaps() {
+        let analyzer = TimingAnalyzer::new();
+        let now = Utc::now();
+
+        // Nine 1s gaps followed by one 100s gap
+        let mut offsets: Vec<i64> = (0..10).collect();
+        offsets.push(109);
+
+        let entries = offsets
+            .iter()
+            .map(|secs| LogEntry {
+                timestamp: now + Duration::seconds(*secs),
+                entry_type: EntryType::Info,
+                message: "tick".to_string(),
+                agent_name: None,
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
+
+        let session = LogSession {
+            id: "skewed".to_string(),
+            entries,
+            start_time: now,
+            end_time: Some(now + Duration::seconds(109)),
+        };
+
+        let stats = analyzer.analyze(&session).unwrap();
+        assert_eq!(stats.avg_time_between_entries, 10.9);
+        assert_eq!(stats.p50_gap_secs, 1.0);
+        assert_eq!(stats.p95_gap_secs, 100.0);
+        assert_eq!(stats.p99_gap_secs, 100.0);
+        assert!(stats.p95_gap_secs > stats.avg_time_between_entries);
+        assert!((stats.stddev_gap_secs - 29.7).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_agent_analyzer() {
+        let analyzer = AgentAnalyzer::new();
+        let session = create_test_session();
+
+        let result = analyzer.analyze(&session);
+        assert!(result.is_ok());
+
+        let stats = result.unwrap();
+        assert_eq!(stats.len(), 1);
+
+        let agent_stats = &stats[0];
+        assert_eq!(agent_stats.name, "test-agent");
+        assert_eq!(agent_stats.invocation_count, 2);
+        assert_eq!(agent_stats.total_duration_ms, 300);
+        assert_eq!(agent_stats.avg_duration_ms, 150.0);
+        assert_eq!(agent_stats.min_duration_ms, Some(100));
+        assert_eq!(agent_stats.max_duration_ms, Some(200));
+    }
+
+    #[test]
+    fn test_agent_analyzer_first_and_last_seen() {
+        let mut session = create_test_session();
+        let start = session.start_time;
+
+        // Invocations out of order still give the earliest and latest
+        session.entries.swap(1, 2);
+
+        let stats = AgentAnalyzer::new().analyze(&session).unwrap();
+        assert_eq!(stats[0].first_seen, Some(start + Duration::seconds(10)));
+        assert_eq!(stats[0].last_seen, Some(start + Duration::seconds(20)));
+
+        let mut later = session.clone();
+        for entry in &mut later.entries {
+            entry.timestamp += Duration::hours(1);
+        }
+        let merged = aggregate_agent_stats(vec![
+            AgentAnalyzer::new().analyze(&later).unwrap(),
+            stats,
+        ]);
+        assert_eq!(merged[0].first_seen, Some(start + Duration::seconds(10)));
+        assert_eq!(merged[0].last_seen, Some(start + Duration::seconds(3620)));
+    }
+
+    #[test]
+    fn test_agent_analyzer_stateful() {
+        let mut analyzer = AgentAnalyzer::new();
+        let session = create_test_session();
+
+        // Process entries directly
+        analyzer.process_entries(&session.entries);
+
+        let stats = analyzer.get_agent_stats("test-agent");
+        assert!(stats.is_some());
+
+        let stats = stats.unwrap();
+        assert_eq!(stats.invocation_count, 2);
+        assert_eq!(stats.total_duration_ms, 300);
+        assert_eq!((stats.min_duration_ms, stats.max_duration_ms), (Some(100), Some(200)));
+
+        // Test clear
+        analyzer.clear();
+        assert!(analyzer.get_agent_stats("test-agent").is_none());
+    }
+
+    fn timed_stats(name: &str, durations: &[u64]) -> AgentStats {
+        let mut stats = AgentStats::new(name.to_string());
+        for &duration in durations {
+            stats.add_duration(duration);
+        }
+        stats
+    }
+
+    #[test]
+    fn test_aggregate_agent_stats_overlapping_agents() {
+        let first = vec![timed_stats("builder", &[100, 200]), timed_stats("architect", &[50])];
+        let second = vec![timed_stats("architect", &[150, 400]), timed_stats("builder", &[300])];
+
+        let merged = aggregate_agent_stats(vec![first, second]);
+
+        let names: Vec<_> = merged.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["architect", "builder"]);
+
+        let architect = &merged[0];
+        assert_eq!(architect.invocation_count, 3);
+        assert_eq!(architect.total_duration_ms, 600);
+        assert_eq!(architect.avg_duration_ms, 200.0);
+        assert_eq!((architect.min_duration_ms, architect.max_duration_ms), (Some(50), Some(400)));
+
+        let builder = &merged[1];
+        assert_eq!(builder.invocation_count, 3);
+        assert_eq!(builder.total_duration_ms, 600);
+        assert_eq!((builder.min_duration_ms, builder.max_duration_ms), (Some(100), Some(300)));
+    }
+
+    #[test]
+    fn test_aggregate_agent_stats_disjoint_agents() {
+        let merged = aggregate_agent_stats(vec![
+            vec![timed_stats("reviewer", &[10])],
+            vec![],
+            vec![timed_stats("architect", &[20, 40])],
+        ]);
+
+        let summary: Vec<_> = merged
+            .iter()
+            .map(|s| (s.name.as_str(), s.invocation_count, s.avg_duration_ms))
+            .collect();
+        assert_eq!(summary, vec![("architect", 2, 30.0), ("reviewer", 1, 10.0)]);
+        assert!(aggregate_agent_stats(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_aggregate_matches_analyzing_merged_session() {
+        let session = create_test_session();
+        let per_session = vec![
+            AgentAnalyzer::new().analyze(&session).unwrap(),
+            AgentAnalyzer::new().analyze(&session).unwrap(),
+        ];
+        let combined = merge_sessions(vec![session.clone(), session]);
+
+        let aggregated = aggregate_agent_stats(per_session);
+        let direct = AgentAnalyzer::new().analyze(&combined).unwrap();
+
+        assert_eq!(aggregated.len(), direct.len());
+        assert_eq!(aggregated[0].invocation_count, direct[0].invocation_count);
+        assert_eq!(aggregated[0].total_duration_ms, direct[0].total_duration_ms);
+        assert_eq!(aggregated[0].avg_duration_ms, direct[0].avg_duration_ms);
+        assert_eq!(aggregated[0].min_duration_ms, direct[0].min_duration_ms);
+    }
+
+    #[test]
+    fn test_pattern_analyzer_error_burst() {
+        let analyzer = PatternAnalyzer::new();
+        let now = Utc::now();
+
+        let entries = vec![
+            LogEntry {
+                timestamp: now,
+                entry_type: EntryType::Error,
+                message: "Error 1".to_string(),
+                agent_name: None,
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            },
+            LogEntry {
+                timestamp: now + Duration::milliseconds(100),
+                entry_type: EntryType::Error,
+                message: "Error 2".to_string(),
+                agent_name: None,
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            },
+            LogEntry {
+                timestamp: now + Duration::milliseconds(200),
+                entry_type: EntryType::Error,
+                message: "Error 3".to_string(),
+                agent_name: None,
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            },
+        ];
+
+        let session = LogSession {
+            id: "error-session".to_string(),
+            entries,
+            start_time: now,
+            end_time: Some(now + Duration::milliseconds(200)),
+        };
+
+        let result = analyzer.analyze(&session);
+        assert!(result.is_ok());
+
+        let analysis = result.unwrap();
+        let has_error_burst = analysis
+            .patterns
+            .iter()
+            .any(|p| matches!(p, LogPattern::ErrorBurst { .. }));
+
+        assert!(has_error_burst);
+    }
+
+    #[test]
+    fn test_patterns_sorted_by_severity() {
+        let analyzer = PatternAnalyzer::new();
+        let now = Utc::now();
+        let at = |millis: i64, entry_type: EntryType| LogEntry {
+            timestamp: now + Duration::milliseconds(millis),
+            entry_type,
+            message: format!("entry at {}", millis),
+            agent_name: None,
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        };
+
+        // A long gap early in the session, followed later by an error burst
+        let entries = vec![
+            at(0, EntryType::Info),
+            at(600_000, EntryType::Error),
+            at(600_100, EntryType::Error),
+            at(600_200, EntryType::Error),
+        ];
+        let session = LogSession::from_entries("severity", entries);
+
+        let patterns = analyzer.analyze(&session).unwrap().patterns;
+
+        assert!(matches!(patterns[0], LogPattern::ErrorBurst { .. }));
+        let gap_idx = patterns
+            .iter()
+            .position(|p| matches!(p, LogPattern::LongGap { .. }))
+            .unwrap();
+        assert!(gap_idx > 0);
+        assert!(patterns.windows(2).all(|w| w[0].severity() >= w[1].severity()));
+        assert_eq!(patterns.last(), Some(&LogPattern::NoAgentActivity));
+    }
+
+    fn stalled_starts(session: &LogSession) -> Vec<LogPattern> {
+        PatternAnalyzer::new()
+            .analyze(session)
+            .unwrap()
+            .patterns
+            .into_iter()
+            .filter(|p| matches!(p, LogPattern::StalledStart { .. }))
+            .collect()
+    }
+
+    fn info_at(start: DateTime<Utc>, offsets_secs: &[i64]) -> Vec<LogEntry> {
+        offsets_secs
+            .iter()
+            .map(|&secs| LogEntry {
+                timestamp: start + Duration::seconds(secs),
+                entry_type: EntryType::Info,
+                message: format!("entry at {}s", secs),
+                agent_name: None,
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stalled_start_only_after_first_entry() {
+        let start = Utc::now();
+
+        // A long gap later in the session is not a stalled start
+        let normal = LogSession::from_entries("normal", info_at(start, &[0, 5, 10, 900]));
+        assert!(stalled_starts(&normal).is_empty());
+
+        let stalled = LogSession::from_entries("stalled", info_at(start, &[0, 600, 605]));
+        assert_eq!(stalled_starts(&stalled), vec![LogPattern::StalledStart { after_secs: 600.0 }]);
+
+        let sensitive = PatternAnalyzer::new().with_stalled_start(1.0);
+        let patterns = sensitive.analyze(&normal).unwrap().patterns;
+        assert!(patterns.contains(&LogPattern::StalledStart { after_secs: 5.0 }));
+    }
+
+    #[test]
+    fn test_stalled_start_single_entry_uses_end_time() {
+        let start = Utc::now();
+        let mut session = LogSession::from_entries("single", info_at(start, &[0]));
+
+        // The end time defaults to the only entry, so nothing is known yet
+        assert!(stalled_starts(&session).is_empty());
+
+        session.end_time = Some(start + Duration::seconds(3600));
+        assert_eq!(stalled_starts(&session), vec![LogPattern::StalledStart { after_secs: 3600.0 }]);
+
+        session.end_time = None;
+        assert!(stalled_starts(&session).is_empty());
+        assert!(stalled_starts(&LogSession::from_entries("empty", vec![])).is_empty());
+    }
+
+    #[test]
+    fn test_error_burst_coalesces_overlapping_windows() {
+        let analyzer = PatternAnalyzer::new();
+        let now = Utc::now();
+
+        let entries: Vec<LogEntry> = (0..6)
+            .map(|i| LogEntry {
+                timestamp: now + Duration::milliseconds(i * 50),
+                entry_type: EntryType::Error,
+                message: format!("Error {}", i),
+                agent_name: None,
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
+
+        let session = LogSession {
+            id: "burst-session".to_string(),
+            entries,
+            start_time: now,
+            end_time: Some(now + Duration::milliseconds(250)),
+        };
+
+        let analysis = analyzer.analyze(&session).unwrap();
+        let bursts: Vec<_> = analysis
+            .patterns
+            .iter()
+            .filter_map(|p| match p {
+                LogPattern::ErrorBurst { count, duration_secs } => Some((*count, *duration_secs)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(bursts.len(), 1);
+        assert_eq!(bursts[0].0, 6);
+        assert!((bursts[0].1 - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pattern_analyzer_no_agent_activity() {
+        let analyzer = PatternAnalyzer::new();
+        let now = Utc::now();
+
+        let entries = vec![LogEntry {
+            timestamp: now,
+            entry_type: EntryType::Info,
+            message: "No agents here".to_string(),
+            agent_name: None,
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        }];
+
+        let session = LogSession {
+            id: "no-agent-session".to_string(),
+            entries,
+            start_time: now,
+            end_time: Some(now + Duration::seconds(10)),
+        };
+
+        let result = analyzer.analyze(&session);
+        assert!(result.is_ok());
+
+        let analysis = result.unwrap();
+        let has_no_agent = analysis
+            .patterns
+            .iter()
+            .any(|p| matches!(p, LogPattern::NoAgentActivity));
+
+        assert!(has_no_agent);
+    }
+
+    #[test]
+    fn test_analyzer_trait_polymorphism() {
+        // Demonstrates trait usage
+        let timing: Box<dyn Analyzer<Output = TimingStats>> = Box::new(TimingAnalyzer::new());
+        let session = create_test_session();
+
+        let result = timing.analyze(&session);
+        assert!(result.is_ok());
+        assert_eq!(timing.name(), "TimingAnalyzer");
+    }
+
+    #[test]
+    fn test_zero_entries_session() {
+        let analyzer = TimingAnalyzer::new();
+        let session = LogSession {
+            id: "empty".to_string(),
+            entries: vec![],
+            start_time: Utc::now(),
+            end_time: None,
+        };
+
+        let result = analyzer.analyze(&session);
+        assert!(result.is_ok());
+
+        let stats = result.unwrap();
+        assert_eq!(stats.entry_count, 0);
+        assert_eq!(stats.total_duration_secs, 0.0);
+    }
+
+    #[test]
+    fn test_single_entry_session() {
+        let analyzer = TimingAnalyzer::new();
+        let now = Utc::now();
+
+        let session = LogSession {
+            id: "single".to_string(),
+            entries: vec![LogEntry {
+                timestamp: now,
+                entry_type: EntryType::Info,
+                message: "Only one".to_string(),
+                agent_name: None,
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            }],
+            start_time: now,
+            end_time: None,
+        };
+
+        let result = analyzer.analyze(&session);
+        assert!(result.is_ok());
+
+        let stats = result.unwrap();
+        assert_eq!(stats.entry_count, 1);
+        assert_eq!(stats.avg_time_between_entries, 0.0);
+        assert_eq!(stats.p95_gap_secs, 0.0);
+        assert_eq!(stats.stddev_gap_secs, 0.0);
+    }
+
+    fn session_with_durations(durations: &[u64]) -> LogSession {
+        let now = Utc::now();
+
+        let entries = durations
+            .iter()
+            .enumerate()
+            .map(|(idx, &duration)| LogEntry {
+                timestamp: now + Duration::seconds(idx as i64),
+                entry_type: EntryType::AgentInvocation,
+                message: "Agent called".to_string(),
+                agent_name: Some("test-agent".to_string()),
+                duration_ms: Some(duration),
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
+
+        LogSession {
+            id: "durations".to_string(),
+            entries,
+            start_time: now,
+            end_time: None,
+        }
+    }
+
+    #[test]
+    fn test_duration_analyzer_empty() {
+        let analyzer = DurationAnalyzer::new();
+        let session = session_with_durations(&[]);
+
+        let histogram = analyzer.analyze(&session).unwrap();
+        assert_eq!(histogram.buckets.len(), 6);
+        assert!(histogram.buckets.iter().all(|(_, _, count)| *count == 0));
+        assert_eq!(histogram.buckets[0], (0, 10, 0));
+        assert_eq!(histogram.buckets[5], (100_000, u64::MAX, 0));
+    }
+
+    #[test]
+    fn test_duration_analyzer_default_buckets() {
+        let analyzer = DurationAnalyzer::new();
+        let session = session_with_durations(&[5, 50, 500, 5_000, 500_000]);
+
+        let histogram = analyzer.analyze(&session).unwrap();
+        let counts: Vec<usize> = histogram.buckets.iter().map(|(_, _, c)| *c).collect();
+        assert_eq!(counts, vec![1, 1, 1, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_duration_analyzer_bucket_edges() {
+        let analyzer = DurationAnalyzer::with_buckets(vec![100, 50, 200]);
+        let session = session_with_durations(&[10, 50, 99, 100, 199, 200, 1_000]);
+
+        let histogram = analyzer.analyze(&session).unwrap();
+        assert_eq!(
+            histogram.buckets,
+            vec![(50, 100, 2), (100, 200, 2), (200, u64::MAX, 2)]
+        );
+    }
+
+    #[test]
+    fn test_error_rate_analyzer_empty() {
+        let analyzer = ErrorRateAnalyzer::new();
+        let session = LogSession {
+            id: "empty".to_string(),
+            entries: vec![],
+            start_time: Utc::now(),
+            end_time: None,
+        };
+
+        assert!(analyzer.analyze(&session).unwrap().is_empty());
+    }
+
+    fn error_buckets(counts: &[usize]) -> Vec<ErrorRateBucket> {
+        let base = "2025-10-18T14:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        counts
+            .iter()
+            .enumerate()
+            .map(|(i, &error_count)| ErrorRateBucket {
+                minute_start: base + Duration::minutes(i as i64),
+                error_count,
+                total_count: error_count,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_smooth_error_rate_edges_and_center() {
+        let buckets = error_buckets(&[0, 6, 0, 3, 9]);
+
+        let smoothed = smooth_error_rate(&buckets, 3);
+
+        // Edges average the two buckets that exist
+        assert_eq!(smoothed.len(), 5);
+        assert_eq!(smoothed[0], 3.0);
+        assert_eq!(smoothed[4], 6.0);
+
+        // Interior buckets average their full three-bucket window
+        assert_eq!(smoothed[1], 2.0);
+        assert_eq!(smoothed[2], 3.0);
+        assert_eq!(smoothed[3], 4.0);
+    }
+
+    #[test]
+    fn test_smooth_error_rate_degenerate_windows() {
+        let buckets = error_buckets(&[1, 5, 3]);
+
+        assert_eq!(smooth_error_rate(&buckets, 0), vec![1.0, 5.0, 3.0]);
+        assert_eq!(smooth_error_rate(&buckets, 1), vec![1.0, 5.0, 3.0]);
+        assert_eq!(smooth_error_rate(&buckets, 99), vec![3.0, 3.0, 3.0]);
+        assert!(smooth_error_rate(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn test_error_rate_analyzer_buckets() {
+        let analyzer = ErrorRateAnalyzer::new();
+        let base = "2025-10-18T14:30:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        let make = |offset_secs: i64, entry_type: EntryType| LogEntry {
+            timestamp: base + Duration::seconds(offset_secs),
+            entry_type,
+            message: "entry".to_string(),
+            agent_name: None,
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        };
+
+        let entries = vec![
+            make(0, EntryType::Error),
+            make(30, EntryType::Info),
+            make(59, EntryType::Error),
+            make(60, EntryType::Info),
+            make(150, EntryType::Error),
+            make(179, EntryType::Warning),
+        ];
+
+        let session = LogSession {
+            id: "errors".to_string(),
+            entries,
+            start_time: base,
+            end_time: None,
+        };
+
+        let buckets = analyzer.analyze(&session).unwrap();
+        assert_eq!(buckets.len(), 3);
+
+        assert_eq!(buckets[0].minute_start, base);
+        assert_eq!((buckets[0].error_count, buckets[0].total_count), (2, 3));
+
+        assert_eq!(buckets[1].minute_start, base + Duration::minutes(1));
+        assert_eq!((buckets[1].error_count, buckets[1].total_count), (0, 1));
+
+        assert_eq!(buckets[2].minute_start, base + Duration::minutes(2));
+        assert_eq!((buckets[2].error_count, buckets[2].total_count), (1, 2));
+    }
+
+    fn agent_entry(agent: &str, fields: &[(&str, &str)]) -> LogEntry {
+        LogEntry {
+            timestamp: Utc::now(),
+            entry_type: EntryType::AgentInvocation,
+            message: format!("{} invoked", agent),
+            agent_name: Some(agent.to_string()),
+            duration_ms: None,
+            fields: fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            byte_offset: None,
+        }
+    }
+
+    #[test]
+    fn test_cost_analyzer_sums_per_agent() {
+        let analyzer = CostAnalyzer::with_rates(0.001, 0.002);
+
+        let session = LogSession {
+            id: "cost".to_string(),
+            entries: vec![
+                agent_entry("architect", &[("tokens_in", "100"), ("tokens_out", "50")]),
+                agent_entry("architect", &[("tokens_in", "200"), ("tokens_out", "25")]),
+                agent_entry("builder", &[("tokens_in", "1000")]),
+                agent_entry("reviewer", &[]),
+            ],
+            start_time: Utc::now(),
+            end_time: None,
+        };
+
+        let costs = analyzer.analyze(&session).unwrap();
+        assert_eq!(costs.len(), 3);
+
+        assert_eq!(costs[0].agent, "architect");
+        assert_eq!(costs[0].total_tokens, 375);
+        assert!((costs[0].estimated_cost_usd - (300.0 * 0.001 + 75.0 * 0.002)).abs() < 1e-9);
+
+        assert_eq!(costs[1].agent, "builder");
+        assert_eq!(costs[1].total_tokens, 1000);
+        assert!((costs[1].estimated_cost_usd - 1.0).abs() < 1e-9);
+
+        // Missing token fields contribute zero
+        assert_eq!(costs[2].agent, "reviewer");
+        assert_eq!(costs[2].total_tokens, 0);
+        assert_eq!(costs[2].estimated_cost_usd, 0.0);
+    }
+
+    #[test]
+    fn test_cost_analyzer_default_rates() {
+        let analyzer = CostAnalyzer::default();
+
+        let session = LogSession {
+            id: "cost".to_string(),
+            entries: vec![agent_entry(
+                "architect",
+                &[("tokens_in", "1000000"), ("tokens_out", "1000000")],
+            )],
+            start_time: Utc::now(),
+            end_time: None,
+        };
+
+        let costs = analyzer.analyze(&session).unwrap();
+        assert!((costs[0].estimated_cost_usd - 18.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_composite_analyzer_heterogeneous() {
+        let mut composite = CompositeAnalyzer::new();
+        composite.add_analyzer(TimingAnalyzer::new());
+        composite.add_analyzer(AgentAnalyzer::new());
+        composite.add_analyzer(PatternAnalyzer::new());
+
+        let session = create_test_session();
+        let results = composite.run_all(&session);
+
+        let names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["TimingAnalyzer", "AgentAnalyzer", "PatternAnalyzer"]);
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+        let agent_report = results[1].1.as_ref().unwrap();
+        assert!(agent_report.contains("test-agent: 2 invocations"));
+    }
+
+    #[test]
+    fn test_decision_analyzer_filters_and_orders() {
+        let analyzer = DecisionAnalyzer::new();
+        let now = Utc::now();
+
+        let make = |offset_secs: i64, entry_type: EntryType, message: &str| LogEntry {
+            timestamp: now + Duration::seconds(offset_secs),
+            entry_type,
+            message: message.to_string(),
+            agent_name: None,
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        };
+
+        let session = LogSession {
+            id: "decisions".to_string(),
+            entries: vec![
+                make(30, EntryType::Decision, "Use rayon"),
+                make(0, EntryType::Info, "Start"),
+                make(10, EntryType::Decision, "Adopt logfmt"),
+                make(20, EntryType::Error, "Oops"),
+            ],
+            start_time: now,
+            end_time: None,
+        };
+
+        let decisions = analyzer.analyze(&session).unwrap();
+        let messages: Vec<&str> = decisions.iter().map(|d| d.message.as_str()).collect();
+
+        assert_eq!(messages, vec!["Adopt logfmt", "Use rayon"]);
+        assert_eq!(decisions[0].timestamp, now + Duration::seconds(10));
+    }
+
+    fn timed_session(agent: &str, durations: &[u64]) -> LogSession {
+        let now = Utc::now();
+        let entries = durations
+            .iter()
+            .enumerate()
+            .map(|(i, &ms)| LogEntry {
+                timestamp: now + Duration::seconds(i as i64),
+                entry_type: EntryType::AgentInvocation,
+                message: format!("{} completed in {}ms", agent, ms),
+                agent_name: Some(agent.to_string()),
+                duration_ms: Some(ms),
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
+
+        LogSession {
+            id: "timed-session".to_string(),
+            entries,
+            start_time: now,
+            end_time: None,
+        }
+    }
+
+    #[test]
+    fn test_outlier_analyzer_flags_slow_call() {
+        let mut durations: Vec<u64> = (0..20).map(|i| 100 + (i % 2) * 10).collect();
+        durations.push(5000);
+        let session = timed_session("builder", &durations);
+
+        let outliers = OutlierAnalyzer::new().analyze(&session).unwrap();
+
+        assert_eq!(outliers.len(), 1);
+        assert_eq!(outliers[0].agent, "builder");
+        assert_eq!(outliers[0].duration_ms, 5000);
+        assert!(outliers[0].z_score > DEFAULT_OUTLIER_Z_SCORE);
+    }
+
+    #[test]
+    fn test_outlier_analyzer_skips_small_samples() {
+        let session = timed_session("builder", &[100, 100_000]);
+        let outliers = OutlierAnalyzer::with_threshold(0.1).analyze(&session).unwrap();
+        assert!(outliers.is_empty());
+    }
+
+    #[test]
+    fn test_outlier_analyzer_ignores_fast_calls() {
+        // A very fast call has a negative z-score and is not "slow"
+        let mut durations = vec![1000; 20];
+        durations.push(1);
+        let session = timed_session("builder", &durations);
+
+        let outliers = OutlierAnalyzer::new().analyze(&session).unwrap();
+        assert!(outliers.is_empty());
+    }
+
+    fn session_of_types(types: &[EntryType]) -> LogSession {
+        let now = Utc::now();
+        let entries = types
+            .iter()
+            .map(|&entry_type| LogEntry {
+                timestamp: now,
+                entry_type,
+                message: format!("{:?}", entry_type),
+                agent_name: None,
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
+
+        LogSession {
+            id: "health-session".to_string(),
+            entries,
+            start_time: now,
+            end_time: None,
+        }
+    }
+
+    #[test]
+    fn test_health_analyzer_clean_session() {
+        let session = session_of_types(&[EntryType::Info, EntryType::AgentInvocation]);
+        let health = HealthAnalyzer::new().analyze(&session).unwrap();
+
+        assert_eq!(health.score, 100.0);
+        assert_eq!(health.error_count, 0);
+        assert_eq!(health.warning_count, 0);
+    }
+
+    #[test]
+    fn test_health_analyzer_penalizes_errors_and_warnings() {
+        let session = session_of_types(&[
+            EntryType::Error,
+            EntryType::Error,
+            EntryType::Warning,
+            EntryType::Info,
+        ]);
+        let health = HealthAnalyzer::with_penalties(15.0, 5.0)
+            .analyze(&session)
+            .unwrap();
+
+        assert_eq!(health.score, 65.0);
+        assert_eq!(health.error_count, 2);
+        assert_eq!(health.warning_count, 1);
+    }
+
+    #[test]
+    fn test_health_analyzer_clamps_at_zero() {
+        let session = session_of_types(&[EntryType::Error; 20]);
+        let health = HealthAnalyzer::new().analyze(&session).unwrap();
+
+        assert_eq!(health.score, 0.0);
+        assert_eq!(health.error_count, 20);
+    }
+
+    fn tagged_entry(session_id: Option<&str>, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: Utc::now(),
+            entry_type: EntryType::Info,
+            message: message.to_string(),
+            agent_name: None,
+            duration_ms: None,
+            fields: session_id
+                .map(|id| HashMap::from([(SESSION_ID_FIELD.to_string(), id.to_string())]))
+                .unwrap_or_default(),
+            byte_offset: None,
+        }
+    }
+
+    #[test]
+    fn test_split_into_sessions_by_session_id() {
+        let entries = vec![
+            tagged_entry(Some("b"), "b1"),
+            tagged_entry(Some("a"), "a1"),
+            tagged_entry(Some("b"), "b2"),
+            tagged_entry(Some("a"), "a2"),
+            tagged_entry(Some("b"), "b3"),
+        ];
+
+        let sessions = split_into_sessions(entries);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].id, "a");
+        assert_eq!(sessions[1].id, "b");
+
+        let messages: Vec<_> = sessions[1].entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["b1", "b2", "b3"]);
     }
 
     #[test]
-    fn test_agent_analyzer() {
-        let analyzer = AgentAnalyzer::new();
-        let session = create_test_session();
+    fn test_split_into_sessions_untagged_fallback() {
+        let entries = vec![tagged_entry(None, "one"), tagged_entry(None, "two")];
 
-        let result = analyzer.analyze(&session);
-        assert!(result.is_ok());
+        let sessions = split_into_sessions(entries);
 
-        let stats = result.unwrap();
-        assert_eq!(stats.len(), 1);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, UNTAGGED_SESSION_ID);
+        assert_eq!(sessions[0].entries.len(), 2);
+    }
 
-        let agent_stats = &stats[0];
-        assert_eq!(agent_stats.name, "test-agent");
-        assert_eq!(agent_stats.invocation_count, 2);
-        assert_eq!(agent_stats.total_duration_ms, 300);
-        assert_eq!(agent_stats.avg_duration_ms, 150.0);
+    #[test]
+    fn test_split_by_markers_two_sessions() {
+        let entries = vec![
+            tagged_entry(None, "Booting"),
+            tagged_entry(None, SESSION_START_MARKER),
+            tagged_entry(None, "first work"),
+            tagged_entry(None, SESSION_END_MARKER),
+            tagged_entry(None, SESSION_START_MARKER),
+            tagged_entry(None, "second work"),
+            tagged_entry(None, "more work"),
+            tagged_entry(None, SESSION_END_MARKER),
+        ];
+
+        let sessions = split_by_markers(entries, SESSION_START_MARKER, SESSION_END_MARKER);
+
+        let ids: Vec<_> = sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["session-1", "session-2", "session-3"]);
+
+        // Entries before the first start marker form an implicit session
+        assert_eq!(sessions[0].entries.len(), 1);
+        assert_eq!(sessions[0].entries[0].message, "Booting");
+
+        let messages: Vec<_> = sessions[2].entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![SESSION_START_MARKER, "second work", "more work", SESSION_END_MARKER]
+        );
     }
 
     #[test]
-    fn test_agent_analyzer_stateful() {
-        let mut analyzer = AgentAnalyzer::new();
-        let session = create_test_session();
+    fn test_split_by_markers_unterminated_and_padded_ids() {
+        let mut entries = Vec::new();
+        for i in 0..10 {
+            entries.push(tagged_entry(None, "START"));
+            entries.push(tagged_entry(None, &format!("work {}", i)));
+        }
 
-        // Process entries directly
-        analyzer.process_entries(&session.entries);
+        let sessions = split_by_markers(entries, "START", "END");
 
-        let stats = analyzer.get_agent_stats("test-agent");
-        assert!(stats.is_some());
+        assert_eq!(sessions.len(), 10);
+        assert_eq!(sessions[0].id, "session-01");
+        assert_eq!(sessions[9].id, "session-10");
+        assert_eq!(sessions[9].entries[1].message, "work 9");
+    }
 
-        let stats = stats.unwrap();
-        assert_eq!(stats.invocation_count, 2);
-        assert_eq!(stats.total_duration_ms, 300);
+    fn invocations_every(agent: &str, count: i64, spacing_secs: i64) -> LogSession {
+        let now = Utc::now();
+        let entries = (0..count)
+            .map(|i| LogEntry {
+                timestamp: now + Duration::seconds(i * spacing_secs),
+                entry_type: EntryType::AgentInvocation,
+                message: format!("{} invoked", agent),
+                agent_name: Some(agent.to_string()),
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
 
-        // Test clear
-        analyzer.clear();
-        assert!(analyzer.get_agent_stats("test-agent").is_none());
+        LogSession {
+            id: "activity-session".to_string(),
+            entries,
+            start_time: now,
+            end_time: None,
+        }
+    }
+
+    fn sustained(analysis: &PatternAnalysis) -> Vec<(String, usize)> {
+        analysis
+            .patterns
+            .iter()
+            .filter_map(|p| match p {
+                LogPattern::SustainedActivity { agent, count, .. } => Some((agent.clone(), *count)),
+                _ => None,
+            })
+            .collect()
     }
 
     #[test]
-    fn test_pattern_analyzer_error_burst() {
-        let analyzer = PatternAnalyzer::new();
+    fn test_sustained_activity_clustered_vs_spread() {
+        let analyzer = PatternAnalyzer::new().with_activity_window(60.0, 5);
+
+        // Ten calls within one minute
+        let clustered = analyzer.analyze(&invocations_every("builder", 10, 5)).unwrap();
+        assert_eq!(sustained(&clustered), vec![("builder".to_string(), 10)]);
+
+        // Ten calls an hour apart: same total, no sustained activity
+        let spread = analyzer.analyze(&invocations_every("builder", 10, 3600)).unwrap();
+        assert!(sustained(&spread).is_empty());
+
+        // Both still count as overall high activity
+        for analysis in [&clustered, &spread] {
+            assert!(analysis
+                .patterns
+                .iter()
+                .any(|p| matches!(p, LogPattern::AgentActivity { .. })));
+        }
+    }
+
+    fn entry_on(timestamp: &str, entry_type: EntryType, agent: Option<&str>) -> LogEntry {
+        LogEntry {
+            timestamp: crate::parser::parse_timestamp(timestamp).unwrap(),
+            entry_type,
+            message: "entry".to_string(),
+            agent_name: agent.map(String::from),
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        }
+    }
+
+    #[test]
+    fn test_trend_analyzer_groups_by_day() {
+        let entries = vec![
+            entry_on("2025-10-20T09:00:00Z", EntryType::AgentInvocation, Some("builder")),
+            entry_on("2025-10-18T10:00:00Z", EntryType::AgentInvocation, Some("architect")),
+            entry_on("2025-10-18T11:00:00Z", EntryType::AgentInvocation, Some("builder")),
+            entry_on("2025-10-18T23:59:59Z", EntryType::Error, None),
+            entry_on("2025-10-21T00:00:00Z", EntryType::Error, None),
+            entry_on("2025-10-21T08:00:00Z", EntryType::AgentInvocation, Some("builder")),
+            entry_on("2025-10-21T09:00:00Z", EntryType::AgentInvocation, Some("builder")),
+        ];
+        let session = LogSession::from_entries("trend", entries);
+
+        let trends = TrendAnalyzer::new().analyze(&session).unwrap();
+
+        // 2025-10-19 has no entries and is omitted
+        let dates: Vec<String> = trends.iter().map(|t| t.date.to_string()).collect();
+        assert_eq!(dates, vec!["2025-10-18", "2025-10-20", "2025-10-21"]);
+
+        assert_eq!(
+            (trends[0].total_invocations, trends[0].unique_agents, trends[0].error_count),
+            (2, 2, 1)
+        );
+        assert_eq!(
+            (trends[1].total_invocations, trends[1].unique_agents, trends[1].error_count),
+            (1, 1, 0)
+        );
+        assert_eq!(
+            (trends[2].total_invocations, trends[2].unique_agents, trends[2].error_count),
+            (2, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_activity_heatmap_counts_weekday_hour_slots() {
+        let entries = vec![
+            // 2025-10-20 is a Monday
+            entry_on("2025-10-20T00:00:00Z", EntryType::Info, None),
+            entry_on("2025-10-20T00:59:59Z", EntryType::Error, None),
+            entry_on("2025-10-22T13:30:00Z", EntryType::AgentInvocation, Some("builder")),
+            entry_on("2025-10-26T23:00:00Z", EntryType::Info, None),
+            // A week later lands in the same Monday 09:00 slot
+            entry_on("2025-10-20T09:15:00Z", EntryType::Info, None),
+            entry_on("2025-10-27T09:45:00Z", EntryType::Info, None),
+        ];
+        let session = LogSession::from_entries("heatmap", entries);
+
+        let heatmap = ActivityHeatmapAnalyzer::new().analyze(&session).unwrap();
+
+        assert_eq!(heatmap[0][0], 2);
+        assert_eq!(heatmap[0][9], 2);
+        assert_eq!(heatmap[2][13], 1);
+        assert_eq!(heatmap[6][23], 1);
+        assert_eq!(heatmap.iter().flatten().sum::<usize>(), 6);
+    }
+
+    #[test]
+    fn test_trend_analyzer_merges_sessions() {
+        let a = LogSession::from_entries(
+            "a",
+            vec![entry_on("2025-10-18T10:00:00Z", EntryType::AgentInvocation, Some("architect"))],
+        );
+        let b = LogSession::from_entries(
+            "b",
+            vec![entry_on("2025-10-18T12:00:00Z", EntryType::AgentInvocation, Some("builder"))],
+        );
+
+        let trends = TrendAnalyzer::new().analyze_sessions(&[a, b]);
+
+        assert_eq!(trends.len(), 1);
+        assert_eq!(trends[0].total_invocations, 2);
+        assert_eq!(trends[0].unique_agents, 2);
+    }
+
+    #[test]
+    fn test_transition_analyzer_counts_pairs() {
+        use EntryType::*;
+
+        let types = [Info, AgentInvocation, Error, Info, AgentInvocation, Error, Error];
+        let session = session_of_types(&types);
+
+        let transitions = TransitionAnalyzer::new().analyze(&session).unwrap();
+
+        assert_eq!(transitions.get(&(Info, AgentInvocation)), Some(&2));
+        assert_eq!(transitions.get(&(AgentInvocation, Error)), Some(&2));
+        assert_eq!(transitions.get(&(Error, Info)), Some(&1));
+        assert_eq!(transitions.get(&(Error, Error)), Some(&1));
+        assert_eq!(transitions.get(&(Info, Error)), None);
+        assert_eq!(transitions.values().sum::<usize>(), types.len() - 1);
+    }
+
+    #[test]
+    fn test_co_occurrence_counts_close_pairs_only() {
         let now = Utc::now();
+        let invoke = |secs: i64, agent: &str| LogEntry {
+            timestamp: now + Duration::seconds(secs),
+            entry_type: EntryType::AgentInvocation,
+            message: format!("{} invoked", agent),
+            agent_name: Some(agent.to_string()),
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        };
 
         let entries = vec![
-            LogEntry {
-                timestamp: now,
+            invoke(0, "builder"),
+            invoke(1, "architect"),
+            invoke(100, "architect"),
+            invoke(102, "builder"),
+            invoke(200, "builder"),
+            invoke(201, "builder"),
+            invoke(1000, "reviewer"),
+        ];
+        let session = LogSession::from_entries("co-occurrence", entries);
+
+        let pairs = CoOccurrenceAnalyzer::new().analyze(&session).unwrap();
+
+        assert_eq!(
+            pairs,
+            vec![AgentPair {
+                a: "architect".to_string(),
+                b: "builder".to_string(),
+                count: 2,
+            }]
+        );
+    }
+
+    fn errors_spread_out(messages: &[&str]) -> LogSession {
+        let now = Utc::now();
+        let entries = messages
+            .iter()
+            .enumerate()
+            .map(|(i, message)| LogEntry {
+                timestamp: now + Duration::minutes(i as i64 * 10),
                 entry_type: EntryType::Error,
-                message: "Error 1".to_string(),
+                message: message.to_string(),
                 agent_name: None,
                 duration_ms: None,
-            },
-            LogEntry {
-                timestamp: now + Duration::milliseconds(100),
-                entry_type: EntryType::Error,
-                message: "Error 2".to_string(),
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
+
+        LogSession::from_entries("repeated", entries)
+    }
+
+    fn repeated_errors(analysis: &PatternAnalysis) -> Vec<(String, usize)> {
+        analysis
+            .patterns
+            .iter()
+            .filter_map(|p| match p {
+                LogPattern::RepeatedError { message, count } => Some((message.clone(), *count)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_repeated_error_flags_only_repeated_message() {
+        let session = errors_spread_out(&[
+            "connection refused",
+            "connection refused ",
+            "disk full",
+            "connection refused",
+            "  connection refused",
+            "connection refused",
+        ]);
+
+        let analysis = PatternAnalyzer::new().analyze(&session).unwrap();
+
+        assert_eq!(
+            repeated_errors(&analysis),
+            vec![("connection refused".to_string(), 5)]
+        );
+    }
+
+    #[test]
+    fn test_repeated_error_strips_trailing_numbers() {
+        let session = errors_spread_out(&[
+            "retry failed 1",
+            "retry failed 2",
+            "retry failed 3",
+            "retry failed 4",
+        ]);
+
+        let exact = PatternAnalyzer::new().analyze(&session).unwrap();
+        assert!(repeated_errors(&exact).is_empty());
+
+        let stripped = PatternAnalyzer::new()
+            .with_repeated_errors(DEFAULT_REPEATED_ERROR_THRESHOLD, true)
+            .analyze(&session)
+            .unwrap();
+        assert_eq!(
+            repeated_errors(&stripped),
+            vec![("retry failed".to_string(), 4)]
+        );
+    }
+
+    #[test]
+    fn test_clock_skew_reports_backwards_timestamps() {
+        let now = Utc::now();
+        let entries = [0, 10, 5, 20]
+            .into_iter()
+            .map(|secs| LogEntry {
+                timestamp: now + Duration::seconds(secs),
+                entry_type: EntryType::Info,
+                message: "tick".to_string(),
                 agent_name: None,
                 duration_ms: None,
-            },
-            LogEntry {
-                timestamp: now + Duration::milliseconds(200),
-                entry_type: EntryType::Error,
-                message: "Error 3".to_string(),
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
+        let session = LogSession::from_entries("skewed", entries);
+
+        let analysis = PatternAnalyzer::new().analyze(&session).unwrap();
+
+        let skews: Vec<_> = analysis
+            .patterns
+            .iter()
+            .filter(|p| matches!(p, LogPattern::ClockSkew { .. }))
+            .collect();
+        assert_eq!(
+            skews,
+            vec![&LogPattern::ClockSkew {
+                prev: now + Duration::seconds(10),
+                next: now + Duration::seconds(5),
+            }]
+        );
+        assert_eq!(analysis.clock_skew_count(), 1);
+    }
+
+    #[test]
+    fn test_warning_analyzer_groups_and_sorts_by_count() {
+        let now = Utc::now();
+        let warnings = [
+            "disk 90% full",
+            "slow response",
+            "disk 90% full",
+            "deprecated flag",
+            " disk 90% full ",
+            "slow response",
+        ];
+        let mut entries: Vec<LogEntry> = warnings
+            .iter()
+            .map(|message| LogEntry {
+                timestamp: now,
+                entry_type: EntryType::Warning,
+                message: message.to_string(),
                 agent_name: None,
                 duration_ms: None,
-            },
-        ];
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
+        entries.push(LogEntry {
+            entry_type: EntryType::Error,
+            message: "slow response".to_string(),
+            ..entries[0].clone()
+        });
+        let session = LogSession::from_entries("warnings", entries);
+
+        let grouped = WarningAnalyzer::new().analyze(&session).unwrap();
+
+        let counts: Vec<_> = grouped.iter().map(|w| (w.message.as_str(), w.count)).collect();
+        assert_eq!(
+            counts,
+            vec![("disk 90% full", 3), ("slow response", 2), ("deprecated flag", 1)]
+        );
+    }
 
-        let session = LogSession {
-            id: "error-session".to_string(),
-            entries,
-            start_time: now,
-            end_time: Some(now + Duration::milliseconds(200)),
+    #[test]
+    fn test_merge_sessions_sorts_and_recomputes_bounds() {
+        let now = Utc::now();
+        let at = |secs: i64, message: &str| LogEntry {
+            timestamp: now + Duration::seconds(secs),
+            ..tagged_entry(None, message)
         };
 
-        let result = analyzer.analyze(&session);
-        assert!(result.is_ok());
+        let a = LogSession::from_entries("a", vec![at(30, "a1"), at(10, "a2")]);
+        let b = LogSession::from_entries("b", vec![at(20, "b1"), at(5, "b2"), at(40, "b3")]);
 
-        let analysis = result.unwrap();
-        let has_error_burst = analysis
-            .patterns
-            .iter()
-            .any(|p| matches!(p, LogPattern::ErrorBurst { .. }));
+        let merged = merge_sessions(vec![a, b]);
 
-        assert!(has_error_burst);
+        let messages: Vec<_> = merged.entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["b2", "a2", "b1", "a1", "b3"]);
+        assert_eq!(merged.id, "a+b");
+        assert_eq!(merged.start_time, now + Duration::seconds(5));
+        assert_eq!(merged.end_time, Some(now + Duration::seconds(40)));
     }
 
     #[test]
-    fn test_pattern_analyzer_no_agent_activity() {
-        let analyzer = PatternAnalyzer::new();
-        let now = Utc::now();
+    fn test_merge_sessions_empty() {
+        let merged = merge_sessions(Vec::new());
+        assert!(merged.entries.is_empty());
+        assert_eq!(merged.end_time, None);
+    }
 
-        let entries = vec![LogEntry {
+    #[test]
+    fn test_timing_per_type_avg_duration() {
+        let now = Utc::now();
+        let entry = |entry_type: EntryType, duration_ms: Option<u64>| LogEntry {
             timestamp: now,
-            entry_type: EntryType::Info,
-            message: "No agents here".to_string(),
+            entry_type,
+            message: "timed".to_string(),
             agent_name: None,
-            duration_ms: None,
-        }];
-
-        let session = LogSession {
-            id: "no-agent-session".to_string(),
-            entries,
-            start_time: now,
-            end_time: Some(now + Duration::seconds(10)),
+            duration_ms,
+            fields: HashMap::new(),
+            byte_offset: None,
         };
 
-        let result = analyzer.analyze(&session);
-        assert!(result.is_ok());
+        let entries = vec![
+            entry(EntryType::AgentInvocation, Some(100)),
+            entry(EntryType::AgentInvocation, Some(300)),
+            entry(EntryType::AgentInvocation, None),
+            entry(EntryType::Decision, Some(50)),
+            entry(EntryType::Info, None),
+        ];
+        let session = LogSession::from_entries("per-type", entries);
 
-        let analysis = result.unwrap();
-        let has_no_agent = analysis
-            .patterns
+        let stats = TimingAnalyzer::new().analyze(&session).unwrap();
+
+        assert_eq!(
+            stats.per_type_avg_duration,
+            HashMap::from([(EntryType::AgentInvocation, 200.0), (EntryType::Decision, 50.0)])
+        );
+    }
+
+    fn agent_messages(lines: &[(&str, &str)]) -> LogSession {
+        let now = Utc::now();
+        let entries = lines
             .iter()
-            .any(|p| matches!(p, LogPattern::NoAgentActivity));
+            .enumerate()
+            .map(|(i, (agent, message))| LogEntry {
+                timestamp: now + Duration::seconds(i as i64),
+                entry_type: EntryType::AgentInvocation,
+                message: message.to_string(),
+                agent_name: (!agent.is_empty()).then(|| agent.to_string()),
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
 
-        assert!(has_no_agent);
+        LogSession::from_entries("failures", entries)
     }
 
     #[test]
-    fn test_analyzer_trait_polymorphism() {
-        // Demonstrates trait usage
-        let timing: Box<dyn Analyzer<Output = TimingStats>> = Box::new(TimingAnalyzer::new());
-        let session = create_test_session();
-
-        let result = timing.analyze(&session);
-        assert!(result.is_ok());
-        assert_eq!(timing.name(), "TimingAnalyzer");
+    fn test_failure_analyzer_counts_per_agent() {
+        let session = agent_messages(&[
+            ("builder", "build completed"),
+            ("builder", "Build FAILED: missing crate"),
+            ("tester", "Request Timeout after 30s"),
+            ("builder", "step cancelled by user"),
+            ("builder", "compile error in main.rs"),
+            ("builder", "retry failed"),
+            ("reviewer", "review approved"),
+            ("", "upstream error with no agent"),
+        ]);
+
+        let failures = FailureAnalyzer::new().analyze(&session).unwrap();
+
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].agent, "builder");
+        assert_eq!(failures[0].failure_count, 4);
+        assert_eq!(
+            failures[0].sample_messages,
+            vec!["Build FAILED: missing crate", "step cancelled by user", "compile error in main.rs"]
+        );
+        assert_eq!(failures[1].agent, "tester");
+        assert_eq!(failures[1].failure_count, 1);
     }
 
     #[test]
-    fn test_zero_entries_session() {
-        let analyzer = TimingAnalyzer::new();
-        let session = LogSession {
-            id: "empty".to_string(),
-            entries: vec![],
-            start_time: Utc::now(),
-            end_time: None,
-        };
+    fn test_failure_analyzer_custom_keywords() {
+        let session = agent_messages(&[("builder", "OOM killed"), ("builder", "build failed")]);
+
+        let failures = FailureAnalyzer::with_keywords(&["oom"], 1).analyze(&session).unwrap();
+
+        assert_eq!(
+            failures,
+            vec![AgentFailure {
+                agent: "builder".to_string(),
+                failure_count: 1,
+                sample_messages: vec!["OOM killed".to_string()],
+            }]
+        );
+    }
 
-        let result = analyzer.analyze(&session);
-        assert!(result.is_ok());
+    fn error_cluster(count: i64) -> LogSession {
+        let now = Utc::now();
+        let entries = (0..count)
+            .map(|i| LogEntry {
+                timestamp: now + Duration::milliseconds(i * 100),
+                entry_type: EntryType::Error,
+                message: format!("failure {}", i),
+                agent_name: None,
+                duration_ms: None,
+                fields: HashMap::new(),
+                byte_offset: None,
+            })
+            .collect();
 
-        let stats = result.unwrap();
-        assert_eq!(stats.entry_count, 0);
-        assert_eq!(stats.total_duration_secs, 0.0);
+        LogSession::from_entries("cluster", entries)
+    }
+
+    fn burst_counts(analyzer: &PatternAnalyzer, session: &LogSession) -> Vec<usize> {
+        analyzer
+            .analyze(session)
+            .unwrap()
+            .patterns
+            .iter()
+            .filter_map(|p| match p {
+                LogPattern::ErrorBurst { count, .. } => Some(*count),
+                _ => None,
+            })
+            .collect()
     }
 
     #[test]
-    fn test_single_entry_session() {
-        let analyzer = TimingAnalyzer::new();
-        let now = Utc::now();
+    fn test_burst_window_size() {
+        let analyzer = PatternAnalyzer::new().with_burst_window(5);
 
-        let session = LogSession {
-            id: "single".to_string(),
-            entries: vec![LogEntry {
-                timestamp: now,
-                entry_type: EntryType::Info,
-                message: "Only one".to_string(),
-                agent_name: None,
-                duration_ms: None,
-            }],
-            start_time: now,
-            end_time: None,
-        };
+        assert_eq!(burst_counts(&analyzer, &error_cluster(5)), vec![5]);
+        assert!(burst_counts(&analyzer, &error_cluster(4)).is_empty());
 
-        let result = analyzer.analyze(&session);
-        assert!(result.is_ok());
+        // The default three-error window still catches the smaller cluster
+        assert_eq!(burst_counts(&PatternAnalyzer::new(), &error_cluster(4)), vec![4]);
+    }
 
-        let stats = result.unwrap();
-        assert_eq!(stats.entry_count, 1);
-        assert_eq!(stats.avg_time_between_entries, 0.0);
+    #[test]
+    fn test_burst_window_below_two_is_clamped() {
+        let analyzer = PatternAnalyzer::new().with_burst_window(0);
+        assert_eq!(burst_counts(&analyzer, &error_cluster(2)), vec![2]);
     }
 }
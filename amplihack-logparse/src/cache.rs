@@ -0,0 +1,203 @@
+// Parse cache for unchanged log files
+//
+// Parsed entries are stored as JSON in a `<file>.cache` sidecar next to
+// each log, keyed on the log's modified time and byte size plus the parse
+// options that change what gets parsed. A cache whose key no longer
+// matches the file or the options is ignored and rewritten.
+//
+// Demonstrates:
+// - Serde round-trips of owned data
+// - Treating a cache as best-effort (failures fall back to parsing)
+
+use crate::error::ParseResult;
+use crate::{log_debug, log_warn};
+use crate::parser::{parse_log_file_with_options, ParseOptions};
+use crate::types::LogEntry;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// File extension appended to a log's name for its cache sidecar
+pub const CACHE_EXTENSION: &str = "cache";
+
+/// Identity of a log file's contents, and of how they were parsed, as
+/// seen by the cache
+///
+/// Agent aliases are left out: cached names are re-canonicalized on load.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CacheKey {
+    modified_nanos: u128,
+    size: u64,
+    timestamp_format: Option<String>,
+    strip_ansi: bool,
+    strict_levels: bool,
+}
+
+impl CacheKey {
+    fn new(path: &Path, options: &ParseOptions) -> ParseResult<Self> {
+        let metadata = std::fs::metadata(path)?;
+        let modified_nanos = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+
+        Ok(Self {
+            modified_nanos,
+            size: metadata.len(),
+            timestamp_format: options.timestamp_format().map(str::to_string),
+            strip_ansi: options.strip_ansi(),
+            strict_levels: options.strict_levels(),
+        })
+    }
+}
+
+/// On-disk layout of a cache sidecar
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    key: CacheKey,
+    entries: Vec<LogEntry>,
+}
+
+/// Path of the cache sidecar for a log file (`session.log.cache`)
+pub fn cache_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(CACHE_EXTENSION);
+    path.with_file_name(name)
+}
+
+/// Parse a log file, reusing its cache sidecar when still fresh
+///
+/// On a miss the file is parsed normally and the cache rewritten. Failing
+/// to write the cache (e.g. a read-only directory) only prints a warning.
+pub fn parse_log_file_cached(path: &Path, options: &ParseOptions) -> ParseResult<Vec<LogEntry>> {
+    if let Some(entries) = load_cached(path, options)? {
+        log_debug!("Using cached entries for {}", path.display());
+        return Ok(entries);
+    }
+
+    let entries = parse_log_file_with_options(path, options)?;
+
+    if let Err(e) = store_cache(path, &entries, options) {
+        log_warn!("Failed to write cache for {}: {}", path.display(), e);
+    }
+
+    Ok(entries)
+}
+
+/// Load cached entries if the sidecar matches the file's current state
+/// and was written with the same parse options
+///
+/// Returns `Ok(None)` for a missing, stale, or unreadable cache. Agent
+/// names are re-canonicalized with `options`, since the aliases may have
+/// changed since the cache was written.
+pub fn load_cached(path: &Path, options: &ParseOptions) -> ParseResult<Option<Vec<LogEntry>>> {
+    let key = CacheKey::new(path, options)?;
+
+    let Ok(file) = File::open(cache_path(path)) else {
+        return Ok(None);
+    };
+
+    match serde_json::from_reader::<_, CacheFile>(BufReader::new(file)) {
+        Ok(cache) if cache.key == key => {
+            let mut entries = cache.entries;
+            for name in entries.iter_mut().filter_map(|e| e.agent_name.as_mut()) {
+                *name = options.canonical_agent_name(name);
+            }
+            Ok(Some(entries))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn store_cache(path: &Path, entries: &[LogEntry], options: &ParseOptions) -> ParseResult<()> {
+    let cache = CacheFile {
+        key: CacheKey::new(path, options)?,
+        entries: entries.to_vec(),
+    };
+
+    let mut writer = BufWriter::new(File::create(cache_path(path))?);
+    serde_json::to_writer(&mut writer, &cache)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "[2025-10-18T14:30:45Z] INFO: Starting analysis\n\
+                       [2025-10-18T14:30:46Z] AGENT: architect completed in 1234ms\n";
+
+    #[test]
+    fn test_cache_path_appends_extension() {
+        assert_eq!(
+            cache_path(Path::new("/logs/session.log")),
+            PathBuf::from("/logs/session.log.cache")
+        );
+    }
+
+    #[test]
+    fn test_second_parse_loads_from_cache() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        std::fs::write(&path, LOG).unwrap();
+
+        assert!(load_cached(&path, &options).unwrap().is_none());
+
+        let first = parse_log_file_cached(&path, &options).unwrap();
+        assert!(cache_path(&path).exists());
+
+        let cached = load_cached(&path, &options).unwrap().expect("cache should be fresh");
+        assert_eq!(cached.len(), first.len());
+        assert_eq!(cached[1].agent_name, first[1].agent_name);
+
+        let second = parse_log_file_cached(&path, &options).unwrap();
+        assert_eq!(second.len(), first.len());
+    }
+
+    #[test]
+    fn test_modifying_file_invalidates_cache() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        std::fs::write(&path, LOG).unwrap();
+
+        assert_eq!(parse_log_file_cached(&path, &options).unwrap().len(), 2);
+
+        let mut appended = LOG.to_string();
+        appended.push_str("[2025-10-18T14:30:47Z] ERROR: Something failed\n");
+        std::fs::write(&path, appended).unwrap();
+
+        assert!(load_cached(&path, &options).unwrap().is_none());
+        assert_eq!(parse_log_file_cached(&path, &options).unwrap().len(), 3);
+        assert!(load_cached(&path, &options).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_changing_parse_options_invalidates_cache() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        let mut log = LOG.to_string();
+        log.push_str("[2025-10-18T14:30:47Z] FATAL: boom\n");
+        std::fs::write(&path, log).unwrap();
+
+        assert_eq!(parse_log_file_cached(&path, &options).unwrap().len(), 3);
+
+        let strict = ParseOptions::new().with_strict_levels(true);
+        assert!(load_cached(&path, &strict).unwrap().is_none());
+        assert_eq!(parse_log_file_cached(&path, &strict).unwrap().len(), 2);
+        assert!(load_cached(&path, &options).unwrap().is_none());
+
+        let formatted = ParseOptions::new().with_timestamp_format("%d/%m/%Y %H:%M:%S").unwrap();
+        assert!(load_cached(&path, &formatted).unwrap().is_none());
+
+        let stripped = ParseOptions::new().with_strip_ansi(true);
+        assert!(load_cached(&path, &stripped).unwrap().is_none());
+    }
+}
@@ -0,0 +1,381 @@
+// Configuration file support
+//
+// Defaults for the command-line flags can be kept in an
+// `amplihack-logparse.toml` file:
+//
+//   logs_dir = ".claude/runtime/logs"
+//   format = "markdown"
+//
+//   [thresholds]
+//   error_burst = 5.0
+//   long_gap = 300.0
+//   agent_activity = 10
+//
+//   [agent_aliases]
+//   arch = "architect"
+//
+// The parser is hand-written because the `toml` crate isn't available to
+// this build. It understands only the subset of TOML this file needs:
+//
+// - `[table]` headers (dotted names allowed) and `key = value` pairs
+// - basic strings on one line, with the `\"`, `\\`, `\n`, and `\t` escapes
+// - integers (`_` separators allowed), floats, and `true` / `false`
+// - `#` comments, whole-line or trailing
+//
+// Anything else is rejected with `ParseError::InvalidConfig` rather than
+// misread, including inline tables (`{ ... }`), arrays, literal strings
+// (`'...'`), multi-line strings (`"""` / `'''`), and arrays of tables
+// (`[[...]]`).
+//
+// Demonstrates:
+// - Hand-written line-oriented parsing
+// - Option fields so absent values fall through to CLI defaults
+
+use crate::error::{ParseError, ParseResult};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// File name searched for in the working directory and then `$HOME`
+pub const CONFIG_FILE_NAME: &str = "amplihack-logparse.toml";
+
+/// Key prefix of entries in the `[agent_aliases]` table
+const AGENT_ALIASES_TABLE: &str = "agent_aliases.";
+
+/// Defaults loaded from a config file; `None` means "not set"
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub logs_dir: Option<PathBuf>,
+
+    /// Output format name, as accepted by `--format`
+    pub format: Option<String>,
+
+    pub error_burst_threshold: Option<f64>,
+    pub long_gap_threshold: Option<f64>,
+    pub agent_activity_threshold: Option<usize>,
+
+    /// Alternate agent names mapped to their canonical name
+    pub agent_aliases: HashMap<String, String>,
+}
+
+/// A scalar TOML value
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+}
+
+impl Config {
+    /// Load the config from an explicit path, or search the default locations
+    ///
+    /// An explicit path must exist. When searching, a missing file simply
+    /// yields the empty default config.
+    pub fn load_or_discover(explicit: Option<&Path>) -> ParseResult<Self> {
+        if let Some(path) = explicit {
+            return Self::load(path);
+        }
+
+        match Self::discover() {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Find `amplihack-logparse.toml` in the current directory, then `$HOME`
+    pub fn discover() -> Option<PathBuf> {
+        let cwd = std::env::current_dir().ok();
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+
+        cwd.into_iter()
+            .chain(home)
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|path| path.is_file())
+    }
+
+    /// Read and parse a config file
+    pub fn load(path: &Path) -> ParseResult<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|_| ParseError::FileNotFound(path.to_path_buf()))?;
+
+        Self::parse(&contents).map_err(|e| match e {
+            ParseError::InvalidConfig(details) => {
+                ParseError::InvalidConfig(format!("{}: {}", path.display(), details))
+            }
+            other => other,
+        })
+    }
+
+    /// Parse config file contents
+    pub fn parse(contents: &str) -> ParseResult<Self> {
+        let values = parse_toml(contents)?;
+        let mut config = Self::default();
+
+        for (key, value) in values {
+            match (key.as_str(), value) {
+                ("logs_dir", Value::String(s)) => config.logs_dir = Some(PathBuf::from(s)),
+                ("format", Value::String(s)) => config.format = Some(s),
+                ("thresholds.error_burst", v) => {
+                    config.error_burst_threshold = Some(as_float(&key, v)?)
+                }
+                ("thresholds.long_gap", v) => config.long_gap_threshold = Some(as_float(&key, v)?),
+                ("thresholds.agent_activity", Value::Integer(n)) if n >= 0 => {
+                    config.agent_activity_threshold = Some(n as usize)
+                }
+                (name, Value::String(canonical)) if name.starts_with(AGENT_ALIASES_TABLE) => {
+                    let alias = &name[AGENT_ALIASES_TABLE.len()..];
+                    config.agent_aliases.insert(alias.to_string(), canonical);
+                }
+                (_, value) => {
+                    return Err(ParseError::InvalidConfig(format!(
+                        "unexpected key '{}' with value {:?}",
+                        key, value
+                    )))
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Accept integers where floats are expected (`long_gap = 300`)
+fn as_float(key: &str, value: Value) -> ParseResult<f64> {
+    match value {
+        Value::Float(f) => Ok(f),
+        Value::Integer(n) => Ok(n as f64),
+        other => Err(ParseError::InvalidConfig(format!(
+            "expected a number for '{}', got {:?}",
+            key, other
+        ))),
+    }
+}
+
+/// Parse TOML into dotted keys (`table.key`) and scalar values
+fn parse_toml(contents: &str) -> ParseResult<HashMap<String, Value>> {
+    let mut values = HashMap::new();
+    let mut table = String::new();
+
+    for (idx, raw) in contents.lines().enumerate() {
+        let line_num = idx + 1;
+        let line = strip_comment(raw).trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let invalid = |details: &str| {
+            ParseError::InvalidConfig(format!("line {}: {}", line_num, details))
+        };
+
+        if line.starts_with("[[") {
+            return Err(invalid("arrays of tables ([[...]]) are not supported"));
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .ok_or_else(|| invalid("unterminated table header"))?;
+            table = name.trim().to_string();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| invalid("expected 'key = value'"))?;
+
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(invalid("empty key"));
+        }
+
+        let value = value.trim();
+        if let Some(kind) = unsupported_value_kind(value) {
+            return Err(invalid(&format!("{} are not supported", kind)));
+        }
+
+        let value = parse_value(value).ok_or_else(|| invalid("unsupported value"))?;
+        let full_key = if table.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", table, key)
+        };
+
+        values.insert(full_key, value);
+    }
+
+    Ok(values)
+}
+
+/// Drop a trailing `#` comment that isn't inside a string
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut chars = line.char_indices();
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            // Skip the escaped character so `\"` doesn't end the string
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+/// Name the TOML value syntax outside the supported subset, if `raw` uses it
+fn unsupported_value_kind(raw: &str) -> Option<&'static str> {
+    if raw.starts_with("\"\"\"") || raw.starts_with("'''") {
+        Some("multi-line strings")
+    } else if raw.starts_with('\'') {
+        Some("literal strings")
+    } else if raw.starts_with('{') {
+        Some("inline tables")
+    } else if raw.starts_with('[') {
+        Some("arrays")
+    } else {
+        None
+    }
+}
+
+fn parse_value(raw: &str) -> Option<Value> {
+    if let Some(inner) = raw.strip_prefix('"').and_then(|r| r.strip_suffix('"')) {
+        return unescape(inner).map(Value::String);
+    }
+
+    match raw {
+        "true" => return Some(Value::Boolean(true)),
+        "false" => return Some(Value::Boolean(false)),
+        _ => {}
+    }
+
+    let number = raw.replace('_', "");
+    if let Ok(n) = number.parse::<i64>() {
+        return Some(Value::Integer(n));
+    }
+    number.parse::<f64>().ok().map(Value::Float)
+}
+
+/// Resolve the basic-string escapes `\"`, `\\`, `\n`, and `\t`
+fn unescape(inner: &str) -> Option<String> {
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // An unescaped quote means the string ended early
+            '"' => return None,
+            '\\' => {}
+            _ => {
+                out.push(c);
+                continue;
+            }
+        }
+
+        match chars.next()? {
+            '"' => out.push('"'),
+            '\\' => out.push('\\'),
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            _ => return None,
+        }
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_config() {
+        let config = Config::parse(
+            r#"
+            # Defaults for amplihack-logparse
+            logs_dir = "/var/log/amplihack"  # trailing comment
+            format = "json"
+
+            [thresholds]
+            error_burst = 2.5
+            long_gap = 60
+            agent_activity = 3
+
+            [agent_aliases]
+            arch = "architect"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                logs_dir: Some(PathBuf::from("/var/log/amplihack")),
+                format: Some("json".to_string()),
+                error_burst_threshold: Some(2.5),
+                long_gap_threshold: Some(60.0),
+                agent_activity_threshold: Some(3),
+                agent_aliases: HashMap::from([("arch".to_string(), "architect".to_string())]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_string_with_hash_and_escapes() {
+        let config = Config::parse(r#"logs_dir = "logs/#1 \"main\"""#).unwrap();
+        assert_eq!(config.logs_dir, Some(PathBuf::from("logs/#1 \"main\"")));
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_keys_and_bad_lines() {
+        assert!(matches!(
+            Config::parse("colour = \"red\""),
+            Err(ParseError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            Config::parse("[thresholds\nlong_gap = 1"),
+            Err(ParseError::InvalidConfig(details)) if details.starts_with("line 1")
+        ));
+    }
+
+    /// The `InvalidConfig` details for contents expected to be rejected
+    fn rejection(contents: &str) -> String {
+        match Config::parse(contents) {
+            Err(ParseError::InvalidConfig(details)) => details,
+            other => panic!("expected InvalidConfig for {:?}, got {:?}", contents, other),
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_syntax_outside_subset() {
+        assert_eq!(
+            rejection("[thresholds]\nlong_gap = { value = 60 }"),
+            "line 2: inline tables are not supported"
+        );
+        assert_eq!(
+            rejection("format = \"\"\"\njson\n\"\"\""),
+            "line 1: multi-line strings are not supported"
+        );
+        assert_eq!(
+            rejection("format = '''json'''"),
+            "line 1: multi-line strings are not supported"
+        );
+        assert_eq!(
+            rejection("[[agent_aliases]]\narch = \"architect\""),
+            "line 1: arrays of tables ([[...]]) are not supported"
+        );
+        assert_eq!(rejection("format = 'json'"), "line 1: literal strings are not supported");
+        assert_eq!(rejection("logs_dir = [\"a\", \"b\"]"), "line 1: arrays are not supported");
+        assert_eq!(rejection("format = \"js\"on\""), "line 1: unsupported value");
+    }
+
+    #[test]
+    fn test_load_missing_explicit_file() {
+        let result = Config::load_or_discover(Some(Path::new("/nonexistent/config.toml")));
+        assert!(matches!(result, Err(ParseError::FileNotFound(_))));
+    }
+}
@@ -21,6 +21,14 @@ pub enum ParseError {
     #[error("Invalid timestamp format: {0}")]
     InvalidTimestamp(String),
 
+    /// Invalid user-supplied strftime format
+    #[error("Invalid timestamp format string: {0}")]
+    InvalidTimestampFormat(String),
+
+    /// Config file could not be understood
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+
     /// Malformed log entry
     #[error("Malformed log entry at line {line}: {details}")]
     MalformedEntry {
@@ -28,6 +36,41 @@ pub enum ParseError {
         details: String,
     },
 
+    /// Too many malformed lines for strict parsing
+    #[error("Too many malformed lines: {errors} exceeds budget of {max_errors}")]
+    ErrorBudgetExceeded {
+        errors: usize,
+        max_errors: usize,
+    },
+
+    /// A log file failed validation
+    #[error("Validation failed: {failures} malformed lines")]
+    ValidationFailed {
+        failures: usize,
+    },
+
+    /// Entries at or above a severity the caller asked to fail on
+    #[error("Found {count} entries at or above level {level}")]
+    SeverityThreshold {
+        level: String,
+        count: usize,
+    },
+
+    /// Invalid regular expression supplied by the user
+    #[error("Invalid regex pattern '{pattern}': {details}")]
+    InvalidPattern {
+        pattern: String,
+        details: String,
+    },
+
+    /// Fetching a log over HTTP failed
+    #[error("HTTP error: {0}")]
+    Http(String),
+
+    /// Export to an external store failed
+    #[error("Export failed: {0}")]
+    Export(String),
+
     /// IO error (automatically converted from std::io::Error)
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
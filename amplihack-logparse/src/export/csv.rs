@@ -0,0 +1,159 @@
+// CSV export for agent statistics
+//
+// The csv crate isn't available to this build, so fields are quoted here
+// following RFC 4180.
+//
+// Demonstrates:
+// - Generic writers (any std::io::Write)
+// - RFC 4180 quoting for fields containing commas, quotes, or newlines
+
+use crate::error::ParseResult;
+use crate::types::AgentStats;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Header row for agent statistics CSV output
+pub const AGENT_STATS_HEADER: &str = "name,invocation_count,total_duration_ms,avg_duration_ms";
+
+/// Write agent statistics as CSV to a file
+///
+/// Rows are written in the order given.
+pub fn export_agent_stats_csv(stats: &[AgentStats], path: &Path) -> ParseResult<()> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    write_agent_stats_csv(&mut writer, stats)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write agent statistics as CSV to any writer
+///
+/// Demonstrates:
+/// - Borrowing: Takes a slice of stats
+/// - Generics: W can be a file, Vec<u8>, or stdout
+pub fn write_agent_stats_csv<W: Write>(writer: &mut W, stats: &[AgentStats]) -> ParseResult<()> {
+    writeln!(writer, "{}", AGENT_STATS_HEADER)?;
+
+    for agent in stats {
+        writeln!(
+            writer,
+            "{},{},{},{}",
+            escape_field(&agent.name),
+            agent.invocation_count,
+            agent.total_duration_ms,
+            agent.avg_duration_ms
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Quote a field if it contains a delimiter, quote, or line break
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(name: &str, durations: &[u64]) -> AgentStats {
+        let mut stats = AgentStats::new(name.to_string());
+        for &duration in durations {
+            stats.add_duration(duration);
+        }
+        stats
+    }
+
+    /// Split RFC 4180 text into records of unquoted fields
+    fn read_csv(text: &str) -> Vec<Vec<String>> {
+        let mut records = Vec::new();
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match (c, in_quotes) {
+                ('"', true) if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                ('"', _) => in_quotes = !in_quotes,
+                (',', false) => record.push(std::mem::take(&mut field)),
+                ('\n', false) => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                _ => field.push(c),
+            }
+        }
+
+        records
+    }
+
+    #[test]
+    fn test_escape_field() {
+        assert_eq!(escape_field("architect"), "architect");
+        assert_eq!(escape_field("a,b"), "\"a,b\"");
+        assert_eq!(escape_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(escape_field("crlf\r\n"), "\"crlf\r\n\"");
+        assert_eq!(escape_field("\""), "\"\"\"\"");
+        assert_eq!(escape_field(""), "");
+    }
+
+    #[test]
+    fn test_awkward_names_round_trip() {
+        let names = [
+            "plain",
+            "comma, inside",
+            "\"quoted\" name",
+            "multi\nline",
+            "all \"three\",\nat once",
+            "trailing quote\"",
+        ];
+        let agents: Vec<AgentStats> = names.iter().map(|name| stats(name, &[10])).collect();
+
+        let mut out = Vec::new();
+        write_agent_stats_csv(&mut out, &agents).unwrap();
+        let records = read_csv(&String::from_utf8(out).unwrap());
+
+        // One header plus one record per agent, each with four fields
+        assert_eq!(records.len(), 1 + names.len());
+        assert!(records.iter().all(|record| record.len() == 4));
+
+        let read_names: Vec<&str> = records[1..].iter().map(|r| r[0].as_str()).collect();
+        assert_eq!(read_names, names);
+        assert!(records[1..].iter().all(|r| r[1..] == ["1", "10", "10"]));
+    }
+
+    #[test]
+    fn test_export_agent_stats_csv_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agents.csv");
+
+        let agents = vec![
+            stats("architect", &[100, 200]),
+            stats("builder, senior", &[50]),
+        ];
+        export_agent_stats_csv(&agents, &path).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                AGENT_STATS_HEADER,
+                "architect,2,300,150",
+                "\"builder, senior\",1,50,50",
+            ]
+        );
+    }
+}
@@ -0,0 +1,88 @@
+// Folded-stack export of agent durations
+//
+// Produces the collapsed-stack format read by `flamegraph.pl` and
+// `inferno-flamegraph`, one line per agent:
+//   session;architect 1500
+//
+// Demonstrates:
+// - Aggregating into a BTreeMap for deterministic output
+// - Building a String with fmt::Write
+
+use crate::types::LogEntry;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Root frame shared by every stack
+pub const ROOT_FRAME: &str = "session";
+
+/// Render total `duration_ms` per agent as folded stacks
+///
+/// Entries without an agent or a duration are skipped. Agents are listed
+/// in name order.
+pub fn render_folded(entries: &[LogEntry]) -> String {
+    let mut totals: BTreeMap<&str, u64> = BTreeMap::new();
+
+    for entry in entries {
+        if let (Some(agent), Some(duration_ms)) = (&entry.agent_name, entry.duration_ms) {
+            *totals.entry(agent).or_insert(0) += duration_ms;
+        }
+    }
+
+    let mut out = String::new();
+    for (agent, total_ms) in totals {
+        // Writing to a String cannot fail, so results are ignored
+        let _ = writeln!(out, "{};{} {}", ROOT_FRAME, escape_frame(agent), total_ms);
+    }
+
+    out
+}
+
+/// Replace the frame separator so a name stays a single frame
+fn escape_frame(name: &str) -> String {
+    name.replace(';', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EntryType;
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    fn entry(agent: Option<&str>, duration_ms: Option<u64>) -> LogEntry {
+        LogEntry {
+            timestamp: Utc::now(),
+            entry_type: EntryType::AgentInvocation,
+            message: "done".to_string(),
+            agent_name: agent.map(String::from),
+            duration_ms,
+            fields: HashMap::new(),
+            byte_offset: None,
+        }
+    }
+
+    #[test]
+    fn test_render_folded_sums_per_agent() {
+        let entries = [
+            entry(Some("builder"), Some(200)),
+            entry(Some("architect"), Some(1000)),
+            entry(Some("builder"), Some(300)),
+            entry(Some("architect"), Some(500)),
+            entry(Some("reviewer"), None),
+            entry(None, Some(999)),
+        ];
+
+        assert_eq!(
+            render_folded(&entries),
+            "session;architect 1500\nsession;builder 500\n"
+        );
+    }
+
+    #[test]
+    fn test_render_folded_escapes_separator() {
+        let entries = [entry(Some("a;b"), Some(10))];
+
+        assert_eq!(render_folded(&entries), "session;a_b 10\n");
+        assert_eq!(render_folded(&[]), "");
+    }
+}
@@ -0,0 +1,131 @@
+// InfluxDB line-protocol export for log entries
+//
+// Each entry becomes one line:
+//   amplihack_log,entry_type=agent,agent=architect duration_ms=1234i,message="done" 1760797845000000000
+//
+// Demonstrates:
+// - Building a String with fmt::Write
+// - Context-specific escaping rules
+
+use crate::types::{EntryType, LogEntry};
+use std::fmt::Write;
+
+/// Measurement name used for every exported line
+pub const MEASUREMENT: &str = "amplihack_log";
+
+/// Render entries as InfluxDB line protocol, one line per entry
+///
+/// The `agent` tag is omitted when an entry has no agent, and the
+/// `duration_ms` field when it has no duration. Every line carries a
+/// `message` field so it always has at least one field.
+pub fn render_line_protocol(entries: &[LogEntry]) -> String {
+    let mut out = String::new();
+
+    for entry in entries {
+        // Writing to a String cannot fail, so results are ignored
+        let _ = write!(
+            out,
+            "{},entry_type={}",
+            escape_measurement(MEASUREMENT),
+            entry_type_tag(entry.entry_type)
+        );
+        if let Some(agent) = &entry.agent_name {
+            let _ = write!(out, ",agent={}", escape_tag(agent));
+        }
+
+        out.push(' ');
+        if let Some(duration_ms) = entry.duration_ms {
+            let _ = write!(out, "duration_ms={}i,", duration_ms);
+        }
+        let _ = write!(out, "message=\"{}\"", escape_string_field(&entry.message));
+
+        let _ = writeln!(out, " {}", entry.timestamp.timestamp_nanos_opt().unwrap_or_default());
+    }
+
+    out
+}
+
+/// Tag value for an entry type, matching the names used by `--type`
+fn entry_type_tag(entry_type: EntryType) -> &'static str {
+    match entry_type {
+        EntryType::AgentInvocation => "agent",
+        EntryType::Info => "info",
+        EntryType::Warning => "warning",
+        EntryType::Error => "error",
+        EntryType::Decision => "decision",
+        EntryType::Debug => "debug",
+        EntryType::Trace => "trace",
+        EntryType::Unknown => "unknown",
+    }
+}
+
+/// Escape commas and spaces in a measurement name
+fn escape_measurement(value: &str) -> String {
+    value.replace(',', "\\,").replace(' ', "\\ ")
+}
+
+/// Escape commas, equals signs, and spaces in a tag key or value
+fn escape_tag(value: &str) -> String {
+    value
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+/// Escape backslashes, double quotes, and newlines in a string field value
+fn escape_string_field(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_timestamp;
+    use std::collections::HashMap;
+
+    fn entry(
+        entry_type: EntryType,
+        message: &str,
+        agent: Option<&str>,
+        duration_ms: Option<u64>,
+    ) -> LogEntry {
+        LogEntry {
+            timestamp: parse_timestamp("2025-10-18T14:30:45Z").unwrap(),
+            entry_type,
+            message: message.to_string(),
+            agent_name: agent.map(String::from),
+            duration_ms,
+            fields: HashMap::new(),
+            byte_offset: None,
+        }
+    }
+
+    #[test]
+    fn test_render_representative_line() {
+        let entries = [entry(
+            EntryType::Error,
+            "build \"failed\"",
+            Some("senior architect,v2"),
+            Some(1234),
+        )];
+
+        assert_eq!(
+            render_line_protocol(&entries),
+            "amplihack_log,entry_type=error,agent=senior\\ architect\\,v2 \
+             duration_ms=1234i,message=\"build \\\"failed\\\"\" 1760797845000000000\n"
+        );
+    }
+
+    #[test]
+    fn test_render_without_agent_or_duration() {
+        let entries = [entry(EntryType::Info, "hello", None, None)];
+
+        assert_eq!(
+            render_line_protocol(&entries),
+            "amplihack_log,entry_type=info message=\"hello\" 1760797845000000000\n"
+        );
+    }
+}
@@ -0,0 +1,9 @@
+// Export module
+//
+// Writers that turn parsed entries and analysis results into formats
+// consumed by other tools (spreadsheets, databases, dashboards).
+
+pub mod csv;
+pub mod folded;
+pub mod influx;
+pub mod sqlite;
@@ -0,0 +1,183 @@
+// SQLite export for parsed log entries
+//
+// Demonstrates:
+// - Generating a SQL script with a generic writer
+// - Driving an external process (the `sqlite3` CLI) through stdin
+//
+// The script is applied by the `sqlite3` command-line shell rather than a
+// linked library, so `sqlite3` must be on PATH at runtime.
+
+use crate::error::{ParseError, ParseResult};
+use crate::types::LogEntry;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Schema for the exported `entries` table
+pub const ENTRIES_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS entries (\
+timestamp TEXT NOT NULL, \
+entry_type TEXT NOT NULL, \
+message TEXT NOT NULL, \
+agent_name TEXT, \
+duration_ms INTEGER)";
+
+/// Column names of the `entries` table, in insertion order
+pub const ENTRIES_COLUMNS: &[&str] =
+    &["timestamp", "entry_type", "message", "agent_name", "duration_ms"];
+
+/// Export entries into the `entries` table of a SQLite database
+///
+/// The table is created if missing and all rows are inserted within a
+/// single transaction, so a failed export leaves the database unchanged.
+pub fn export_to_sqlite(entries: &[LogEntry], db_path: &Path) -> ParseResult<()> {
+    let mut script = Vec::new();
+    write_entries_sql(&mut script, entries)?;
+    run_sqlite(&db_path.to_string_lossy(), &script)?;
+    Ok(())
+}
+
+/// Write the SQL that creates the `entries` table and inserts all entries
+///
+/// Demonstrates:
+/// - Generics: W can be a file, Vec<u8>, or a child process's stdin
+pub fn write_entries_sql<W: Write>(writer: &mut W, entries: &[LogEntry]) -> ParseResult<()> {
+    writeln!(writer, "BEGIN TRANSACTION;")?;
+    writeln!(writer, "{};", ENTRIES_SCHEMA)?;
+
+    for entry in entries {
+        writeln!(
+            writer,
+            "INSERT INTO entries VALUES ({}, {}, {}, {}, {});",
+            quote(&entry.timestamp.to_rfc3339()),
+            quote(&format!("{:?}", entry.entry_type)),
+            quote(&entry.message),
+            entry.agent_name.as_deref().map_or_else(|| "NULL".to_string(), quote),
+            entry
+                .duration_ms
+                .map_or_else(|| "NULL".to_string(), |ms| ms.to_string()),
+        )?;
+    }
+
+    writeln!(writer, "COMMIT;")?;
+    Ok(())
+}
+
+/// Feed a SQL script to `sqlite3` and return its standard output
+fn run_sqlite(db: &str, script: &[u8]) -> ParseResult<String> {
+    let mut child = Command::new("sqlite3")
+        .arg("-bail")
+        .arg(db)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| ParseError::Export(format!("failed to run sqlite3 (is it on PATH?): {}", e)))?;
+
+    // Take stdin so it is closed (EOF) once the script is written
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(script)?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(ParseError::Export(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Quote a string as a SQL literal, doubling embedded single quotes
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EntryType;
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    fn entry(message: &str, agent: Option<&str>, duration_ms: Option<u64>) -> LogEntry {
+        LogEntry {
+            timestamp: Utc::now(),
+            entry_type: EntryType::AgentInvocation,
+            message: message.to_string(),
+            agent_name: agent.map(String::from),
+            duration_ms,
+            fields: HashMap::new(),
+            byte_offset: None,
+        }
+    }
+
+    #[test]
+    fn test_columns_match_schema() {
+        let column_defs = ENTRIES_SCHEMA
+            .split_once('(')
+            .and_then(|(_, rest)| rest.strip_suffix(')'))
+            .unwrap();
+
+        let names: Vec<_> = column_defs
+            .split(", ")
+            .map(|def| def.split_whitespace().next().unwrap())
+            .collect();
+
+        assert_eq!(names, ENTRIES_COLUMNS);
+    }
+
+    #[test]
+    fn test_quote_escapes_single_quotes() {
+        assert_eq!(quote("plain"), "'plain'");
+        assert_eq!(quote("it's"), "'it''s'");
+    }
+
+    #[test]
+    fn test_write_entries_sql_uses_null_for_missing_values() {
+        let mut script = Vec::new();
+        write_entries_sql(&mut script, &[entry("hello", None, None)]).unwrap();
+        let script = String::from_utf8(script).unwrap();
+
+        assert!(script.starts_with("BEGIN TRANSACTION;"));
+        assert!(script.contains("'AgentInvocation', 'hello', NULL, NULL);"));
+        assert!(script.trim_end().ends_with("COMMIT;"));
+    }
+
+    #[test]
+    #[ignore = "requires the sqlite3 CLI on PATH; run with --ignored"]
+    fn test_export_to_in_memory_database() {
+        let entries = vec![
+            entry("architect completed", Some("architect"), Some(120)),
+            entry("it's done", None, None),
+            entry("builder completed", Some("builder"), Some(80)),
+        ];
+
+        let mut script = Vec::new();
+        write_entries_sql(&mut script, &entries).unwrap();
+        script.extend_from_slice(b"SELECT COUNT(*) FROM entries;\n");
+        script.extend_from_slice(b"SELECT agent_name FROM entries WHERE duration_ms = 80;\n");
+
+        let output = run_sqlite(":memory:", &script).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines, vec!["3", "builder"]);
+    }
+
+    #[test]
+    #[ignore = "requires the sqlite3 CLI on PATH; run with --ignored"]
+    fn test_export_to_sqlite_file_appends_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("logs.db");
+        let entries = vec![entry("one", Some("architect"), Some(10))];
+
+        export_to_sqlite(&entries, &db_path).unwrap();
+        export_to_sqlite(&entries, &db_path).unwrap();
+
+        let output = run_sqlite(
+            &db_path.to_string_lossy(),
+            b"SELECT COUNT(*) FROM entries;\n",
+        )
+        .unwrap();
+        assert_eq!(output.trim(), "2");
+    }
+}
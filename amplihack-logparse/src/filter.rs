@@ -0,0 +1,362 @@
+// Composable entry filters
+//
+// `EntryFilter` collects the predicates behind the `query` subcommand so
+// library users can select entries the same way. Every predicate that is
+// set must match; an empty filter matches everything. Excluded agents
+// take precedence: their entries never match, even when an agent filter
+// would include them.
+//
+// Demonstrates:
+// - Consuming builder methods returning Self
+// - Option::is_none_or for "unset matches all" predicates
+
+use crate::error::{ParseError, ParseResult};
+use crate::parser::normalize_agent_name;
+use crate::types::{EntryType, LogEntry};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+/// Message matcher used by `EntryFilter`
+#[derive(Debug, Clone)]
+pub enum TextMatcher {
+    /// Case-insensitive substring (stored lowercased)
+    Substring(String),
+
+    /// Regular expression matched against the raw message
+    Pattern(Regex),
+}
+
+impl TextMatcher {
+    /// Build a matcher, compiling the pattern when `use_regex` is set
+    pub fn new(text: &str, use_regex: bool) -> ParseResult<Self> {
+        if use_regex {
+            Regex::new(text)
+                .map(TextMatcher::Pattern)
+                .map_err(|e| ParseError::InvalidPattern {
+                    pattern: text.to_string(),
+                    details: e.to_string(),
+                })
+        } else {
+            Ok(TextMatcher::Substring(text.to_lowercase()))
+        }
+    }
+
+    pub fn matches(&self, message: &str) -> bool {
+        match self {
+            TextMatcher::Substring(text) => message.to_lowercase().contains(text),
+            TextMatcher::Pattern(re) => re.is_match(message),
+        }
+    }
+}
+
+/// Predicate over log entries, built up with `with_*` methods
+#[derive(Debug, Clone, Default)]
+pub struct EntryFilter {
+    /// Normalized substring the agent name must contain
+    agent: Option<String>,
+
+    /// Canonical agent names whose entries never match
+    excluded_agents: Vec<String>,
+
+    text: Option<TextMatcher>,
+
+    /// Accepted entry types (empty accepts all)
+    types: Vec<EntryType>,
+
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+
+    /// Minimum `duration_ms`; entries without a duration never match
+    min_duration_ms: Option<u64>,
+}
+
+impl EntryFilter {
+    /// Create a filter that matches every entry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match entries whose agent name contains `agent`, compared
+    /// after normalization like excluded agents
+    ///
+    /// Aliases are not resolved here: pass the name through
+    /// `ParseOptions::canonical_agent_name` first to include by alias.
+    pub fn with_agent(mut self, agent: &str) -> Self {
+        self.agent = Some(normalize_agent_name(agent));
+        self
+    }
+
+    /// Never match entries of agent `agent`, compared after normalization;
+    /// repeated calls exclude each agent
+    ///
+    /// Aliases are not resolved here: pass the name through
+    /// `ParseOptions::canonical_agent_name` first to exclude by alias.
+    pub fn with_excluded_agent(mut self, agent: &str) -> Self {
+        let agent = normalize_agent_name(agent);
+        if !self.excluded_agents.contains(&agent) {
+            self.excluded_agents.push(agent);
+        }
+        self
+    }
+
+    /// Whether `agent` is one of the excluded agent names
+    pub fn excludes_agent(&self, agent: &str) -> bool {
+        self.excluded_agents.iter().any(|excluded| excluded == agent)
+    }
+
+    /// Only match messages containing `text`, ignoring case
+    pub fn with_contains(mut self, text: &str) -> Self {
+        self.text = Some(TextMatcher::Substring(text.to_lowercase()));
+        self
+    }
+
+    /// Only match messages accepted by `matcher`, e.g. a compiled regex
+    pub fn with_text_matcher(mut self, matcher: TextMatcher) -> Self {
+        self.text = Some(matcher);
+        self
+    }
+
+    /// Accept entries of `entry_type`; repeated calls accept any of the types
+    pub fn with_type(mut self, entry_type: EntryType) -> Self {
+        if !self.types.contains(&entry_type) {
+            self.types.push(entry_type);
+        }
+        self
+    }
+
+    /// Only match entries within the inclusive `[since, until]` window;
+    /// either bound may be left open
+    pub fn with_time_range(
+        mut self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.since = since;
+        self.until = until;
+        self
+    }
+
+    /// Only match entries whose duration is at least `min_ms`, dropping
+    /// entries without a duration
+    pub fn with_min_duration(mut self, min_ms: u64) -> Self {
+        self.min_duration_ms = Some(min_ms);
+        self
+    }
+
+    /// Whether `entry` satisfies every predicate that has been set
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        if entry.agent_name.as_deref().is_some_and(|name| self.excludes_agent(name)) {
+            return false;
+        }
+
+        let agent_match = self.agent.as_deref().is_none_or(|agent| {
+            entry
+                .agent_name
+                .as_ref()
+                .is_some_and(|name| name.contains(agent))
+        });
+
+        let text_match = self
+            .text
+            .as_ref()
+            .is_none_or(|matcher| matcher.matches(&entry.message));
+
+        let type_match = self.types.is_empty() || self.types.contains(&entry.entry_type);
+
+        let time_match = self.since.is_none_or(|start| entry.timestamp >= start)
+            && self.until.is_none_or(|end| entry.timestamp <= end);
+
+        let duration_match = self
+            .min_duration_ms
+            .is_none_or(|min| entry.duration_ms.is_some_and(|d| d >= min));
+
+        agent_match && text_match && type_match && time_match && duration_match
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::collections::HashMap;
+
+    fn base() -> DateTime<Utc> {
+        "2025-10-18T14:30:00Z".parse().unwrap()
+    }
+
+    fn entry(
+        offset_secs: i64,
+        entry_type: EntryType,
+        agent: Option<&str>,
+        message: &str,
+    ) -> LogEntry {
+        LogEntry {
+            timestamp: base() + Duration::seconds(offset_secs),
+            entry_type,
+            message: message.to_string(),
+            agent_name: agent.map(str::to_string),
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        }
+    }
+
+    fn sample() -> Vec<LogEntry> {
+        vec![
+            entry(0, EntryType::Info, None, "Starting session"),
+            entry(10, EntryType::AgentInvocation, Some("architect"), "Designing"),
+            entry(20, EntryType::Error, Some("builder"), "Build TIMEOUT"),
+            entry(30, EntryType::Warning, Some("builder"), "Retrying build"),
+            entry(40, EntryType::Error, None, "Connection lost"),
+        ]
+    }
+
+    fn matching(filter: &EntryFilter) -> Vec<String> {
+        sample()
+            .into_iter()
+            .filter(|e| filter.matches(e))
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        assert_eq!(matching(&EntryFilter::new()).len(), 5);
+    }
+
+    #[test]
+    fn test_agent_filter() {
+        let filter = EntryFilter::new().with_agent("build");
+        assert_eq!(matching(&filter), vec!["Build TIMEOUT", "Retrying build"]);
+    }
+
+    #[test]
+    fn test_excluded_agents_are_dropped() {
+        let filter = EntryFilter::new().with_excluded_agent("builder");
+        assert_eq!(
+            matching(&filter),
+            vec!["Starting session", "Designing", "Connection lost"]
+        );
+
+        // Names are normalized; repeated flags exclude every agent listed
+        let both = filter
+            .with_excluded_agent("Agent:ARCHITECT")
+            .with_excluded_agent("builder");
+        assert_eq!(matching(&both), vec!["Starting session", "Connection lost"]);
+        assert!(both.excludes_agent("architect"));
+        assert!(!both.excludes_agent("arch"));
+    }
+
+    #[test]
+    fn test_exclude_wins_over_include() {
+        // Include "build" matches both "builder" entries; excluding
+        // "builder" leaves nothing
+        let filter = EntryFilter::new()
+            .with_agent("build")
+            .with_excluded_agent("builder");
+        assert!(matching(&filter).is_empty());
+
+        // Include "e" matches architect and builder; exclude removes one
+        let difference = EntryFilter::new()
+            .with_agent("e")
+            .with_excluded_agent("architect");
+        assert_eq!(matching(&difference), vec!["Build TIMEOUT", "Retrying build"]);
+
+        // Both sides normalize, so the rule holds however names are written
+        let normalized = EntryFilter::new()
+            .with_agent("E")
+            .with_excluded_agent("Agent:Architect");
+        assert_eq!(matching(&normalized), vec!["Build TIMEOUT", "Retrying build"]);
+    }
+
+    #[test]
+    fn test_agent_filter_normalizes_name() {
+        let filter = EntryFilter::new().with_agent("Architect");
+        assert_eq!(matching(&filter), vec!["Designing"]);
+    }
+
+    #[test]
+    fn test_contains_filter_is_case_insensitive() {
+        let filter = EntryFilter::new().with_contains("timeout");
+        assert_eq!(matching(&filter), vec!["Build TIMEOUT"]);
+    }
+
+    #[test]
+    fn test_type_filter_accepts_any_listed_type() {
+        let filter = EntryFilter::new()
+            .with_type(EntryType::Warning)
+            .with_type(EntryType::Info);
+        assert_eq!(matching(&filter), vec!["Starting session", "Retrying build"]);
+    }
+
+    #[test]
+    fn test_time_range_filter() {
+        let since = Some(base() + Duration::seconds(10));
+        let until = Some(base() + Duration::seconds(30));
+
+        let both = EntryFilter::new().with_time_range(since, until);
+        assert_eq!(matching(&both), vec!["Designing", "Build TIMEOUT", "Retrying build"]);
+
+        let open_end = EntryFilter::new().with_time_range(until, None);
+        assert_eq!(matching(&open_end), vec!["Retrying build", "Connection lost"]);
+    }
+
+    #[test]
+    fn test_min_duration_filter() {
+        let mut entries = sample();
+        entries[1].duration_ms = Some(900);
+        entries[2].duration_ms = Some(1_000);
+        entries[3].duration_ms = Some(2_500);
+
+        let filter = EntryFilter::new().with_min_duration(1_000);
+        let slow: Vec<_> = entries
+            .iter()
+            .filter(|e| filter.matches(e))
+            .map(|e| e.message.as_str())
+            .collect();
+
+        // Boundary is inclusive; entries without a duration drop out
+        assert_eq!(slow, vec!["Build TIMEOUT", "Retrying build"]);
+    }
+
+    #[test]
+    fn test_combined_predicates() {
+        let filter = EntryFilter::new()
+            .with_agent("builder")
+            .with_type(EntryType::Error)
+            .with_time_range(Some(base()), None);
+        assert_eq!(matching(&filter), vec!["Build TIMEOUT"]);
+
+        let none = filter.with_contains("lost");
+        assert!(matching(&none).is_empty());
+    }
+
+    #[test]
+    fn test_text_matcher_substring_is_case_insensitive() {
+        let matcher = TextMatcher::new("Timeout", false).unwrap();
+        assert!(matcher.matches("request TIMEOUT after 30s"));
+        assert!(!matcher.matches("request completed"));
+        // Regex syntax is literal in substring mode
+        assert!(!TextMatcher::new("error|timeout", false).unwrap().matches("timeout"));
+    }
+
+    #[test]
+    fn test_text_matcher_regex() {
+        let matcher = TextMatcher::new("^(error|timeout)", true).unwrap();
+        assert!(matcher.matches("timeout waiting for agent"));
+        assert!(matcher.matches("error in builder"));
+        assert!(!matcher.matches("agent timeout"));
+
+        let filter = EntryFilter::new().with_text_matcher(TextMatcher::new("^Re", true).unwrap());
+        assert_eq!(matching(&filter), vec!["Retrying build"]);
+    }
+
+    #[test]
+    fn test_text_matcher_invalid_regex() {
+        let result = TextMatcher::new("(unclosed", true);
+        match result {
+            Err(ParseError::InvalidPattern { pattern, .. }) => assert_eq!(pattern, "(unclosed"),
+            _ => panic!("Expected InvalidPattern error"),
+        }
+    }
+}
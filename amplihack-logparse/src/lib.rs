@@ -0,0 +1,17 @@
+// amplihack-logparse library
+//
+// Exposes the parser, analyzers, and core types so they can be reused
+// outside of the command-line binary.
+
+pub mod analyzer;
+pub mod cache;
+pub mod config;
+pub mod error;
+pub mod export;
+pub mod filter;
+pub mod logging;
+pub mod parser;
+pub mod progress;
+pub mod report;
+pub mod schema;
+pub mod types;
@@ -0,0 +1,136 @@
+// Leveled diagnostics on stderr
+//
+// Warnings and progress notes go through `log_warn!`, `log_info!`, and
+// `log_debug!` so the command-line `--quiet`/`--verbose` flags can hide or
+// reveal them. Results and fatal errors are printed directly and are not
+// affected.
+//
+// Demonstrates:
+// - macro_rules! macros exported with #[macro_export]
+// - A process-wide setting in an AtomicU8
+// - fmt::Arguments to format lazily, only when a record is written
+
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Severity of a diagnostic, from most to least important
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl Level {
+    /// Text written before the message
+    fn prefix(self) -> &'static str {
+        match self {
+            Level::Error => "Error: ",
+            Level::Warn => "Warning: ",
+            Level::Info => "",
+            Level::Debug => "Debug: ",
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Level::Error,
+            1 => Level::Warn,
+            2 => Level::Info,
+            _ => Level::Debug,
+        }
+    }
+}
+
+/// Most verbose level currently written (default `Info`)
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+/// Set the most verbose level written for the rest of the process
+pub fn set_max_level(level: Level) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// The most verbose level currently written
+pub fn max_level() -> Level {
+    Level::from_u8(MAX_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Write a record to stderr if `level` is enabled; used by the macros
+#[doc(hidden)]
+pub fn log(level: Level, args: fmt::Arguments) {
+    // Diagnostics are best-effort; a closed stderr shouldn't abort parsing
+    let _ = write_record(&mut io::stderr().lock(), max_level(), level, args);
+}
+
+/// Write `args` to `out` with the level's prefix, unless `level` is more
+/// verbose than `max_level`
+fn write_record<W: Write>(
+    out: &mut W,
+    max_level: Level,
+    level: Level,
+    args: fmt::Arguments,
+) -> io::Result<()> {
+    if level > max_level {
+        return Ok(());
+    }
+    writeln!(out, "{}{}", level.prefix(), args)
+}
+
+/// Log a warning (`Warning: ...`), hidden by `--quiet`
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        $crate::logging::log($crate::logging::Level::Warn, format_args!($($arg)*))
+    };
+}
+
+/// Log a progress note, hidden by `--quiet`
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => {
+        $crate::logging::log($crate::logging::Level::Info, format_args!($($arg)*))
+    };
+}
+
+/// Log a detail only shown with `--verbose` (`Debug: ...`)
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => {
+        $crate::logging::log($crate::logging::Level::Debug, format_args!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(max_level: Level, level: Level) -> String {
+        let mut out = Vec::new();
+        write_record(&mut out, max_level, level, format_args!("line {}", 3)).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hides_warnings() {
+        assert_eq!(record(Level::Error, Level::Warn), "");
+        assert_eq!(record(Level::Error, Level::Info), "");
+        assert_eq!(record(Level::Error, Level::Error), "Error: line 3\n");
+    }
+
+    #[test]
+    fn test_default_and_verbose_levels() {
+        assert_eq!(record(Level::Info, Level::Warn), "Warning: line 3\n");
+        assert_eq!(record(Level::Info, Level::Info), "line 3\n");
+        assert_eq!(record(Level::Info, Level::Debug), "");
+        assert_eq!(record(Level::Debug, Level::Debug), "Debug: line 3\n");
+    }
+
+    #[test]
+    fn test_level_round_trips_through_u8() {
+        for level in [Level::Error, Level::Warn, Level::Info, Level::Debug] {
+            assert_eq!(Level::from_u8(level as u8), level);
+        }
+    }
+}
@@ -3,419 +3,3766 @@
 // This tool demonstrates Rust's memory safety and ownership system by providing
 // fast, safe parsing of amplihack log files.
 
-mod types;
-mod error;
-mod parser;
-mod analyzer;
-
-use std::path::PathBuf;
-use std::time::Instant;
-use clap::{Parser, Subcommand};
-use chrono::Utc;
-
-use crate::analyzer::{Analyzer, TimingAnalyzer, AgentAnalyzer, PatternAnalyzer};
-use crate::error::ParseResult;
-use crate::parser::parse_log_file;
-use crate::types::{LogSession, EntryType};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use clap::{ArgAction, ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use rayon::prelude::*;
+use serde::Deserialize;
+use chrono::{DateTime, Utc};
+
+use amplihack_logparse::analyzer::{
+    merge_sessions, split_by_markers, split_into_sessions, ActivityHeatmapAnalyzer, AgentAnalyzer,
+    Analyzer,
+    DecisionAnalyzer, PatternAnalyzer, TimingAnalyzer, TransitionAnalyzer, WarningAnalyzer,
+    SESSION_END_MARKER, SESSION_START_MARKER, UNTAGGED_SESSION_ID,
+    DEFAULT_AGENT_ACTIVITY_THRESHOLD, DEFAULT_BURST_WINDOW, DEFAULT_ERROR_BURST_THRESHOLD,
+    DEFAULT_LONG_GAP_THRESHOLD, DEFAULT_STALLED_START_THRESHOLD,
+};
+use amplihack_logparse::analyzer::compare::compare_sessions;
+use amplihack_logparse::cache::parse_log_file_cached;
+use amplihack_logparse::config::Config;
+use amplihack_logparse::error::{ParseError, ParseResult};
+use amplihack_logparse::export::csv::export_agent_stats_csv;
+use amplihack_logparse::export::sqlite::{export_to_sqlite, ENTRIES_COLUMNS};
+use amplihack_logparse::filter::{EntryFilter, TextMatcher};
+use amplihack_logparse::logging::{set_max_level, Level};
+use amplihack_logparse::{log_info, log_warn};
+use amplihack_logparse::progress::ProgressBar;
+use amplihack_logparse::report::AnalysisReport;
+use amplihack_logparse::report::dot::render_dot;
+use amplihack_logparse::report::heatmap::render_heatmap;
+use amplihack_logparse::report::markdown::render_markdown;
+use amplihack_logparse::report::table::render_agent_table;
+use amplihack_logparse::report::timeline::{render_timeline, DEFAULT_TIMELINE_COLUMNS};
+use amplihack_logparse::report::yaml::to_yaml;
+use amplihack_logparse::schema::schema;
+use amplihack_logparse::parser::{
+    dedupe_entries, find_log_files, is_log_file, parse_entry_type, parse_log_entries_streaming,
+    parse_log_file_mmap, parse_log_file_report, parse_log_file_sampled, parse_log_file_strict,
+    parse_log_file_with_options, parse_timestamp, validate_log_file, ParseOptions,
+};
+use amplihack_logparse::parser::follow::LogFollower;
+use amplihack_logparse::parser::recovery::ParseReport;
+#[cfg(feature = "http")]
+use amplihack_logparse::parser::http::parse_log_url;
+use amplihack_logparse::types::{LogEntry, LogSession, EntryType};
+
+/// How often `watch` checks the file for appended data
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Characters of each message shown by `parse` and `query` by default
+const DEFAULT_MESSAGE_WIDTH: usize = 60;
 
 #[derive(Parser)]
 #[command(name = "amplihack-logparse")]
 #[command(about = "High-performance log parser for amplihack session logs", long_about = None)]
 struct Cli {
+    /// Config file with default options (default: ./amplihack-logparse.toml,
+    /// then ~/amplihack-logparse.toml)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Custom strftime format for log timestamps (e.g. "%d/%m/%Y %H:%M:%S"),
+    /// tried before the built-in formats
+    #[arg(long, global = true)]
+    timestamp_format: Option<String>,
+
+    /// Report lines whose level isn't a known entry type (e.g. FATAL) as
+    /// malformed instead of parsing them as Unknown
+    #[arg(long, global = true)]
+    strict_levels: bool,
+
+    /// Remove ANSI color codes from messages while parsing
+    #[arg(long, global = true, default_value_t = true, action = ArgAction::Set)]
+    strip_ansi: bool,
+
+    /// Write the command's output to this file instead of stdout; warnings
+    /// and progress stay on stderr
+    #[arg(short, long, global = true)]
+    output: Option<PathBuf>,
+
+    /// Also print debug details (e.g. cache hits) on stderr
+    #[arg(short, long, global = true, conflicts_with = "quiet")]
+    verbose: bool,
+
+    /// Only print errors on stderr: no parse warnings, progress notes, or
+    /// progress bar
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Output formats for the Analyze report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable text
+    Text,
+
+    /// Pretty-printed JSON
+    Json,
+
+    /// Markdown with headed sections and tables
+    Markdown,
+
+    /// Graphviz DOT graph of entry-type transitions
+    Dot,
+
+    /// Block-style YAML of the same structure as `json`
+    Yaml,
+
+    /// Agent statistics as an aligned table
+    Table,
+}
+
+impl OutputFormat {
+    /// File extension for reports written with `--output-dir`
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Text | OutputFormat::Table => "txt",
+            OutputFormat::Json => "json",
+            OutputFormat::Markdown => "md",
+            OutputFormat::Dot => "dot",
+            OutputFormat::Yaml => "yaml",
+        }
+    }
+}
+
+/// Sort orders for Query results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SortField {
+    /// Oldest first
+    Time,
+
+    /// Newest first
+    TimeDesc,
+
+    /// Grouped by agent name (entries without an agent last)
+    Agent,
+
+    /// Grouped by entry type
+    Type,
+}
+
+/// Fields Query results can be grouped and counted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum GroupField {
+    /// Agent name (entries without an agent are grouped as "-")
+    Agent,
+
+    /// Entry type
+    Type,
+
+    /// Hour the entry was logged (UTC)
+    Hour,
+
+    /// Day the entry was logged (UTC)
+    Day,
+}
+
+impl GroupField {
+    fn label(self) -> &'static str {
+        match self {
+            GroupField::Agent => "agent",
+            GroupField::Type => "type",
+            GroupField::Hour => "hour",
+            GroupField::Day => "day",
+        }
+    }
+
+    /// The bucket an entry falls into for this field
+    fn key(self, entry: &LogEntry) -> String {
+        match self {
+            GroupField::Agent => entry.agent_name.clone().unwrap_or_else(|| "-".to_string()),
+            GroupField::Type => entry.entry_type.to_string(),
+            GroupField::Hour => entry.timestamp.format("%Y-%m-%d %H:00").to_string(),
+            GroupField::Day => entry.timestamp.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// Severities `analyze --fail-on` can fail on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum FailLevel {
+    /// Warnings or errors
+    Warning,
+
+    /// Errors only
+    Error,
+}
+
+impl FailLevel {
+    fn label(self) -> &'static str {
+        match self {
+            FailLevel::Warning => "warning",
+            FailLevel::Error => "error",
+        }
+    }
+
+    /// Whether an entry of this type is at or above the level
+    fn includes(self, entry_type: EntryType) -> bool {
+        match self {
+            FailLevel::Warning => matches!(entry_type, EntryType::Warning | EntryType::Error),
+            FailLevel::Error => entry_type == EntryType::Error,
+        }
+    }
+}
+
+/// Exit code when `analyze --fail-on` finds entries at or above its level
+const FAIL_ON_EXIT_CODE: i32 = 2;
+
+/// Seed used by `analyze --sample` when `--seed` isn't given
+const DEFAULT_SAMPLE_SEED: u64 = 0;
+
+/// Logs directory used when neither a flag nor the config file sets one
+const DEFAULT_LOGS_DIR: &str = ".claude/runtime/logs";
+
+/// Options for the Analyze subcommand
+///
+/// Options left unset fall back to the config file, then built-in defaults.
+#[derive(Args, Clone)]
+#[command(group(ArgGroup::new("split").args(["per_session", "split_on_markers"])))]
+struct AnalyzeArgs {
+    /// Path to logs directory (default: .claude/runtime/logs)
+    #[arg(short, long)]
+    logs_dir: Option<PathBuf>,
+
+    /// Fetch the log from a plain-HTTP (http://) URL instead of reading a
+    /// directory; https:// is not supported (requires the `http` feature)
+    #[arg(long, conflicts_with_all = ["logs_dir", "glob"])]
+    url: Option<String>,
+
+    /// Analyze a JSON-serialized LogSession (or array of LogEntry) instead
+    /// of parsing text logs
+    #[arg(long, conflicts_with_all = ["logs_dir", "glob", "url"])]
+    from_json: Option<PathBuf>,
+
+    /// Only analyze sessions from last N days
+    #[arg(short, long)]
+    since: Option<u32>,
+
+    /// Write agent statistics as CSV to this path
+    #[arg(long, conflicts_with_all = ["per_session", "split_on_markers"])]
+    output_csv: Option<PathBuf>,
+
+    /// Always re-parse log files instead of reusing `.cache` sidecars
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Don't show a progress bar while parsing files (set from the global
+    /// `--quiet` flag)
+    #[arg(skip)]
+    quiet: bool,
+
+    /// Drop duplicate entries (same timestamp, type, and message) across files
+    #[arg(long)]
+    dedupe: bool,
+
+    /// Report statistics separately for each `session_id` field value
+    #[arg(long)]
+    per_session: bool,
+
+    /// Report statistics separately for each session delimited by
+    /// `=== SESSION START ===` / `=== SESSION END ===` marker messages
+    #[arg(long, conflicts_with = "per_session")]
+    split_on_markers: bool,
+
+    /// Write each session's report to `<DIR>/<session id>.<format>` instead
+    /// of printing them (requires --per-session or --split-on-markers)
+    #[arg(long, value_name = "DIR", requires = "split")]
+    output_dir: Option<PathBuf>,
+
+    /// Glob pattern (e.g. "**/*.log") selecting log files recursively
+    #[arg(long)]
+    glob: Option<String>,
+
+    /// Output format for the analysis report (default: text)
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Errors per second that count as an error burst
+    #[arg(long)]
+    error_burst_threshold: Option<f64>,
+
+    /// Seconds between entries that count as a long gap
+    #[arg(long)]
+    long_gap_threshold: Option<f64>,
+
+    /// Invocations of one agent that count as high activity
+    #[arg(long)]
+    agent_activity_threshold: Option<usize>,
+
+    /// Seconds without entries after the first that count as a stalled
+    /// start (default: 120)
+    #[arg(long)]
+    stalled_start_threshold: Option<f64>,
+
+    /// Consecutive errors examined when detecting error bursts (default: 3)
+    #[arg(long)]
+    burst_window: Option<usize>,
+
+    /// Only report the N most-invoked agents (default: all)
+    #[arg(long)]
+    top: Option<usize>,
+
+    /// Leave this agent out of the agent statistics (repeatable)
+    #[arg(long, value_name = "NAME")]
+    exclude_agent: Vec<String>,
+
+    /// Analyze a random sample of this fraction of entries (e.g. 0.1 for
+    /// 10%); results are estimates
+    #[arg(
+        long,
+        value_name = "RATE",
+        value_parser = parse_sample_rate,
+        conflicts_with_all = ["url", "from_json"]
+    )]
+    sample: Option<f64>,
+
+    /// Seed choosing which entries --sample keeps (default: 0)
+    #[arg(long, requires = "sample")]
+    seed: Option<u64>,
+
+    /// Follow the text report with a heatmap of entries by UTC weekday and
+    /// hour of day
+    #[arg(long)]
+    heatmap: bool,
+
+    /// Exit with code 2 after the report if any entry is at or above this
+    /// level
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    fail_on: Option<FailLevel>,
+}
+
+impl AnalyzeArgs {
+    /// Fill options not given on the command line from the config file
+    fn with_config(&self, config: &Config) -> ParseResult<Self> {
+        let format = match (&self.format, &config.format) {
+            (Some(format), _) => Some(*format),
+            (None, Some(name)) => Some(OutputFormat::from_str(name, true).map_err(|_| {
+                ParseError::InvalidConfig(format!("unknown output format '{}'", name))
+            })?),
+            (None, None) => None,
+        };
+
+        Ok(Self {
+            logs_dir: self.logs_dir.clone().or_else(|| config.logs_dir.clone()),
+            format,
+            error_burst_threshold: self.error_burst_threshold.or(config.error_burst_threshold),
+            long_gap_threshold: self.long_gap_threshold.or(config.long_gap_threshold),
+            agent_activity_threshold: self
+                .agent_activity_threshold
+                .or(config.agent_activity_threshold),
+            ..self.clone()
+        })
+    }
+
+    fn logs_dir(&self) -> PathBuf {
+        self.logs_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_LOGS_DIR))
+    }
+
+    fn format(&self) -> OutputFormat {
+        self.format.unwrap_or(OutputFormat::Text)
+    }
+
+    /// How to read log files: sampled, from cache sidecars, or in full
+    fn parse_mode(&self) -> ParseMode {
+        match self.sample {
+            Some(rate) => ParseMode::Sampled {
+                rate,
+                seed: self.seed.unwrap_or(DEFAULT_SAMPLE_SEED),
+            },
+            None if self.no_cache => ParseMode::Full,
+            None => ParseMode::Cached,
+        }
+    }
+
+    /// Build a pattern analyzer, using defaults for thresholds not given
+    fn pattern_analyzer(&self) -> PatternAnalyzer {
+        PatternAnalyzer::with_thresholds(
+            self.error_burst_threshold.unwrap_or(DEFAULT_ERROR_BURST_THRESHOLD),
+            self.long_gap_threshold.unwrap_or(DEFAULT_LONG_GAP_THRESHOLD),
+            self.agent_activity_threshold.unwrap_or(DEFAULT_AGENT_ACTIVITY_THRESHOLD),
+        )
+        .with_burst_window(self.burst_window.unwrap_or(DEFAULT_BURST_WINDOW))
+        .with_stalled_start(
+            self.stalled_start_threshold.unwrap_or(DEFAULT_STALLED_START_THRESHOLD),
+        )
+    }
+}
+
+/// Options for the Query subcommand
+#[derive(Args)]
+struct QueryArgs {
+    /// Filter by agent name
+    #[arg(short, long)]
+    agent: Option<String>,
+
+    /// Drop entries from this agent, even if --agent matches it (repeatable)
+    #[arg(long, value_name = "NAME")]
+    exclude_agent: Vec<String>,
+
+    /// Search for text in messages
+    #[arg(short, long)]
+    contains: Option<String>,
+
+    /// Treat --contains as a regular expression
+    #[arg(long, requires = "contains")]
+    regex: bool,
+
+    /// Only include entries at or after this ISO-8601 timestamp
+    #[arg(long, value_parser = parse_timestamp)]
+    since: Option<DateTime<Utc>>,
+
+    /// Only include entries at or before this ISO-8601 timestamp
+    #[arg(long, value_parser = parse_timestamp)]
+    until: Option<DateTime<Utc>>,
+
+    /// Only include entries of this type
+    /// (info|warning|error|agent|decision|debug|trace|unknown; repeatable)
+    #[arg(long = "type", value_parser = parse_entry_type_arg)]
+    entry_types: Vec<EntryType>,
+
+    /// Glob pattern (e.g. "**/*.log") selecting log files recursively
+    #[arg(long)]
+    glob: Option<String>,
+
+    /// Sort matching entries before printing
+    #[arg(long, value_enum)]
+    sort: Option<SortField>,
+
+    /// Maximum number of matching entries to print
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+
+    /// Number of matching entries to skip before printing
+    #[arg(long, default_value_t = 0)]
+    offset: usize,
+
+    /// Show timestamps as offsets from the first entry (+MM:SS.mmm)
+    #[arg(long)]
+    relative: bool,
+
+    /// Print counts grouped by these fields instead of listing entries
+    /// (agent|type|hour|day; comma-separated for nested grouping)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    group_by: Vec<GroupField>,
+
+    /// Print only the number of matching entries
+    #[arg(long, conflicts_with = "group_by")]
+    count: bool,
+
+    /// Stream every matching entry as one JSON object per line, with no
+    /// headers or truncation
+    #[arg(
+        long,
+        conflicts_with_all = ["group_by", "count", "sort", "limit", "offset", "relative"]
+    )]
+    ndjson: bool,
+
+    /// Only include entries with a duration of at least this many milliseconds
+    #[arg(long, value_name = "MS")]
+    min_duration: Option<u64>,
+
+    /// Truncate messages longer than this many characters (0 = no limit)
+    #[arg(long, default_value_t = DEFAULT_MESSAGE_WIDTH)]
+    max_message_width: usize,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Parse a single session log
     Parse {
         /// Path to the session directory
-        session_path: PathBuf,
+        #[arg(required_unless_present = "url")]
+        session_path: Option<PathBuf>,
+
+        /// Fetch the log from a plain-HTTP (http://) URL instead; https:// is
+        /// not supported (requires the `http` feature)
+        #[arg(long, conflicts_with_all = ["session_path", "max_errors"])]
+        url: Option<String>,
+
+        /// Fail if any file has more than this many malformed lines
+        #[arg(long)]
+        max_errors: Option<usize>,
+
+        /// Show timestamps as offsets from the first entry (+MM:SS.mmm)
+        #[arg(long)]
+        relative: bool,
+
+        /// Truncate messages longer than this many characters (0 = no limit)
+        #[arg(long, default_value_t = DEFAULT_MESSAGE_WIDTH)]
+        max_message_width: usize,
     },
     /// Analyze logs and generate statistics
-    Analyze {
+    Analyze(AnalyzeArgs),
+    /// Query logs with filters
+    Query(QueryArgs),
+    /// Follow a live log file, printing entries as they are appended
+    Watch {
+        /// Path to the log file to follow
+        session_path: PathBuf,
+    },
+    /// Compare two sessions and print their differences
+    Diff {
+        /// Path to the first (baseline) session directory or log file
+        session_a: PathBuf,
+
+        /// Path to the second session directory or log file
+        session_b: PathBuf,
+    },
+    /// Check that every line of a log file parses; exits non-zero otherwise
+    Validate {
+        /// Path to the log file to validate
+        path: PathBuf,
+    },
+    /// Print the entries surrounding every error
+    Context {
+        /// Path to the session directory or log file
+        session_path: PathBuf,
+
+        /// Number of entries to show before and after each error
+        #[arg(short = 'n', long, default_value_t = 3)]
+        lines: usize,
+    },
+    /// Export parsed log entries to a SQLite database (requires sqlite3)
+    Export {
         /// Path to logs directory (default: .claude/runtime/logs)
         #[arg(short, long, default_value = ".claude/runtime/logs")]
         logs_dir: PathBuf,
 
-        /// Only analyze sessions from last N days
-        #[arg(short, long)]
-        since: Option<u32>,
+        /// Path to the SQLite database to create or append to
+        #[arg(long)]
+        db: PathBuf,
+
+        /// Report the target, row count, and columns without writing anything
+        #[arg(long)]
+        dry_run: bool,
     },
-    /// Query logs with filters
-    Query {
-        /// Filter by agent name
-        #[arg(short, long)]
-        agent: Option<String>,
-
-        /// Search for text in messages
-        #[arg(short, long)]
-        contains: Option<String>,
+    /// Load a logs directory once, then run queries typed on stdin
+    Repl {
+        /// Path to logs directory (default: .claude/runtime/logs)
+        #[arg(short, long, default_value = ".claude/runtime/logs")]
+        logs_dir: PathBuf,
+    },
+    /// List every log file in a directory as a session with its entry
+    /// count, duration, and errors
+    Overview {
+        /// Path to logs directory (default: .claude/runtime/logs)
+        #[arg(short, long, default_value = ".claude/runtime/logs")]
+        logs_dir: PathBuf,
     },
     /// Run performance benchmarks
     Bench {
         /// Number of iterations
         #[arg(short, long, default_value = "100")]
         iterations: u32,
+
+        /// Parse every log file in the logs directory per iteration instead
+        /// of just the first one
+        #[arg(long)]
+        all_files: bool,
     },
+    /// Print the JSON Schema for the serialized output types
+    Schema,
 }
 
 fn main() {
     let cli = Cli::parse();
+    set_max_level(log_level(&cli));
 
-    let result = match &cli.command {
-        Commands::Parse { session_path } => handle_parse(session_path),
-        Commands::Analyze { logs_dir, since } => handle_analyze(logs_dir, *since),
-        Commands::Query { agent, contains } => handle_query(agent.as_deref(), contains.as_deref()),
-        Commands::Bench { iterations } => handle_bench(*iterations),
+    let config = match Config::load_or_discover(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
     };
 
-    if let Err(e) = result {
+    let result = open_output(cli.output.as_deref()).and_then(|mut out| {
+        run_command(&cli, &config, &mut out)?;
+        out.flush()?;
+        Ok(())
+    });
+
+    if let Err(e) = &result {
         eprintln!("Error: {}", e);
-        std::process::exit(1);
+        std::process::exit(exit_code(&result));
+    }
+}
+
+/// Process exit code for a command's result
+///
+/// `--fail-on` hits exit with `FAIL_ON_EXIT_CODE` so CI can tell them
+/// apart from other failures, which exit with 1.
+fn exit_code(result: &ParseResult<()>) -> i32 {
+    match result {
+        Ok(()) => 0,
+        Err(ParseError::SeverityThreshold { .. }) => FAIL_ON_EXIT_CODE,
+        Err(_) => 1,
+    }
+}
+
+/// Where command output goes: the `--output` file, or stdout
+fn open_output(path: Option<&Path>) -> ParseResult<Box<dyn Write>> {
+    match path {
+        Some(path) => {
+            let file = File::create(path).map_err(|e| {
+                ParseError::Io(io::Error::new(
+                    e.kind(),
+                    format!("cannot create {}: {}", path.display(), e),
+                ))
+            })?;
+            Ok(Box::new(BufWriter::new(file)))
+        }
+        None => Ok(Box::new(io::stdout().lock())),
+    }
+}
+
+/// Dispatch the parsed subcommand, writing its output to `out`
+fn run_command(cli: &Cli, config: &Config, out: &mut dyn Write) -> ParseResult<()> {
+    let options = parse_options(cli, config)?;
+
+    match &cli.command {
+        Commands::Parse { session_path, url, max_errors, relative, max_message_width } => {
+            handle_parse(
+                out,
+                session_path.as_deref(),
+                url.as_deref(),
+                *max_errors,
+                *relative,
+                *max_message_width,
+                &options,
+            )
+        }
+        Commands::Analyze(args) => {
+            let args = AnalyzeArgs { quiet: cli.quiet, ..args.with_config(config)? };
+            handle_analyze(out, &args, &options)
+        }
+        Commands::Query(args) => handle_query(out, args, &options),
+        Commands::Watch { session_path } => handle_watch(out, session_path, &options),
+        Commands::Diff { session_a, session_b } => {
+            handle_diff(out, session_a, session_b, &options)
+        }
+        Commands::Validate { path } => handle_validate(out, path, &options),
+        Commands::Context { session_path, lines } => {
+            handle_context(out, session_path, *lines, &options)
+        }
+        Commands::Export { logs_dir, db, dry_run } => {
+            handle_export(out, logs_dir, db, *dry_run, &options)
+        }
+        Commands::Overview { logs_dir } => handle_overview(out, logs_dir, &options),
+        Commands::Repl { logs_dir } => handle_repl(out, logs_dir, &options),
+        Commands::Bench { iterations, all_files } => {
+            let logs_dir = Path::new(DEFAULT_LOGS_DIR);
+            handle_bench(out, logs_dir, *iterations, *all_files, &options)
+        }
+        Commands::Schema => handle_schema(out),
+    }
+}
+
+/// Parse options selected by the global flags and the config's aliases
+fn parse_options(cli: &Cli, config: &Config) -> ParseResult<ParseOptions> {
+    let options = ParseOptions::new()
+        .with_strip_ansi(cli.strip_ansi)
+        .with_strict_levels(cli.strict_levels)
+        .with_agent_aliases(&config.agent_aliases);
+
+    match &cli.timestamp_format {
+        Some(format) => options.with_timestamp_format(format),
+        None => Ok(options),
     }
 }
 
-fn handle_parse(session_path: &PathBuf) -> ParseResult<()> {
-    println!("Parsing session: {:?}", session_path);
+/// Most verbose diagnostics level selected by `--quiet` / `--verbose`
+fn log_level(cli: &Cli) -> Level {
+    if cli.quiet {
+        Level::Error
+    } else if cli.verbose {
+        Level::Debug
+    } else {
+        Level::Info
+    }
+}
 
-    let entries = parse_log_file(session_path)?;
+fn handle_parse(
+    out: &mut dyn Write,
+    session_path: Option<&Path>,
+    url: Option<&str>,
+    max_errors: Option<usize>,
+    relative: bool,
+    max_message_width: usize,
+    options: &ParseOptions,
+) -> ParseResult<()> {
+    // Failure tallies are only gathered for resilient parsing of files
+    let (session, parse_report) = match (url, session_path) {
+        (Some(url), _) => {
+            writeln!(out, "Parsing URL: {}", url)?;
+            (load_url_session(url, options)?, None)
+        }
+        (None, Some(session_path)) => {
+            writeln!(out, "Parsing session: {:?}", session_path)?;
+
+            match max_errors {
+                Some(max_errors) => {
+                    (load_session_strict(session_path, max_errors, options)?, None)
+                }
+                None => {
+                    let (session, report) = load_session_report(session_path, options)?;
+                    (session, Some(report))
+                }
+            }
+        }
+        (None, None) => unreachable!("clap requires a session path or --url"),
+    };
+    let entries = &session.entries;
+    let base = relative.then(|| relative_base(entries)).flatten();
 
-    println!("\nParsed {} log entries:", entries.len());
-    println!("{:-<80}", "");
+    writeln!(out, "\nSession: {}", session.id)?;
+    writeln!(out, "Parsed {} log entries:", entries.len())?;
+    writeln!(out, "{:-<80}", "")?;
 
     for (idx, entry) in entries.iter().enumerate().take(10) {
-        println!(
-            "[{}] {} | {:?} | {}",
+        writeln!(
+            out,
+            "[{}] {} | {} | {}",
             idx + 1,
-            entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            format_entry_time(entry.timestamp, base),
             entry.entry_type,
-            if entry.message.len() > 60 {
-                format!("{}...", &entry.message[..60])
-            } else {
-                entry.message.clone()
-            }
-        );
+            truncate_message(&entry.message, max_message_width)
+        )?;
 
         if let Some(ref agent) = entry.agent_name {
-            println!("    Agent: {}", agent);
+            writeln!(out, "    Agent: {}", agent)?;
         }
 
         if let Some(duration) = entry.duration_ms {
-            println!("    Duration: {}ms", duration);
+            writeln!(out, "    Duration: {}ms", duration)?;
         }
     }
 
     if entries.len() > 10 {
-        println!("\n... and {} more entries", entries.len() - 10);
+        writeln!(out, "\n... and {} more entries", entries.len() - 10)?;
     }
 
-    println!("\nSummary:");
-    println!("  Total entries: {}", entries.len());
+    writeln!(out, "\nSummary:")?;
+    writeln!(out, "  Total entries: {}", entries.len())?;
 
-    let entry_type_counts = count_entry_types(&entries);
+    let entry_type_counts = count_entry_types(entries);
     for (entry_type, count) in entry_type_counts {
-        println!("  {:?}: {}", entry_type, count);
+        writeln!(out, "  {}: {}", entry_type, count)?;
     }
 
+    if let Some(report) = parse_report.filter(|report| !report.is_clean()) {
+        writeln!(out, "\nMalformed lines skipped: {}", report.failure_count())?;
+        write!(out, "{}", report.summary())?;
+    }
+
+    writeln!(out)?;
+    write!(out, "{}", render_timeline(&session, DEFAULT_TIMELINE_COLUMNS))?;
+
     Ok(())
 }
 
-fn handle_analyze(logs_dir: &PathBuf, since: Option<u32>) -> ParseResult<()> {
-    println!("Analyzing logs in: {:?}", logs_dir);
+fn handle_analyze(
+    out: &mut dyn Write,
+    args: &AnalyzeArgs,
+    options: &ParseOptions,
+) -> ParseResult<()> {
+    let format = args.format();
+    let text = format == OutputFormat::Text;
 
-    if let Some(days) = since {
-        println!("Only analyzing last {} days", days);
+    if args.heatmap && !text {
+        log_warn!("--heatmap only applies to text output; ignoring it");
     }
 
-    if !logs_dir.exists() {
-        return Err(crate::error::ParseError::FileNotFound(logs_dir.clone()));
-    }
+    let loaded = match (&args.url, &args.from_json) {
+        (Some(url), _) => {
+            if text {
+                writeln!(out, "Analyzing logs from: {}", url)?;
+            }
+            Some(load_url_session(url, options)?)
+        }
+        (None, Some(path)) => {
+            if text {
+                writeln!(out, "Analyzing JSON session: {:?}", path)?;
+            }
+            Some(load_json_session(path)?)
+        }
+        (None, None) => load_logs_dir(out, args, text, options)?,
+    };
 
-    let log_files = std::fs::read_dir(logs_dir)?
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| {
-            let path = entry.path();
-            path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("log")
-        })
-        .collect::<Vec<_>>();
+    let Some(mut session) = loaded else {
+        return Ok(());
+    };
 
-    if log_files.is_empty() {
-        println!("No .log files found in directory");
+    if args.dedupe {
+        session.entries = dedupe_entries(session.entries);
+    }
+
+    if session.entries.is_empty() {
+        eprintln!("\nNo entries found to analyze");
         return Ok(());
     }
 
-    println!("\nFound {} log files to analyze", log_files.len());
-    println!("{:=<80}", "");
+    // Counted up front, since splitting into sessions consumes the entries
+    let fail_on = args.fail_on.map(|level| {
+        let count = session.entries.iter().filter(|e| level.includes(e.entry_type)).count();
+        (level, count)
+    });
 
-    let mut all_entries = Vec::new();
+    if args.per_session || args.split_on_markers {
+        let sessions = if args.split_on_markers {
+            split_by_markers(session.entries, SESSION_START_MARKER, SESSION_END_MARKER)
+        } else {
+            split_into_sessions(session.entries)
+        };
+        let reports = sessions
+            .iter()
+            .map(|session| Ok((session.id.clone(), build_report(session, args, options)?)))
+            .collect::<ParseResult<BTreeMap<String, AnalysisReport>>>()?;
+
+        if let Some(dir) = &args.output_dir {
+            write_session_reports(dir, args, &sessions, &reports)?;
+            return check_fail_on(fail_on);
+        }
 
-    for file_entry in log_files {
-        let path = file_entry.path();
-        match parse_log_file(&path) {
-            Ok(entries) => {
-                println!("Parsed {}: {} entries", path.display(), entries.len());
-                all_entries.extend(entries);
+        match format {
+            OutputFormat::Text => {
+                for session in &sessions {
+                    writeln!(out, "\nSession: {}", session.id)?;
+                    write_text_report(out, &reports[&session.id])?;
+                    if args.heatmap {
+                        write_heatmap(out, session)?;
+                    }
+                }
             }
-            Err(e) => {
-                eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
+            OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&reports)?)?,
+            OutputFormat::Yaml => write!(out, "{}", to_yaml(&reports)?)?,
+            OutputFormat::Markdown => {
+                for (id, report) in &reports {
+                    writeln!(out, "# Session {}\n", id)?;
+                    write_markdown_report(out, report)?;
+                }
+            }
+            OutputFormat::Dot => {
+                for session in &sessions {
+                    write_transition_graph(out, session)?;
+                }
+            }
+            OutputFormat::Table => {
+                for session in &sessions {
+                    writeln!(out, "Session: {}", session.id)?;
+                    write!(out, "{}", render_agent_table(&reports[&session.id].agents))?;
+                }
             }
         }
-    }
 
-    if all_entries.is_empty() {
-        println!("\nNo entries found to analyze");
-        return Ok(());
+        return check_fail_on(fail_on);
     }
 
-    let session = create_session_from_entries("aggregate", all_entries);
-
-    println!("\n{:=<80}", "");
-    println!("ANALYSIS RESULTS");
-    println!("{:=<80}", "");
+    let report = build_report(&session, args, options)?;
 
-    let timing_analyzer = TimingAnalyzer::new();
-    if let Ok(timing_stats) = timing_analyzer.analyze(&session) {
-        println!("\nTiming Statistics:");
-        println!("  Total duration: {:.2} seconds", timing_stats.total_duration_secs);
-        println!("  Entry count: {}", timing_stats.entry_count);
-        println!("  Avg time between entries: {:.2}s", timing_stats.avg_time_between_entries);
+    if let Some(csv_path) = &args.output_csv {
+        export_agent_stats_csv(&report.agents, csv_path)?;
+        log_info!("Wrote agent statistics CSV to {}", csv_path.display());
     }
 
-    let agent_analyzer = AgentAnalyzer::new();
-    if let Ok(agent_stats) = agent_analyzer.analyze(&session) {
-        println!("\nAgent Statistics:");
-        if agent_stats.is_empty() {
-            println!("  No agent invocations found");
-        } else {
-            for stats in agent_stats {
-                println!("  {}", stats.name);
-                println!("    Invocations: {}", stats.invocation_count);
-                println!("    Total duration: {}ms", stats.total_duration_ms);
-                println!("    Avg duration: {:.2}ms", stats.avg_duration_ms);
+    write_report(out, args, &session, &report)?;
+    check_fail_on(fail_on)
+}
+
+/// Write one session's report in the chosen output format
+fn write_report(
+    out: &mut dyn Write,
+    args: &AnalyzeArgs,
+    session: &LogSession,
+    report: &AnalysisReport,
+) -> ParseResult<()> {
+    match args.format() {
+        OutputFormat::Text => {
+            write_text_report(out, report)?;
+            if args.heatmap {
+                write_heatmap(out, session)?;
             }
         }
+        OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(report)?)?,
+        OutputFormat::Yaml => write!(out, "{}", to_yaml(report)?)?,
+        OutputFormat::Markdown => write_markdown_report(out, report)?,
+        OutputFormat::Dot => write_transition_graph(out, session)?,
+        OutputFormat::Table => write!(out, "{}", render_agent_table(&report.agents))?,
     }
+    Ok(())
+}
 
-    let pattern_analyzer = PatternAnalyzer::new();
-    if let Ok(pattern_analysis) = pattern_analyzer.analyze(&session) {
-        println!("\nPattern Detection:");
-        if pattern_analysis.patterns.is_empty() {
-            println!("  No significant patterns detected");
-        } else {
-            for pattern in pattern_analysis.patterns {
-                println!("  {:?}", pattern);
+/// Write each session's report to its own file in `dir`, returning the
+/// paths written
+///
+/// `dir` is created if missing. Only `<session id>.<ext>` files are
+/// written, so anything else in the directory is left alone; ids that
+/// map to the same file name get a numeric suffix instead of overwriting
+/// each other.
+fn write_session_reports(
+    dir: &Path,
+    args: &AnalyzeArgs,
+    sessions: &[LogSession],
+    reports: &BTreeMap<String, AnalysisReport>,
+) -> ParseResult<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir).map_err(|e| {
+        ParseError::Io(io::Error::new(
+            e.kind(),
+            format!("cannot create {}: {}", dir.display(), e),
+        ))
+    })?;
+
+    let extension = args.format().extension();
+    let mut used = HashSet::new();
+    let mut written = Vec::new();
+
+    for session in sessions {
+        let stem = report_file_stem(&session.id);
+        let mut name = format!("{}.{}", stem, extension);
+        for n in 2.. {
+            if used.insert(name.clone()) {
+                break;
             }
+            name = format!("{}-{}.{}", stem, n, extension);
         }
-    }
 
-    println!("\n{:=<80}", "");
+        let path = dir.join(name);
+        let mut out = open_output(Some(&path))?;
+        write_report(&mut out, args, session, &reports[&session.id])?;
+        out.flush()?;
 
-    Ok(())
-}
+        log_info!("Wrote {}", path.display());
+        written.push(path);
+    }
 
-fn handle_query(agent: Option<&str>, contains: Option<&str>) -> ParseResult<()> {
-    println!("Querying logs");
+    Ok(written)
+}
 
-    let logs_dir = PathBuf::from(".claude/runtime/logs");
+/// File name stem for a session id, keeping only characters that are safe
+/// in file names on every platform
+fn report_file_stem(id: &str) -> String {
+    let stem: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
 
-    if !logs_dir.exists() {
-        return Err(crate::error::ParseError::FileNotFound(logs_dir));
+    // A stem of only dots would name the directory itself or its parent
+    if stem.chars().all(|c| c == '.') {
+        format!("session{}", stem.replace('.', "_"))
+    } else {
+        stem
     }
+}
 
-    let log_files = std::fs::read_dir(&logs_dir)?
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| {
-            let path = entry.path();
-            path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("log")
-        })
-        .collect::<Vec<_>>();
-
-    let mut all_entries = Vec::new();
+/// Fail with `SeverityThreshold` if `--fail-on` counted any entries
+fn check_fail_on(fail_on: Option<(FailLevel, usize)>) -> ParseResult<()> {
+    match fail_on {
+        Some((level, count)) if count > 0 => Err(ParseError::SeverityThreshold {
+            level: level.label().to_string(),
+            count,
+        }),
+        _ => Ok(()),
+    }
+}
 
-    for file_entry in log_files {
-        let path = file_entry.path();
-        if let Ok(entries) = parse_log_file(&path) {
-            all_entries.extend(entries);
+/// Parse and merge every log file in the analyze logs directory
+///
+/// The merged session is given the untagged session id. Returns `None`
+/// (after saying so) if the directory holds no log files.
+fn load_logs_dir(
+    out: &mut dyn Write,
+    args: &AnalyzeArgs,
+    text: bool,
+    options: &ParseOptions,
+) -> ParseResult<Option<LogSession>> {
+    let logs_dir = &args.logs_dir();
+
+    if text {
+        writeln!(out, "Analyzing logs in: {:?}", logs_dir)?;
+
+        if let Some(days) = args.since {
+            writeln!(out, "Only analyzing last {} days", days)?;
         }
     }
 
-    let filtered_entries: Vec<_> = all_entries
-        .iter()
-        .filter(|entry| {
-            let agent_match = agent
-                .map(|a| entry.agent_name.as_ref().map_or(false, |name| name.contains(a)))
-                .unwrap_or(true);
+    if !logs_dir.exists() {
+        return Err(ParseError::FileNotFound(logs_dir.clone()));
+    }
 
-            let text_match = contains
-                .map(|text| entry.message.to_lowercase().contains(&text.to_lowercase()))
-                .unwrap_or(true);
+    let log_files = find_log_files(logs_dir, args.glob.as_deref())?;
 
-            agent_match && text_match
-        })
-        .collect();
+    if log_files.is_empty() {
+        eprintln!("No .log or .log.gz files found in directory");
+        return Ok(None);
+    }
 
-    println!("\nQuery Filters:");
-    if let Some(agent_name) = agent {
-        println!("  Agent: {}", agent_name);
+    if text {
+        writeln!(out, "\nFound {} log files to analyze", log_files.len())?;
+        writeln!(out, "{:=<80}", "")?;
     }
-    if let Some(search_text) = contains {
-        println!("  Contains: {}", search_text);
+
+    let mode = args.parse_mode();
+    if let ParseMode::Sampled { rate, seed } = mode {
+        let note = format!(
+            "Sampling {:.1}% of entries (seed {}); results are estimates",
+            rate * 100.0,
+            seed
+        );
+        if text {
+            writeln!(out, "{}", note)?;
+        } else {
+            log_info!("{}", note);
+        }
     }
 
-    println!("\nFound {} matching entries:", filtered_entries.len());
-    println!("{:-<80}", "");
+    let progress = ProgressBar::stderr(log_files.len(), args.quiet);
+    let sessions = parse_files_parallel(&log_files, mode, options, &progress);
 
-    for (idx, entry) in filtered_entries.iter().enumerate().take(20) {
-        println!(
-            "[{}] {} | {:?}",
-            idx + 1,
-            entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
-            entry.entry_type
-        );
-        println!("    {}", entry.message);
+    let mut session = merge_sessions(sessions);
+    session.id = UNTAGGED_SESSION_ID.to_string();
+    Ok(Some(session))
+}
 
-        if let Some(ref agent_name) = entry.agent_name {
-            println!("    Agent: {}", agent_name);
+/// Run the standard analyzers over one session
+fn build_report(
+    session: &LogSession,
+    args: &AnalyzeArgs,
+    options: &ParseOptions,
+) -> ParseResult<AnalysisReport> {
+    let mut agents = AgentAnalyzer::new().analyze(session)?;
+
+    let excluded = args.exclude_agent.iter().fold(EntryFilter::new(), |filter, agent| {
+        filter.with_excluded_agent(&options.canonical_agent_name(agent))
+    });
+    agents.retain(|stats| !excluded.excludes_agent(&stats.name));
+
+    match args.top {
+        Some(n) => {
+            agents.sort_by(|a, b| {
+                b.invocation_count
+                    .cmp(&a.invocation_count)
+                    .then(b.total_duration_ms.cmp(&a.total_duration_ms))
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+            agents.truncate(n);
         }
-        println!();
+        None => agents.sort_by(|a, b| a.name.cmp(&b.name)),
     }
 
-    if filtered_entries.len() > 20 {
-        println!("... and {} more entries", filtered_entries.len() - 20);
-    }
+    Ok(AnalysisReport {
+        timing: TimingAnalyzer::new().analyze(session)?,
+        agents,
+        patterns: args.pattern_analyzer().analyze(session)?,
+        decisions: DecisionAnalyzer::new().analyze(session)?,
+        warnings: WarningAnalyzer::new().analyze(session)?,
+    })
+}
 
+fn write_markdown_report(out: &mut dyn Write, report: &AnalysisReport) -> ParseResult<()> {
+    write!(
+        out,
+        "{}",
+        render_markdown(&report.timing, &report.agents, &report.patterns)
+    )?;
     Ok(())
 }
 
-fn handle_bench(iterations: u32) -> ParseResult<()> {
-    println!("Running benchmarks with {} iterations", iterations);
+fn write_transition_graph(out: &mut dyn Write, session: &LogSession) -> ParseResult<()> {
+    let transitions = TransitionAnalyzer::new().analyze(session)?;
+    write!(out, "{}", render_dot(&session.id, &transitions))?;
+    Ok(())
+}
 
-    let logs_dir = PathBuf::from(".claude/runtime/logs");
+fn write_heatmap(out: &mut dyn Write, session: &LogSession) -> ParseResult<()> {
+    let heatmap = ActivityHeatmapAnalyzer::new().analyze(session)?;
+    write!(out, "\n{}", render_heatmap(&heatmap))?;
+    Ok(())
+}
 
-    if !logs_dir.exists() {
-        return Err(crate::error::ParseError::FileNotFound(logs_dir));
+fn write_text_report(out: &mut dyn Write, report: &AnalysisReport) -> ParseResult<()> {
+    writeln!(out, "\n{:=<80}", "")?;
+    writeln!(out, "ANALYSIS RESULTS")?;
+    writeln!(out, "{:=<80}", "")?;
+
+    let timing_stats = &report.timing;
+    writeln!(out, "\nTiming Statistics:")?;
+    writeln!(out, "  Total duration: {:.2} seconds", timing_stats.total_duration_secs)?;
+    writeln!(out, "  Entry count: {}", timing_stats.entry_count)?;
+    writeln!(out, "  Avg time between entries: {:.2}s", timing_stats.avg_time_between_entries)?;
+    writeln!(
+        out,
+        "  Gap p50/p95/p99: {:.2}s / {:.2}s / {:.2}s",
+        timing_stats.p50_gap_secs, timing_stats.p95_gap_secs, timing_stats.p99_gap_secs
+    )?;
+    writeln!(out, "  Gap stddev: {:.2}s", timing_stats.stddev_gap_secs)?;
+
+    let mut per_type: Vec<_> = timing_stats.per_type_avg_duration.iter().collect();
+    per_type.sort_by_key(|(entry_type, _)| **entry_type);
+    for (entry_type, avg_ms) in per_type {
+        writeln!(out, "  Avg {} duration: {:.2}ms", entry_type, avg_ms)?;
     }
 
-    let log_file = std::fs::read_dir(&logs_dir)?
-        .filter_map(|entry| entry.ok())
-        .find(|entry| {
-            let path = entry.path();
-            path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("log")
-        })
-        .map(|e| e.path());
+    let skews = report.patterns.clock_skew_count();
+    if skews > 0 {
+        writeln!(
+            out,
+            "  Warning: {} out-of-order timestamp(s); gap statistics may be unreliable",
+            skews
+        )?;
+    }
 
-    let test_file = match log_file {
-        Some(path) => path,
-        None => {
-            println!("No log files found for benchmarking");
-            return Ok(());
+    writeln!(out, "\nAgent Statistics:")?;
+    if report.agents.is_empty() {
+        writeln!(out, "  No agent invocations found")?;
+    } else {
+        for stats in &report.agents {
+            writeln!(out, "  {}", stats.name)?;
+            writeln!(out, "    Invocations: {}", stats.invocation_count)?;
+            writeln!(out, "    Total duration: {}ms", stats.total_duration_ms)?;
+            writeln!(out, "    Avg duration: {:.2}ms", stats.avg_duration_ms)?;
+            if let (Some(min), Some(max)) = (stats.min_duration_ms, stats.max_duration_ms) {
+                writeln!(out, "    Min/max duration: {}ms / {}ms", min, max)?;
+            }
         }
-    };
-
-    println!("Benchmarking with file: {}", test_file.display());
-    println!("{:=<80}", "");
-
-    let mut parse_times = Vec::new();
-
-    println!("\nRunning parse benchmarks...");
-    for i in 0..iterations {
-        let start = Instant::now();
-        let entries = parse_log_file(&test_file)?;
-        let elapsed = start.elapsed();
-        parse_times.push(elapsed.as_micros() as f64 / 1000.0);
+    }
 
-        if i == 0 {
-            println!("  First run parsed {} entries", entries.len());
+    writeln!(out, "\nPattern Detection:")?;
+    if report.patterns.patterns.is_empty() {
+        writeln!(out, "  No significant patterns detected")?;
+    } else {
+        for pattern in &report.patterns.patterns {
+            writeln!(out, "  {:?}", pattern)?;
         }
+    }
 
-        if (i + 1) % 10 == 0 {
-            print!(".");
-            if (i + 1) % 50 == 0 {
-                println!(" {}/{}", i + 1, iterations);
-            }
+    writeln!(out, "\nWarnings:")?;
+    if report.warnings.is_empty() {
+        writeln!(out, "  No warnings")?;
+    } else {
+        for warning in &report.warnings {
+            writeln!(out, "  {:>5}x {}", warning.count, warning.message)?;
         }
     }
-    println!();
 
-    let avg_time = parse_times.iter().sum::<f64>() / parse_times.len() as f64;
-    let min_time = parse_times.iter().cloned().fold(f64::INFINITY, f64::min);
-    let max_time = parse_times.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    writeln!(out, "\nDecisions:")?;
+    if report.decisions.is_empty() {
+        writeln!(out, "  No decisions recorded")?;
+    } else {
+        for decision in &report.decisions {
+            writeln!(
+                out,
+                "  - [{}] {}",
+                decision.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                decision.message
+            )?;
+        }
+    }
 
-    println!("\n{:=<80}", "");
-    println!("BENCHMARK RESULTS");
-    println!("{:=<80}", "");
-    println!("Parse Performance:");
-    println!("  Iterations: {}", iterations);
-    println!("  Average time: {:.2}ms", avg_time);
-    println!("  Min time: {:.2}ms", min_time);
-    println!("  Max time: {:.2}ms", max_time);
+    writeln!(out, "\n{:=<80}", "")?;
+    Ok(())
+}
 
-    if let Ok(entries) = parse_log_file(&test_file) {
-        let session = create_session_from_entries("bench", entries);
+fn handle_query(out: &mut dyn Write, args: &QueryArgs, options: &ParseOptions) -> ParseResult<()> {
+    let agent = args.agent.as_deref();
+    let contains = args.contains.as_deref();
+    let use_regex = args.regex;
+    let (since, until) = (args.since, args.until);
 
-        let mut analyzer_times = Vec::new();
+    if !args.count && !args.ndjson {
+        writeln!(out, "Querying logs")?;
+    }
 
-        println!("\nRunning analyzer benchmarks...");
-        for _ in 0..iterations {
-            let start = Instant::now();
+    // Compile up front so an invalid pattern fails before any parsing
+    let filter = entry_filter(args, options)?;
 
-            let timing_analyzer = TimingAnalyzer::new();
-            let _ = timing_analyzer.analyze(&session);
+    let logs_dir = PathBuf::from(".claude/runtime/logs");
 
-            let agent_analyzer = AgentAnalyzer::new();
-            let _ = agent_analyzer.analyze(&session);
+    if !logs_dir.exists() {
+        return Err(ParseError::FileNotFound(logs_dir));
+    }
 
-            let pattern_analyzer = PatternAnalyzer::new();
-            let _ = pattern_analyzer.analyze(&session);
+    let log_files = find_log_files(&logs_dir, args.glob.as_deref())?;
 
-            let elapsed = start.elapsed();
-            analyzer_times.push(elapsed.as_micros() as f64 / 1000.0);
-        }
+    if args.ndjson {
+        write_ndjson(out, &log_files, &filter, options)?;
+        return Ok(());
+    }
 
-        let avg_analyzer_time = analyzer_times.iter().sum::<f64>() / analyzer_times.len() as f64;
-        let min_analyzer_time = analyzer_times.iter().cloned().fold(f64::INFINITY, f64::min);
-        let max_analyzer_time = analyzer_times.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let mut all_entries = Vec::new();
 
-        println!("\nAnalyzer Performance (all 3 analyzers):");
-        println!("  Average time: {:.2}ms", avg_analyzer_time);
-        println!("  Min time: {:.2}ms", min_analyzer_time);
-        println!("  Max time: {:.2}ms", max_analyzer_time);
+    for path in log_files {
+        if let Ok(entries) = parse_log_file_with_options(&path, options) {
+            all_entries.extend(entries);
+        }
     }
 
-    println!("{:=<80}", "");
+    // Offsets are from the start of the logs, not of the filtered results
+    let base = args.relative.then(|| relative_base(&all_entries)).flatten();
 
-    Ok(())
-}
+    let mut filtered_entries: Vec<_> = all_entries.iter().filter(|e| filter.matches(e)).collect();
 
-fn count_entry_types(entries: &[crate::types::LogEntry]) -> Vec<(EntryType, usize)> {
-    use std::collections::HashMap;
+    if args.count {
+        write_count(out, &filtered_entries)?;
+        return Ok(());
+    }
 
-    let mut counts = HashMap::new();
+    writeln!(out, "\nQuery Filters:")?;
+    if let Some(agent_name) = agent {
+        writeln!(out, "  Agent: {}", agent_name)?;
+    }
+    if !args.exclude_agent.is_empty() {
+        writeln!(out, "  Excluding agents: {}", args.exclude_agent.join(", "))?;
+    }
+    if let Some(search_text) = contains {
+        if use_regex {
+            writeln!(out, "  Matches regex: {}", search_text)?;
+        } else {
+            writeln!(out, "  Contains: {}", search_text)?;
+        }
+    }
+    if let Some(start) = since {
+        writeln!(out, "  Since: {}", start.format("%Y-%m-%d %H:%M:%S"))?;
+    }
+    if let Some(end) = until {
+        writeln!(out, "  Until: {}", end.format("%Y-%m-%d %H:%M:%S"))?;
+    }
+    if let Some(min_ms) = args.min_duration {
+        writeln!(out, "  Min duration: {}ms", min_ms)?;
+    }
 
-    for entry in entries {
-        *counts.entry(entry.entry_type).or_insert(0) += 1;
+    if !args.group_by.is_empty() {
+        writeln!(out, "\nFound {} matching entries:", filtered_entries.len())?;
+        write_group_counts(out, &args.group_by, &group_counts(&filtered_entries, &args.group_by))?;
+        return Ok(());
     }
 
-    let mut result: Vec<_> = counts.into_iter().collect();
-    result.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    if let Some(field) = args.sort {
+        sort_entries(&mut filtered_entries, field);
+    }
 
-    result
-}
+    writeln!(out, "\nFound {} matching entries:", filtered_entries.len())?;
+    writeln!(out, "{:-<80}", "")?;
 
-fn create_session_from_entries(id: &str, entries: Vec<crate::types::LogEntry>) -> LogSession {
-    let start_time = entries
-        .first()
-        .map(|e| e.timestamp)
-        .unwrap_or_else(Utc::now);
+    let page = paginate(&filtered_entries, args.offset, args.limit);
 
-    let end_time = entries.last().map(|e| e.timestamp);
+    for (idx, entry) in page.iter().enumerate() {
+        writeln!(
+            out,
+            "[{}] {} | {}",
+            args.offset + idx + 1,
+            format_entry_time(entry.timestamp, base),
+            entry.entry_type
+        )?;
+        writeln!(out, "    {}", truncate_message(&entry.message, args.max_message_width))?;
 
-    LogSession {
-        id: id.to_string(),
-        entries,
-        start_time,
-        end_time,
+        if let Some(ref agent_name) = entry.agent_name {
+            writeln!(out, "    Agent: {}", agent_name)?;
+        }
+        writeln!(out)?;
+    }
+
+    let remaining = filtered_entries
+        .len()
+        .saturating_sub(args.offset + page.len());
+    if remaining > 0 {
+        writeln!(out, "... and {} more entries", remaining)?;
+    }
+
+    Ok(())
+}
+
+/// Build the entry filter described by the query flags
+fn entry_filter(args: &QueryArgs, options: &ParseOptions) -> ParseResult<EntryFilter> {
+    let mut filter = EntryFilter::new().with_time_range(args.since, args.until);
+
+    if let Some(agent) = &args.agent {
+        filter = filter.with_agent(&options.canonical_agent_name(agent));
+    }
+    for agent in &args.exclude_agent {
+        filter = filter.with_excluded_agent(&options.canonical_agent_name(agent));
+    }
+    if let Some(text) = &args.contains {
+        filter = filter.with_text_matcher(TextMatcher::new(text, args.regex)?);
+    }
+    for &entry_type in &args.entry_types {
+        filter = filter.with_type(entry_type);
+    }
+    if let Some(min_ms) = args.min_duration {
+        filter = filter.with_min_duration(min_ms);
+    }
+
+    Ok(filter)
+}
+
+/// Stream the entries of `paths` that match `filter` as newline-delimited
+/// JSON for `query --ndjson`, returning how many were written
+///
+/// Entries are parsed and written one at a time, so memory use doesn't
+/// grow with the size of the logs. Malformed lines are reported on stderr
+/// and skipped, keeping the output valid JSON lines.
+fn write_ndjson(
+    out: &mut dyn Write,
+    paths: &[PathBuf],
+    filter: &EntryFilter,
+    options: &ParseOptions,
+) -> ParseResult<usize> {
+    let mut written = 0;
+
+    for path in paths {
+        for result in parse_log_entries_streaming(path, options) {
+            match result {
+                Ok(entry) if filter.matches(&entry) => {
+                    serde_json::to_writer(&mut *out, &entry)?;
+                    writeln!(out)?;
+                    written += 1;
+                }
+                Ok(_) => {}
+                Err(e) => log_warn!("{}: {}", path.display(), e),
+            }
+        }
+    }
+
+    Ok(written)
+}
+
+/// Write the bare match count for `query --count`, so scripts can read it
+/// without stripping headers
+fn write_count(out: &mut dyn Write, entries: &[&LogEntry]) -> io::Result<()> {
+    writeln!(out, "{}", entries.len())
+}
+
+fn handle_watch(
+    out: &mut dyn Write,
+    session_path: &Path,
+    options: &ParseOptions,
+) -> ParseResult<()> {
+    writeln!(out, "Watching {} (Ctrl-C to stop)", session_path.display())?;
+    writeln!(out, "{:-<80}", "")?;
+
+    let mut follower = LogFollower::new(session_path)?.with_options(options.clone());
+
+    loop {
+        for entry in follower.poll()? {
+            writeln!(out, "{}", entry)?;
+
+            if let Some(ref agent) = entry.agent_name {
+                writeln!(out, "    Agent: {}", agent)?;
+            }
+        }
+        out.flush()?;
+
+        std::thread::sleep(WATCH_POLL_INTERVAL);
+    }
+}
+
+fn handle_diff(
+    out: &mut dyn Write,
+    session_a: &Path,
+    session_b: &Path,
+    options: &ParseOptions,
+) -> ParseResult<()> {
+    let a = load_session(session_a, options)?;
+    let b = load_session(session_b, options)?;
+
+    writeln!(out, "Comparing sessions:")?;
+    writeln!(out, "  A: {} ({} entries)", a.id, a.entries.len())?;
+    writeln!(out, "  B: {} ({} entries)", b.id, b.entries.len())?;
+    writeln!(out, "{:=<80}", "")?;
+
+    let diff = compare_sessions(&a, &b);
+
+    writeln!(out, "Entry count delta: {:+}", diff.entry_count_delta)?;
+    writeln!(out, "Duration delta: {:+.2}s", diff.duration_delta_secs)?;
+
+    writeln!(out, "\nAgents only in A:")?;
+    if diff.agents_only_in_a.is_empty() {
+        writeln!(out, "  (none)")?;
+    }
+    for agent in &diff.agents_only_in_a {
+        writeln!(out, "  {}", agent)?;
+    }
+
+    writeln!(out, "\nAgents only in B:")?;
+    if diff.agents_only_in_b.is_empty() {
+        writeln!(out, "  (none)")?;
+    }
+    for agent in &diff.agents_only_in_b {
+        writeln!(out, "  {}", agent)?;
+    }
+
+    writeln!(out, "\nInvocation changes:")?;
+    if diff.invocation_changes.is_empty() {
+        writeln!(out, "  (none)")?;
+    }
+    for change in &diff.invocation_changes {
+        writeln!(
+            out,
+            "  {}: {} -> {} ({:+})",
+            change.agent,
+            change.count_a,
+            change.count_b,
+            change.delta()
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Number of failed lines listed by `validate`
+const VALIDATE_MAX_LISTED: usize = 5;
+
+fn handle_validate(out: &mut dyn Write, path: &Path, options: &ParseOptions) -> ParseResult<()> {
+    let report = validate_log_file(path, options)?;
+
+    writeln!(out, "Validating {}", path.display())?;
+    writeln!(out, "  Valid entries: {}", report.valid_entries)?;
+    writeln!(out, "  Malformed lines: {}", report.failures.len())?;
+
+    if report.is_valid() {
+        return Ok(());
+    }
+
+    writeln!(out, "\nFailures by kind:")?;
+    write!(out, "{}", report.parse_report().summary())?;
+
+    writeln!(out, "\nFirst failures:")?;
+    for (line_num, error) in report.failures.iter().take(VALIDATE_MAX_LISTED) {
+        writeln!(out, "  line {}: {}", line_num, error)?;
+    }
+    if report.failures.len() > VALIDATE_MAX_LISTED {
+        writeln!(out, "  ... and {} more", report.failures.len() - VALIDATE_MAX_LISTED)?;
+    }
+
+    Err(ParseError::ValidationFailed {
+        failures: report.failures.len(),
+    })
+}
+
+fn handle_context(
+    out: &mut dyn Write,
+    session_path: &Path,
+    lines: usize,
+    options: &ParseOptions,
+) -> ParseResult<()> {
+    let session = load_session(session_path, options)?;
+    let windows = error_context_windows(&session.entries, lines);
+
+    writeln!(
+        out,
+        "Session {}: {} errors with {} entries of context",
+        session.id,
+        windows.len(),
+        lines
+    )?;
+
+    for (error_idx, window) in windows {
+        writeln!(out, "{:-<80}", "")?;
+
+        for idx in window {
+            let entry = &session.entries[idx];
+            let marker = if idx == error_idx { ">>" } else { "  " };
+            writeln!(out, "{} [{}] {}", marker, idx + 1, entry)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Index ranges of `lines` entries on either side of every error
+///
+/// Returns `(error_index, window)` pairs; windows are clamped to the
+/// bounds of `entries`.
+fn error_context_windows(entries: &[LogEntry], lines: usize) -> Vec<(usize, Range<usize>)> {
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.entry_type == EntryType::Error)
+        .map(|(idx, _)| {
+            let start = idx.saturating_sub(lines);
+            let end = idx.saturating_add(lines).saturating_add(1).min(entries.len());
+            (idx, start..end)
+        })
+        .collect()
+}
+
+fn handle_export(
+    out: &mut dyn Write,
+    logs_dir: &Path,
+    db: &Path,
+    dry_run: bool,
+    options: &ParseOptions,
+) -> ParseResult<()> {
+    let session = load_session(logs_dir, options)?;
+
+    if dry_run {
+        write!(out, "{}", describe_export(db, session.entries.len()))?;
+        return Ok(());
+    }
+
+    export_to_sqlite(&session.entries, db)?;
+
+    writeln!(
+        out,
+        "Exported {} entries to {}",
+        session.entries.len(),
+        db.display()
+    )?;
+
+    Ok(())
+}
+
+/// Summary printed by `export --dry-run`
+fn describe_export(db: &Path, rows: usize) -> String {
+    format!(
+        "Dry run: nothing written\n  Target: {}\n  Rows: {}\n  Columns: {}\n",
+        db.display(),
+        rows,
+        ENTRIES_COLUMNS.join(", ")
+    )
+}
+
+/// Summary of one parsed log file for `overview`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SessionSummary {
+    /// Earliest entry timestamp (None for a file without entries)
+    start: Option<DateTime<Utc>>,
+    entries: usize,
+    duration_secs: f64,
+    errors: usize,
+}
+
+impl SessionSummary {
+    fn from_entries(entries: &[LogEntry]) -> Self {
+        let start = entries.iter().map(|e| e.timestamp).min();
+        let end = entries.iter().map(|e| e.timestamp).max();
+        let duration_ms = start.zip(end).map_or(0, |(s, e)| (e - s).num_milliseconds());
+
+        Self {
+            start,
+            entries: entries.len(),
+            duration_secs: duration_ms as f64 / 1000.0,
+            errors: entries.iter().filter(|e| e.entry_type == EntryType::Error).count(),
+        }
+    }
+}
+
+/// One `overview` row: a file's summary, or why it couldn't be parsed
+#[derive(Debug)]
+struct OverviewRow {
+    id: String,
+    summary: Result<SessionSummary, String>,
+}
+
+/// Marker shown in place of the columns of a file that failed to parse
+const OVERVIEW_ERROR_MARKER: &str = "!!";
+
+fn handle_overview(
+    out: &mut dyn Write,
+    logs_dir: &Path,
+    options: &ParseOptions,
+) -> ParseResult<()> {
+    if !logs_dir.exists() {
+        return Err(ParseError::FileNotFound(logs_dir.to_path_buf()));
+    }
+
+    let rows = overview_rows(&find_log_files(logs_dir, None)?, options);
+
+    writeln!(out, "Sessions in {}: {}", logs_dir.display(), rows.len())?;
+    write_overview(out, &rows)?;
+    Ok(())
+}
+
+/// Parse each file as its own session, ordered by start time
+///
+/// Files without entries follow the dated sessions, and files that fail
+/// to parse come last, so one bad file never hides the rest.
+fn overview_rows(paths: &[PathBuf], options: &ParseOptions) -> Vec<OverviewRow> {
+    let mut rows: Vec<OverviewRow> = paths
+        .par_iter()
+        .map(|path| OverviewRow {
+            id: session_id(path),
+            summary: parse_log_file_with_options(path, options)
+                .map(|entries| SessionSummary::from_entries(&entries))
+                .map_err(|e| e.to_string()),
+        })
+        .collect();
+
+    rows.sort_by(|a, b| {
+        let key = |row: &OverviewRow| {
+            let start = row.summary.as_ref().ok().and_then(|s| s.start);
+            (row.summary.is_err(), start.is_none(), start)
+        };
+        key(a).cmp(&key(b)).then_with(|| a.id.cmp(&b.id))
+    });
+    rows
+}
+
+fn write_overview(out: &mut dyn Write, rows: &[OverviewRow]) -> io::Result<()> {
+    let width = rows
+        .iter()
+        .map(|row| row.id.len())
+        .chain(std::iter::once("Session".len()))
+        .max()
+        .unwrap_or(0);
+
+    writeln!(
+        out,
+        "{:<width$}  {:<19}  {:>8}  {:>10}  {:>6}",
+        "Session",
+        "Start",
+        "Entries",
+        "Duration",
+        "Errors",
+        width = width
+    )?;
+    writeln!(out, "{:-<80}", "")?;
+
+    for row in rows {
+        match &row.summary {
+            Ok(summary) => {
+                let start = summary
+                    .start
+                    .map_or_else(|| "-".to_string(), |t| t.format("%Y-%m-%d %H:%M:%S").to_string());
+                writeln!(
+                    out,
+                    "{:<width$}  {:<19}  {:>8}  {:>9.1}s  {:>6}",
+                    row.id,
+                    start,
+                    summary.entries,
+                    summary.duration_secs,
+                    summary.errors,
+                    width = width
+                )?;
+            }
+            Err(e) => {
+                writeln!(
+                    out,
+                    "{:<width$}  {} failed to parse: {}",
+                    row.id,
+                    OVERVIEW_ERROR_MARKER,
+                    e,
+                    width = width
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Matching entries printed per REPL query before the rest are counted
+const REPL_MAX_SHOWN: usize = 10;
+
+/// Commands understood by the REPL, shown by `help`
+const REPL_HELP: &str = "\
+Commands:
+  agent <name>       entries whose agent name contains <name>
+  contains <text>    entries whose message contains <text>
+  regex <pattern>    entries whose message matches <pattern>
+  type <level>       entries of one type (info, warning, error, agent, ...)
+  all                every entry
+  help               show this help
+  quit               leave the REPL";
+
+/// One line of REPL input
+enum ReplCommand {
+    /// Print the entries matching a filter
+    Query(EntryFilter),
+    Help,
+    Quit,
+}
+
+fn handle_repl(out: &mut dyn Write, logs_dir: &Path, options: &ParseOptions) -> ParseResult<()> {
+    if !logs_dir.exists() {
+        return Err(ParseError::FileNotFound(logs_dir.to_path_buf()));
+    }
+
+    let files = find_log_files(logs_dir, None)?;
+    let progress = ProgressBar::hidden();
+    let sessions = parse_files_parallel(&files, ParseMode::Full, options, &progress);
+    let session = merge_sessions(sessions);
+
+    writeln!(
+        out,
+        "Loaded {} entries from {} files; type 'help' for commands",
+        session.entries.len(),
+        files.len()
+    )?;
+
+    run_repl(io::stdin().lock(), out, &session.entries)
+}
+
+/// Answer queries read from `input` until `quit` or the end of input
+///
+/// Every line is a separate query over all of `entries`; mistakes are
+/// reported and the loop carries on.
+fn run_repl<R: BufRead>(input: R, out: &mut dyn Write, entries: &[LogEntry]) -> ParseResult<()> {
+    write!(out, "> ")?;
+    out.flush()?;
+
+    for line in input.lines() {
+        match parse_repl_command(&line?) {
+            Ok(Some(ReplCommand::Quit)) => return Ok(()),
+            Ok(Some(ReplCommand::Help)) => writeln!(out, "{}", REPL_HELP)?,
+            Ok(Some(ReplCommand::Query(filter))) => write_repl_matches(out, entries, &filter)?,
+            Ok(None) => {}
+            Err(message) => writeln!(out, "{}", message)?,
+        }
+
+        write!(out, "> ")?;
+        out.flush()?;
+    }
+
+    // End of input leaves the cursor after a prompt
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Parse a REPL line; blank lines yield `None`
+fn parse_repl_command(line: &str) -> Result<Option<ReplCommand>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    let (command, arg) = match line.split_once(char::is_whitespace) {
+        Some((command, arg)) => (command, arg.trim()),
+        None => (line, ""),
+    };
+    let needs_arg = || {
+        if arg.is_empty() {
+            Err(format!("'{}' needs an argument; type 'help' for commands", command))
+        } else {
+            Ok(arg)
+        }
+    };
+
+    let filter = EntryFilter::new();
+    let command = match command {
+        "quit" | "exit" => ReplCommand::Quit,
+        "help" => ReplCommand::Help,
+        "all" => ReplCommand::Query(filter),
+        "agent" => ReplCommand::Query(filter.with_agent(needs_arg()?)),
+        "contains" => ReplCommand::Query(filter.with_contains(needs_arg()?)),
+        "regex" => {
+            let matcher = TextMatcher::new(needs_arg()?, true).map_err(|e| e.to_string())?;
+            ReplCommand::Query(filter.with_text_matcher(matcher))
+        }
+        "type" => ReplCommand::Query(filter.with_type(parse_entry_type_arg(needs_arg()?)?)),
+        other => return Err(format!("Unknown command '{}'; type 'help' for commands", other)),
+    };
+
+    Ok(Some(command))
+}
+
+/// Print the match count and the first `REPL_MAX_SHOWN` matching entries
+fn write_repl_matches(
+    out: &mut dyn Write,
+    entries: &[LogEntry],
+    filter: &EntryFilter,
+) -> io::Result<()> {
+    let matches: Vec<&LogEntry> = entries.iter().filter(|e| filter.matches(e)).collect();
+
+    writeln!(out, "{} matching entries", matches.len())?;
+    for entry in matches.iter().take(REPL_MAX_SHOWN) {
+        writeln!(out, "  {}", entry)?;
+    }
+    if matches.len() > REPL_MAX_SHOWN {
+        writeln!(out, "  ... and {} more", matches.len() - REPL_MAX_SHOWN)?;
+    }
+    Ok(())
+}
+
+fn handle_schema(out: &mut dyn Write) -> ParseResult<()> {
+    writeln!(out, "{}", serde_json::to_string_pretty(&schema())?)?;
+    Ok(())
+}
+
+fn handle_bench(
+    out: &mut dyn Write,
+    logs_dir: &Path,
+    iterations: u32,
+    all_files: bool,
+    options: &ParseOptions,
+) -> ParseResult<()> {
+    writeln!(out, "Running benchmarks with {} iterations", iterations)?;
+
+    if !logs_dir.exists() {
+        return Err(ParseError::FileNotFound(logs_dir.to_path_buf()));
+    }
+
+    let bench_files = if all_files {
+        find_log_files(logs_dir, None)?
+    } else {
+        std::fs::read_dir(logs_dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .find(|path| is_log_file(path))
+            .into_iter()
+            .collect()
+    };
+
+    match bench_files.as_slice() {
+        [] => {
+            writeln!(out, "No log files found for benchmarking")?;
+            return Ok(());
+        }
+        [test_file] if !all_files => {
+            writeln!(out, "Benchmarking with file: {}", test_file.display())?;
+        }
+        files => {
+            writeln!(
+                out,
+                "Benchmarking with all {} files in: {}",
+                files.len(),
+                logs_dir.display()
+            )?;
+        }
+    }
+    writeln!(out, "{:=<80}", "")?;
+
+    let mut parse_times = Vec::new();
+
+    writeln!(out, "\nRunning parse benchmarks...")?;
+    for i in 0..iterations {
+        let start = Instant::now();
+        let entries = parse_bench_files(&bench_files, options)?;
+        let elapsed = start.elapsed();
+        parse_times.push(elapsed.as_micros() as f64 / 1000.0);
+
+        if i == 0 {
+            writeln!(out, "  First run parsed {} entries", entries)?;
+        }
+
+        if (i + 1) % 10 == 0 {
+            write!(out, ".")?;
+            if (i + 1) % 50 == 0 {
+                writeln!(out, " {}/{}", i + 1, iterations)?;
+            }
+        }
+    }
+    writeln!(out)?;
+
+    let parse_summary = TimingSummary::from_samples(&parse_times);
+    let throughput = measure_throughput(&bench_files, parse_summary.median, options)?;
+
+    writeln!(out, "\n{:=<80}", "")?;
+    writeln!(out, "BENCHMARK RESULTS")?;
+    writeln!(out, "{:=<80}", "")?;
+    writeln!(out, "Parse Performance:")?;
+    writeln!(out, "  Iterations: {}", iterations)?;
+    parse_summary.write(out)?;
+    writeln!(
+        out,
+        "  Throughput (median): {:.0} entries/s, {:.2} MB/s ({} entries, {} bytes)",
+        throughput.entries_per_sec, throughput.mb_per_sec, throughput.entries, throughput.bytes
+    )?;
+
+    let parsed: Vec<LogSession> = bench_files
+        .iter()
+        .filter_map(|path| parse_log_file_with_options(path, options).ok())
+        .map(|entries| LogSession::from_entries("bench", entries))
+        .collect();
+
+    if !parsed.is_empty() {
+        let session = merge_sessions(parsed);
+
+        let mut analyzer_times = Vec::new();
+
+        writeln!(out, "\nRunning analyzer benchmarks...")?;
+        for _ in 0..iterations {
+            let start = Instant::now();
+
+            let timing_analyzer = TimingAnalyzer::new();
+            let _ = timing_analyzer.analyze(&session);
+
+            let agent_analyzer = AgentAnalyzer::new();
+            let _ = agent_analyzer.analyze(&session);
+
+            let pattern_analyzer = PatternAnalyzer::new();
+            let _ = pattern_analyzer.analyze(&session);
+
+            let elapsed = start.elapsed();
+            analyzer_times.push(elapsed.as_micros() as f64 / 1000.0);
+        }
+
+        writeln!(out, "\nAnalyzer Performance (all 3 analyzers):")?;
+        TimingSummary::from_samples(&analyzer_times).write(out)?;
+    }
+
+    writeln!(out, "{:=<80}", "")?;
+
+    Ok(())
+}
+
+/// Min/avg/median/max of repeated timings in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TimingSummary {
+    min: f64,
+    avg: f64,
+    median: f64,
+    max: f64,
+}
+
+impl TimingSummary {
+    /// Summarize samples; an empty slice yields all zeros
+    fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self { min: 0.0, avg: 0.0, median: 0.0, max: 0.0 };
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2.0
+        } else {
+            sorted[mid]
+        };
+
+        Self {
+            min: sorted[0],
+            avg: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            median,
+            max: sorted[sorted.len() - 1],
+        }
+    }
+
+    fn write(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "  Average time: {:.2}ms", self.avg)?;
+        writeln!(out, "  Median time: {:.2}ms", self.median)?;
+        writeln!(out, "  Min time: {:.2}ms", self.min)?;
+        writeln!(out, "  Max time: {:.2}ms", self.max)?;
+        Ok(())
+    }
+}
+
+/// Parse every benchmarked file once, in parallel like `analyze`, and
+/// return the total number of entries
+fn parse_bench_files(paths: &[PathBuf], options: &ParseOptions) -> ParseResult<usize> {
+    paths
+        .par_iter()
+        .map(|path| parse_log_file_mmap(path, options).map(|entries| entries.len()))
+        .sum()
+}
+
+/// Parse throughput for a set of files at a given time to parse them all
+#[derive(Debug, Clone, Copy)]
+struct Throughput {
+    entries: usize,
+    bytes: u64,
+    entries_per_sec: f64,
+    mb_per_sec: f64,
+}
+
+/// Compute throughput from the total size of the files and the entries of
+/// a single parse of each
+fn measure_throughput(
+    paths: &[PathBuf],
+    parse_ms: f64,
+    options: &ParseOptions,
+) -> ParseResult<Throughput> {
+    let mut bytes = 0;
+    let mut entries = 0;
+
+    for path in paths {
+        bytes += std::fs::metadata(path)?.len();
+        entries += parse_log_file_with_options(path, options)?.len();
+    }
+
+    // Guard against a zero timing on tiny files
+    let secs = parse_ms.max(1e-6) / 1000.0;
+
+    Ok(Throughput {
+        entries,
+        bytes,
+        entries_per_sec: entries as f64 / secs,
+        mb_per_sec: bytes as f64 / (1024.0 * 1024.0) / secs,
+    })
+}
+
+/// How `parse_files_parallel` reads each file
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParseMode {
+    /// Parse every file from scratch
+    Full,
+
+    /// Load unchanged files from their cache sidecar
+    Cached,
+
+    /// Keep a random `rate` fraction of entries; each file's sample is
+    /// seeded from `seed` and its position, so reruns keep the same entries
+    Sampled { rate: f64, seed: u64 },
+}
+
+impl ParseMode {
+    /// Parse the file at position `idx` of the input list
+    fn parse(self, idx: usize, path: &Path, options: &ParseOptions) -> ParseResult<Vec<LogEntry>> {
+        match self {
+            ParseMode::Full => parse_log_file_with_options(path, options),
+            ParseMode::Cached => parse_log_file_cached(path, options),
+            ParseMode::Sampled { rate, seed } => {
+                parse_log_file_sampled(path, rate, seed.wrapping_add(idx as u64), options)
+            }
+        }
+    }
+}
+
+/// Parse many log files in parallel, one session per file
+///
+/// Files that fail to parse are reported and skipped. Sessions are named
+/// after their file and returned in `paths` order, ready for
+/// `merge_sessions`.
+fn parse_files_parallel<W: Write + Send>(
+    paths: &[PathBuf],
+    mode: ParseMode,
+    options: &ParseOptions,
+    progress: &ProgressBar<W>,
+) -> Vec<LogSession> {
+    let results: Vec<_> = paths
+        .par_iter()
+        .enumerate()
+        .map(|(idx, path)| {
+            let result = mode.parse(idx, path, options);
+            let name = path.file_name().unwrap_or(path.as_os_str());
+            progress.inc(&name.to_string_lossy());
+            (path, result)
+        })
+        .collect();
+
+    progress.finish();
+
+    let mut sessions = Vec::new();
+
+    for (path, result) in results {
+        match result {
+            Ok(entries) => {
+                log_info!("Parsed {}: {} entries", path.display(), entries.len());
+                sessions.push(LogSession::from_entries(&session_id(path), entries));
+            }
+            Err(e) => {
+                log_warn!("Failed to parse {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    sessions
+}
+
+/// Stable-sort query results by the chosen field
+fn sort_entries(entries: &mut [&LogEntry], field: SortField) {
+    match field {
+        SortField::Time => entries.sort_by_key(|e| e.timestamp),
+        SortField::TimeDesc => entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp)),
+        SortField::Agent => {
+            entries.sort_by(|a, b| match (&a.agent_name, &b.agent_name) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            })
+        }
+        SortField::Type => entries.sort_by_key(|e| e.entry_type),
+    }
+}
+
+/// Slice one page of results, returning an empty page when `offset` is
+/// past the end
+fn paginate<T>(items: &[T], offset: usize, limit: usize) -> &[T] {
+    let start = offset.min(items.len());
+    let end = start.saturating_add(limit).min(items.len());
+    &items[start..end]
+}
+
+/// Earliest timestamp, used as the zero point for `--relative`
+fn relative_base(entries: &[LogEntry]) -> Option<DateTime<Utc>> {
+    entries.iter().map(|e| e.timestamp).min()
+}
+
+/// Shorten `message` to `max_width` characters followed by `...`
+///
+/// Counts chars rather than bytes so multibyte text is never split. A
+/// width of 0 disables truncation.
+fn truncate_message(message: &str, max_width: usize) -> Cow<'_, str> {
+    if max_width == 0 {
+        return Cow::Borrowed(message);
+    }
+
+    match message.char_indices().nth(max_width) {
+        Some((end, _)) => Cow::Owned(format!("{}...", &message[..end])),
+        None => Cow::Borrowed(message),
+    }
+}
+
+/// Format a timestamp absolutely, or as `+MM:SS.mmm` from `base` if given
+fn format_entry_time(timestamp: DateTime<Utc>, base: Option<DateTime<Utc>>) -> String {
+    let Some(base) = base else {
+        return timestamp.format("%Y-%m-%d %H:%M:%S").to_string();
+    };
+
+    let delta_ms = (timestamp - base).num_milliseconds();
+    let sign = if delta_ms < 0 { '-' } else { '+' };
+    let ms = delta_ms.unsigned_abs();
+
+    format!("{}{:02}:{:02}.{:03}", sign, ms / 60_000, ms / 1000 % 60, ms % 1000)
+}
+
+/// Count entries per combination of group keys
+///
+/// Keys are ordered by the first field, then the second, and so on, so
+/// nested groups print next to each other.
+fn group_counts(entries: &[&LogEntry], fields: &[GroupField]) -> BTreeMap<Vec<String>, usize> {
+    let mut counts = BTreeMap::new();
+
+    for entry in entries {
+        let key = fields.iter().map(|field| field.key(entry)).collect();
+        *counts.entry(key).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+fn write_group_counts(
+    out: &mut dyn Write,
+    fields: &[GroupField],
+    counts: &BTreeMap<Vec<String>, usize>,
+) -> io::Result<()> {
+    let widths: Vec<usize> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            counts
+                .keys()
+                .map(|key| key[i].len())
+                .chain(std::iter::once(field.label().len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for (field, width) in fields.iter().zip(&widths) {
+        write!(out, "{:<width$}  ", field.label(), width = width)?;
+    }
+    writeln!(out, "count")?;
+    writeln!(out, "{:-<80}", "")?;
+
+    for (key, count) in counts {
+        for (value, width) in key.iter().zip(&widths) {
+            write!(out, "{:<width$}  ", value, width = width)?;
+        }
+        writeln!(out, "{}", count)?;
+    }
+    Ok(())
+}
+
+/// Parse a `--sample` rate, which must be in `(0, 1]`
+fn parse_sample_rate(s: &str) -> Result<f64, String> {
+    let rate: f64 = s
+        .parse()
+        .map_err(|_| format!("invalid sample rate '{}' (expected a number like 0.1)", s))?;
+
+    if rate > 0.0 && rate <= 1.0 {
+        Ok(rate)
+    } else {
+        Err(format!("sample rate must be greater than 0 and at most 1, got {}", s))
+    }
+}
+
+/// Parse a `--type` value, rejecting names that aren't entry types
+fn parse_entry_type_arg(s: &str) -> Result<EntryType, String> {
+    match parse_entry_type(s) {
+        EntryType::Unknown if !s.eq_ignore_ascii_case("unknown") => Err(format!(
+            "unknown entry type '{}' \
+             (expected info, warning, error, agent, decision, debug, trace or unknown)",
+            s
+        )),
+        entry_type => Ok(entry_type),
+    }
+}
+
+fn count_entry_types(entries: &[LogEntry]) -> Vec<(EntryType, usize)> {
+    use std::collections::HashMap;
+
+    let mut counts = HashMap::new();
+
+    for entry in entries {
+        *counts.entry(entry.entry_type).or_insert(0) += 1;
+    }
+
+    let mut result: Vec<_> = counts.into_iter().collect();
+    result.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    result
+}
+
+/// Load a session from a log file or a directory of log files
+///
+/// The session id is the final path component. For directories, every
+/// `.log`/`.log.gz` file inside is parsed and merged chronologically.
+fn load_session(path: &Path, options: &ParseOptions) -> ParseResult<LogSession> {
+    if !path.exists() {
+        return Err(ParseError::FileNotFound(path.to_path_buf()));
+    }
+
+    let entries = if path.is_dir() {
+        let files = find_log_files(path, None)?;
+        let progress = ProgressBar::hidden();
+        merge_sessions(parse_files_parallel(&files, ParseMode::Full, options, &progress)).entries
+    } else {
+        parse_log_file_with_options(path, options)?
+    };
+
+    Ok(LogSession::from_entries(&session_id(path), entries))
+}
+
+/// Like `load_session`, also tallying malformed lines across all files
+///
+/// Files in a directory that can't be read are reported and skipped.
+fn load_session_report(
+    path: &Path,
+    options: &ParseOptions,
+) -> ParseResult<(LogSession, ParseReport)> {
+    if !path.exists() {
+        return Err(ParseError::FileNotFound(path.to_path_buf()));
+    }
+
+    if !path.is_dir() {
+        let (entries, report) = parse_log_file_report(path, options)?;
+        return Ok((LogSession::from_entries(&session_id(path), entries), report));
+    }
+
+    let files = find_log_files(path, None)?;
+    let results: Vec<_> =
+        files.par_iter().map(|file| parse_log_file_report(file, options)).collect();
+
+    let mut sessions = Vec::new();
+    let mut report = ParseReport::default();
+    for (file, result) in files.iter().zip(results) {
+        match result {
+            Ok((entries, file_report)) => {
+                report.merge(&file_report);
+                sessions.push(LogSession::from_entries(&session_id(file), entries));
+            }
+            Err(e) => log_warn!("Failed to parse {}: {}", file.display(), e),
+        }
+    }
+
+    let entries = merge_sessions(sessions).entries;
+    Ok((LogSession::from_entries(&session_id(path), entries), report))
+}
+
+/// JSON accepted by `analyze --from-json`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonSessionInput {
+    Session(LogSession),
+    Entries(Vec<LogEntry>),
+}
+
+/// Load a session serialized as JSON, bypassing the text parser
+///
+/// Accepts either a whole `LogSession` or a bare array of entries; the
+/// latter is named after the file.
+fn load_json_session(path: &Path) -> ParseResult<LogSession> {
+    let file = std::fs::File::open(path)
+        .map_err(|_| ParseError::FileNotFound(path.to_path_buf()))?;
+
+    match serde_json::from_reader(std::io::BufReader::new(file))? {
+        JsonSessionInput::Session(session) => Ok(session),
+        JsonSessionInput::Entries(entries) => {
+            Ok(LogSession::from_entries(&session_id(path), entries))
+        }
+    }
+}
+
+/// Fetch and parse a log served over HTTP
+#[cfg(feature = "http")]
+fn load_url_session(url: &str, options: &ParseOptions) -> ParseResult<LogSession> {
+    Ok(LogSession::from_entries(url, parse_log_url(url, options)?))
+}
+
+#[cfg(not(feature = "http"))]
+fn load_url_session(url: &str, _options: &ParseOptions) -> ParseResult<LogSession> {
+    Err(ParseError::Http(format!(
+        "cannot fetch {}: built without the `http` feature",
+        url
+    )))
+}
+
+/// Like `load_session`, but fail if any file exceeds the malformed-line budget
+fn load_session_strict(
+    path: &Path,
+    max_errors: usize,
+    options: &ParseOptions,
+) -> ParseResult<LogSession> {
+    if !path.exists() {
+        return Err(ParseError::FileNotFound(path.to_path_buf()));
+    }
+
+    let files = if path.is_dir() {
+        find_log_files(path, None)?
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut entries = Vec::new();
+    for file in &files {
+        entries.extend(parse_log_file_strict(file, max_errors, options)?);
+    }
+    entries.sort_by_key(|entry| entry.timestamp);
+
+    Ok(LogSession::from_entries(&session_id(path), entries))
+}
+
+/// Session id for a path: its final component
+fn session_id(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amplihack_logparse::parser::parse_log_file;
+    use amplihack_logparse::analyzer::{LogPattern, PatternAnalysis};
+    use chrono::Duration;
+    use std::collections::HashMap;
+
+    fn entry_at(timestamp: DateTime<Utc>, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp,
+            entry_type: EntryType::Info,
+            message: message.to_string(),
+            agent_name: None,
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        }
+    }
+
+    fn entries_over_days() -> Vec<LogEntry> {
+        let base = parse_timestamp("2025-10-15T12:00:00Z").unwrap();
+        (0..5)
+            .map(|day| entry_at(base + Duration::days(day), &format!("day {}", day)))
+            .collect()
+    }
+
+    fn messages_in_range(since: Option<&str>, until: Option<&str>) -> Vec<String> {
+        let since = since.map(|s| parse_timestamp(s).unwrap());
+        let until = until.map(|s| parse_timestamp(s).unwrap());
+
+        entries_over_days()
+            .into_iter()
+            .filter(|entry| EntryFilter::new().with_time_range(since, until).matches(entry))
+            .map(|entry| entry.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_files_parallel_matches_sequential() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let mut paths = Vec::new();
+
+        for file_idx in 0..8 {
+            let path = dir.path().join(format!("session-{}.log", file_idx));
+            let contents: String = (0..50)
+                .map(|line| {
+                    // Interleave timestamps across files
+                    let secs = line * 8 + file_idx;
+                    format!(
+                        "[2025-10-18T14:{:02}:{:02}Z] INFO: file {} line {}\n",
+                        secs / 60,
+                        secs % 60,
+                        file_idx,
+                        line
+                    )
+                })
+                .collect();
+            std::fs::write(&path, contents).unwrap();
+            paths.push(path);
+        }
+        paths.push(dir.path().join("missing.log"));
+
+        let mut sequential: Vec<LogEntry> = paths
+            .iter()
+            .filter_map(|path| parse_log_file(path).ok())
+            .flatten()
+            .collect();
+        sequential.sort_by_key(|entry| entry.timestamp);
+
+        let progress = ProgressBar::hidden();
+        let sessions = parse_files_parallel(&paths, ParseMode::Full, &options, &progress);
+        let parallel = merge_sessions(sessions).entries;
+
+        assert_eq!(parallel.len(), 400);
+        assert_eq!(parallel.len(), sequential.len());
+        for (a, b) in parallel.iter().zip(sequential.iter()) {
+            assert_eq!(a.timestamp, b.timestamp);
+            assert_eq!(a.message, b.message);
+        }
+        assert!(parallel.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+    }
+
+    #[test]
+    fn test_load_session_merges_directory() {
+        let options = ParseOptions::default();
+        let root = tempfile::tempdir().unwrap();
+        let session_dir = root.path().join("session-123");
+        std::fs::create_dir(&session_dir).unwrap();
+
+        std::fs::write(
+            session_dir.join("b.log"),
+            "[2025-10-18T14:30:47Z] INFO: from b\n",
+        )
+        .unwrap();
+        std::fs::write(
+            session_dir.join("a.log"),
+            "[2025-10-18T14:30:45Z] INFO: from a\n[2025-10-18T14:30:49Z] INFO: later a\n",
+        )
+        .unwrap();
+        std::fs::write(session_dir.join("notes.txt"), "ignored").unwrap();
+
+        let session = load_session(&session_dir, &options).unwrap();
+
+        assert_eq!(session.id, "session-123");
+        let messages: Vec<&str> = session.entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["from a", "from b", "later a"]);
+        assert_eq!(session.start_time, session.entries[0].timestamp);
+        assert_eq!(session.end_time, Some(session.entries[2].timestamp));
+    }
+
+    fn analyze_args(extra: &[&str]) -> AnalyzeArgs {
+        let mut argv = vec!["amplihack-logparse", "analyze"];
+        argv.extend_from_slice(extra);
+
+        let cli = Cli::parse_from(argv);
+        match cli.command {
+            Commands::Analyze(args) => AnalyzeArgs { quiet: cli.quiet, ..args },
+            _ => panic!("Expected analyze command"),
+        }
+    }
+
+    #[test]
+    fn test_quiet_and_verbose_set_log_level() {
+        let level = |args: &[&str]| {
+            let mut argv = vec!["amplihack-logparse"];
+            argv.extend_from_slice(args);
+            log_level(&Cli::parse_from(argv))
+        };
+
+        assert_eq!(level(&["schema"]), Level::Info);
+        assert_eq!(level(&["-q", "schema"]), Level::Error);
+        assert_eq!(level(&["schema", "--verbose"]), Level::Debug);
+        assert_eq!(level(&["analyze", "-v"]), Level::Debug);
+
+        let both = Cli::try_parse_from(["amplihack-logparse", "schema", "-q", "-v"]);
+        assert!(both.is_err());
+    }
+
+    #[test]
+    fn test_long_gap_threshold_flag() {
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let entries = (0..4)
+            .map(|i| entry_at(base + Duration::seconds(i * 60), "tick"))
+            .collect();
+        let session = LogSession::from_entries("gaps", entries);
+
+        let has_long_gap = |analysis: PatternAnalysis| {
+            analysis
+                .patterns
+                .iter()
+                .any(|p| matches!(p, LogPattern::LongGap { .. }))
+        };
+
+        // Default threshold (300s) ignores one-minute gaps
+        let default = analyze_args(&[]).pattern_analyzer();
+        assert!(!has_long_gap(default.analyze(&session).unwrap()));
+
+        let sensitive = analyze_args(&["--long-gap-threshold", "30"]).pattern_analyzer();
+        assert!(has_long_gap(sensitive.analyze(&session).unwrap()));
+    }
+
+    fn agent_entry_at(timestamp: DateTime<Utc>, agent: Option<&str>) -> LogEntry {
+        LogEntry {
+            agent_name: agent.map(String::from),
+            ..entry_at(timestamp, "call")
+        }
+    }
+
+    #[test]
+    fn test_sort_time_desc_reverses_order() {
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let entries: Vec<LogEntry> = (0..3)
+            .map(|i| entry_at(base + Duration::seconds(i), "tick"))
+            .collect();
+        let mut refs: Vec<&LogEntry> = entries.iter().collect();
+
+        sort_entries(&mut refs, SortField::TimeDesc);
+
+        let times: Vec<_> = refs.iter().map(|e| e.timestamp).collect();
+        assert_eq!(
+            times,
+            vec![entries[2].timestamp, entries[1].timestamp, entries[0].timestamp]
+        );
+    }
+
+    #[test]
+    fn test_sort_agent_groups_stably() {
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let entries = [
+            agent_entry_at(base, Some("builder")),
+            agent_entry_at(base + Duration::seconds(1), None),
+            agent_entry_at(base + Duration::seconds(2), Some("architect")),
+            agent_entry_at(base + Duration::seconds(3), Some("builder")),
+        ];
+        let mut refs: Vec<&LogEntry> = entries.iter().collect();
+
+        sort_entries(&mut refs, SortField::Agent);
+
+        let order: Vec<_> = refs
+            .iter()
+            .map(|e| (e.agent_name.as_deref(), e.timestamp))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (Some("architect"), entries[2].timestamp),
+                (Some("builder"), entries[0].timestamp),
+                (Some("builder"), entries[3].timestamp),
+                (None, entries[1].timestamp),
+            ]
+        );
+    }
+
+    fn query_args(extra: &[&str]) -> QueryArgs {
+        let mut argv = vec!["amplihack-logparse", "query"];
+        argv.extend_from_slice(extra);
+
+        match Cli::parse_from(argv).command {
+            Commands::Query(args) => args,
+            _ => panic!("Expected query command"),
+        }
+    }
+
+    fn typed_entries() -> Vec<LogEntry> {
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        [EntryType::Info, EntryType::Error, EntryType::Warning, EntryType::Error]
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry_type)| LogEntry {
+                entry_type,
+                ..entry_at(base + Duration::seconds(i as i64), "msg")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_query_count_prints_only_the_count() {
+        let options = ParseOptions::default();
+        let args = query_args(&["--count", "--type", "error"]);
+        let entries = typed_entries();
+
+        let filter = entry_filter(&args, &options).unwrap();
+        let filtered: Vec<_> = entries.iter().filter(|e| filter.matches(e)).collect();
+        let mut out = Vec::new();
+        write_count(&mut out, &filtered).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "2\n");
+    }
+
+    #[test]
+    fn test_min_duration_keeps_only_slow_entries() {
+        let options = ParseOptions::default();
+        let filter = entry_filter(&query_args(&["--min-duration", "500"]), &options).unwrap();
+        let mut entries = typed_entries();
+        entries[0].duration_ms = Some(499);
+        entries[1].duration_ms = Some(500);
+        entries[2].duration_ms = Some(12_000);
+
+        let durations: Vec<_> = entries
+            .iter()
+            .filter(|e| filter.matches(e))
+            .map(|e| e.duration_ms)
+            .collect();
+
+        // The last entry has no duration and is excluded
+        assert_eq!(durations, vec![Some(500), Some(12_000)]);
+    }
+
+    #[test]
+    fn test_type_filter_single() {
+        let options = ParseOptions::default();
+        let filter = entry_filter(&query_args(&["--type", "ERROR"]), &options).unwrap();
+        let entries = typed_entries();
+
+        let matched: Vec<_> = entries
+            .iter()
+            .filter(|e| filter.matches(e))
+            .collect();
+
+        assert_eq!(matched.len(), 2);
+        assert!(matched.iter().all(|e| e.entry_type == EntryType::Error));
+    }
+
+    #[test]
+    fn test_type_filter_multiple() {
+        let options = ParseOptions::default();
+        let args = query_args(&["--type", "warning", "--type", "info"]);
+        let filter = entry_filter(&args, &options).unwrap();
+        let entries = typed_entries();
+
+        let matched: Vec<_> = entries
+            .iter()
+            .filter(|e| filter.matches(e))
+            .map(|e| e.entry_type)
+            .collect();
+
+        assert_eq!(matched, vec![EntryType::Info, EntryType::Warning]);
+    }
+
+    #[test]
+    fn test_type_filter_rejects_unknown_names() {
+        assert_eq!(parse_entry_type_arg("agent"), Ok(EntryType::AgentInvocation));
+        assert_eq!(parse_entry_type_arg("Unknown"), Ok(EntryType::Unknown));
+        assert!(parse_entry_type_arg("bogus").is_err());
+    }
+
+    #[test]
+    fn test_error_context_windows_middle() {
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let mut entries: Vec<LogEntry> = (0..9)
+            .map(|i| entry_at(base + Duration::seconds(i), "tick"))
+            .collect();
+        entries[4].entry_type = EntryType::Error;
+
+        let windows = error_context_windows(&entries, 2);
+
+        assert_eq!(windows, vec![(4, 2..7)]);
+    }
+
+    #[test]
+    fn test_error_context_windows_clamped_at_edges() {
+        let entries = typed_entries();
+        // Errors at index 1 and 3 of 4 entries
+        let windows = error_context_windows(&entries, 5);
+
+        assert_eq!(windows, vec![(1, 0..4), (3, 0..4)]);
+        assert!(error_context_windows(&entries[..1], 3).is_empty());
+    }
+
+    #[test]
+    fn test_handle_validate_valid_file() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("valid.log");
+        std::fs::write(&path, "[2025-10-18T14:30:45Z] INFO: ok\n").unwrap();
+
+        let mut out = Vec::new();
+        assert!(handle_validate(&mut out, &path, &options).is_ok());
+        assert!(String::from_utf8(out).unwrap().contains("  Valid entries: 1\n"));
+    }
+
+    #[test]
+    fn test_handle_validate_malformed_file() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invalid.log");
+        std::fs::write(&path, "[2025-10-18T14:30:45Z] INFO: ok\n[bad] INFO: x\n[worse\n").unwrap();
+
+        let mut out = Vec::new();
+        match handle_validate(&mut out, &path, &options) {
+            Err(ParseError::ValidationFailed { failures }) => assert_eq!(failures, 2),
+            other => panic!("Expected ValidationFailed, got {:?}", other),
+        }
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains(
+            "Failures by kind:\n  no closing bracket: 1\n  invalid timestamp: 1\n"
+        ));
+    }
+
+    #[test]
+    fn test_load_session_report_merges_directory_tallies() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("a.log"),
+            "[2025-10-18T14:30:00Z] INFO: ok\n[bad] INFO: x\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("b.log"),
+            "[2025-10-18T14:29:00Z] INFO: earlier\n[nope] INFO: y\n[open\n",
+        )
+        .unwrap();
+
+        let (session, report) = load_session_report(dir.path(), &options).unwrap();
+
+        assert_eq!(session.entries.len(), 2);
+        assert_eq!(session.entries[0].message, "earlier");
+        assert_eq!(report.entries, 2);
+        assert_eq!(report.summary(), "  no closing bracket: 1\n  invalid timestamp: 2\n");
+    }
+
+    #[test]
+    fn test_overview_lists_one_row_per_log_sorted_by_start() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("a.log"),
+            "[2025-10-18T15:00:00Z] INFO: later\n[2025-10-18T15:01:30Z] ERROR: failed\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("b.log"),
+            "[2025-10-18T09:00:00Z] INFO: one\n\
+             [2025-10-18T09:00:02Z] INFO: two\n\
+             [2025-10-18T09:00:04Z] INFO: three\n",
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        handle_overview(&mut out, dir.path(), &options).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let rows: Vec<&str> = text.lines().skip(3).collect();
+
+        assert!(text.starts_with(&format!("Sessions in {}: 2\n", dir.path().display())));
+        assert_eq!(
+            rows,
+            vec![
+                "b.log    2025-10-18 09:00:00         3        4.0s       0",
+                "a.log    2025-10-18 15:00:00         2       90.0s       1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overview_marks_unparseable_files() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let good = dir.path().join("good.log");
+        let bad = dir.path().join("bad.log.gz");
+        let empty = dir.path().join("empty.log");
+        std::fs::write(&good, "[2025-10-18T09:00:00Z] INFO: ok\n").unwrap();
+        // Gzip magic bytes followed by a corrupt stream
+        std::fs::write(&bad, b"\x1f\x8b\x08\x00garbage").unwrap();
+        std::fs::write(&empty, "").unwrap();
+
+        let rows = overview_rows(&[bad, empty, good], &options);
+        let ids: Vec<&str> = rows.iter().map(|row| row.id.as_str()).collect();
+        assert_eq!(ids, vec!["good.log", "empty.log", "bad.log.gz"]);
+        assert!(rows[2].summary.is_err());
+
+        let mut out = Vec::new();
+        write_overview(&mut out, &rows).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("empty.log   -                           0        0.0s       0\n"));
+        assert!(text.contains("bad.log.gz  !! failed to parse: "));
+    }
+
+    #[test]
+    fn test_timing_summary_median() {
+        let summary = TimingSummary::from_samples(&[9.0, 1.0, 2.0, 3.0]);
+        assert_eq!(
+            summary,
+            TimingSummary { min: 1.0, avg: 3.75, median: 2.5, max: 9.0 }
+        );
+        assert_eq!(TimingSummary::from_samples(&[5.0, 1.0, 3.0]).median, 3.0);
+    }
+
+    #[test]
+    fn test_measure_throughput_matches_parse() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bench.log");
+        let contents: String = (0..100)
+            .map(|i| format!("[2025-10-18T14:30:{:02}Z] INFO: line {}\n", i % 60, i))
+            .collect();
+        std::fs::write(&path, &contents).unwrap();
+
+        let throughput = measure_throughput(std::slice::from_ref(&path), 2.0, &options).unwrap();
+
+        assert_eq!(throughput.entries, parse_log_file(&path).unwrap().len());
+        assert_eq!(throughput.bytes, contents.len() as u64);
+        assert!(throughput.entries_per_sec > 0.0);
+        assert!(throughput.mb_per_sec > 0.0);
+    }
+
+    #[test]
+    fn test_bench_all_files_counts_entries_across_files() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let line = |i: usize| format!("[2025-10-18T14:30:{:02}Z] INFO: line {}\n", i % 60, i);
+        std::fs::write(dir.path().join("a.log"), (0..3).map(line).collect::<String>()).unwrap();
+        std::fs::write(dir.path().join("b.log"), (0..5).map(line).collect::<String>()).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), line(0)).unwrap();
+
+        let files = find_log_files(dir.path(), None).unwrap();
+        let expected: usize = files.iter().map(|path| parse_log_file(path).unwrap().len()).sum();
+        assert_eq!(expected, 8);
+        assert_eq!(parse_bench_files(&files, &options).unwrap(), expected);
+
+        let throughput = measure_throughput(&files, 1.0, &options).unwrap();
+        assert_eq!(throughput.entries, expected);
+
+        let run = |all_files| {
+            let mut out = Vec::new();
+            handle_bench(&mut out, dir.path(), 2, all_files, &options).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let all = run(true);
+        assert!(all.contains("Benchmarking with all 2 files in: "));
+        assert!(all.contains("  First run parsed 8 entries\n"));
+        assert!(all.contains(" entries/s, "));
+        assert!(all.contains("(8 entries, "));
+
+        // The default still benchmarks a single file
+        let single = run(false);
+        assert!(single.contains("Benchmarking with file: "));
+        assert!(!single.contains("parsed 8 entries"));
+    }
+
+    #[test]
+    fn test_paginate_mid_range() {
+        let items: Vec<u32> = (1..=50).collect();
+        assert_eq!(paginate(&items, 20, 20), &items[20..40]);
+        assert_eq!(paginate(&items, 45, 20), &[46, 47, 48, 49, 50]);
+    }
+
+    #[test]
+    fn test_paginate_offset_out_of_bounds() {
+        let items = vec![1, 2, 3];
+        assert!(paginate(&items, 10, 20).is_empty());
+        assert!(paginate(&items, 0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_query_pagination_flags() {
+        let cli = Cli::parse_from(["amplihack-logparse", "query", "--limit", "5", "--offset", "10"]);
+        match cli.command {
+            Commands::Query(args) => assert_eq!((args.limit, args.offset), (5, 10)),
+            _ => panic!("Expected query command"),
+        }
+    }
+
+    #[test]
+    fn test_config_fills_missing_flags() {
+        let config = Config {
+            logs_dir: Some(PathBuf::from("/from/config")),
+            format: Some("json".to_string()),
+            long_gap_threshold: Some(30.0),
+            ..Config::default()
+        };
+
+        let args = analyze_args(&[]).with_config(&config).unwrap();
+
+        assert_eq!(args.logs_dir(), PathBuf::from("/from/config"));
+        assert_eq!(args.format(), OutputFormat::Json);
+        assert_eq!(args.long_gap_threshold, Some(30.0));
+        assert_eq!(args.error_burst_threshold, None);
+    }
+
+    #[test]
+    fn test_flags_override_config() {
+        let config = Config {
+            logs_dir: Some(PathBuf::from("/from/config")),
+            format: Some("json".to_string()),
+            long_gap_threshold: Some(30.0),
+            ..Config::default()
+        };
+
+        let args = analyze_args(&[
+            "--logs-dir",
+            "/from/flag",
+            "--format",
+            "markdown",
+            "--long-gap-threshold",
+            "90",
+        ])
+        .with_config(&config)
+        .unwrap();
+
+        assert_eq!(args.logs_dir(), PathBuf::from("/from/flag"));
+        assert_eq!(args.format(), OutputFormat::Markdown);
+        assert_eq!(args.long_gap_threshold, Some(90.0));
+    }
+
+    #[test]
+    fn test_yaml_format_from_flag_and_config() {
+        assert_eq!(analyze_args(&["--format", "yaml"]).format(), OutputFormat::Yaml);
+
+        let config = Config {
+            format: Some("yaml".to_string()),
+            ..Config::default()
+        };
+        let args = analyze_args(&[]).with_config(&config).unwrap();
+        assert_eq!(args.format(), OutputFormat::Yaml);
+    }
+
+    #[test]
+    fn test_config_defaults_when_unset() {
+        let args = analyze_args(&[]).with_config(&Config::default()).unwrap();
+        assert_eq!(args.logs_dir(), PathBuf::from(DEFAULT_LOGS_DIR));
+        assert_eq!(args.format(), OutputFormat::Text);
+
+        let bad = Config {
+            format: Some("yaml-ish".to_string()),
+            ..Config::default()
+        };
+        assert!(matches!(
+            analyze_args(&[]).with_config(&bad),
+            Err(ParseError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_time_range_no_bounds() {
+        assert_eq!(messages_in_range(None, None).len(), 5);
+    }
+
+    #[test]
+    fn test_time_range_since_only() {
+        assert_eq!(
+            messages_in_range(Some("2025-10-17T12:00:00Z"), None),
+            vec!["day 2", "day 3", "day 4"]
+        );
+    }
+
+    #[test]
+    fn test_time_range_until_only() {
+        assert_eq!(
+            messages_in_range(None, Some("2025-10-16T00:00:00Z")),
+            vec!["day 0"]
+        );
+    }
+
+    #[test]
+    fn test_time_range_both_bounds() {
+        assert_eq!(
+            messages_in_range(Some("2025-10-16T00:00:00Z"), Some("2025-10-18T12:00:00Z")),
+            vec!["day 1", "day 2", "day 3"]
+        );
+    }
+
+    fn group_key(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_group_by_agent_counts() {
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let entries = [
+            agent_entry_at(base, Some("builder")),
+            agent_entry_at(base, Some("architect")),
+            agent_entry_at(base, Some("builder")),
+            agent_entry_at(base, None),
+        ];
+        let refs: Vec<&LogEntry> = entries.iter().collect();
+
+        let args = query_args(&["--group-by", "agent"]);
+        let counts = group_counts(&refs, &args.group_by);
+
+        assert_eq!(
+            counts,
+            BTreeMap::from([
+                (group_key(&["-"]), 1),
+                (group_key(&["architect"]), 1),
+                (group_key(&["builder"]), 2),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_group_by_hour_counts() {
+        let base = parse_timestamp("2025-10-18T14:10:00Z").unwrap();
+        let entries: Vec<LogEntry> = [0, 20, 45, 70, 200]
+            .into_iter()
+            .map(|mins| entry_at(base + Duration::minutes(mins), "tick"))
+            .collect();
+        let refs: Vec<&LogEntry> = entries.iter().collect();
+
+        let counts = group_counts(&refs, &query_args(&["--group-by", "hour"]).group_by);
+
+        assert_eq!(
+            counts,
+            BTreeMap::from([
+                (group_key(&["2025-10-18 14:00"]), 3),
+                (group_key(&["2025-10-18 15:00"]), 1),
+                (group_key(&["2025-10-18 17:00"]), 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_group_by_nested_fields() {
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let entries = [
+            agent_entry_at(base, Some("builder")),
+            agent_entry_at(base + Duration::hours(1), Some("builder")),
+            agent_entry_at(base + Duration::hours(1), Some("builder")),
+        ];
+        let refs: Vec<&LogEntry> = entries.iter().collect();
+
+        let args = query_args(&["--group-by", "agent,hour"]);
+        assert_eq!(args.group_by, vec![GroupField::Agent, GroupField::Hour]);
+
+        assert_eq!(
+            group_counts(&refs, &args.group_by),
+            BTreeMap::from([
+                (group_key(&["builder", "2025-10-18 14:00"]), 1),
+                (group_key(&["builder", "2025-10-18 15:00"]), 2),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_top_keeps_busiest_agents_in_order() {
+        let options = ParseOptions::default();
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let calls = [
+            ("architect", 100),
+            ("builder", 50),
+            ("builder", 50),
+            ("reviewer", 10),
+            ("reviewer", 10),
+            ("reviewer", 10),
+            ("architect", 200),
+        ];
+        let entries = calls
+            .iter()
+            .enumerate()
+            .map(|(i, (agent, ms))| LogEntry {
+                duration_ms: Some(*ms),
+                ..agent_entry_at(base + Duration::seconds(i as i64), Some(agent))
+            })
+            .collect();
+        let session = LogSession::from_entries("top", entries);
+
+        let report = build_report(&session, &analyze_args(&["--top", "2"]), &options).unwrap();
+        let names: Vec<_> = report.agents.iter().map(|a| a.name.as_str()).collect();
+
+        // architect and builder tie on invocations; architect ran longer
+        assert_eq!(names, vec!["reviewer", "architect"]);
+
+        let all = build_report(&session, &analyze_args(&[]), &options).unwrap();
+        assert_eq!(all.agents.len(), 3);
+    }
+
+    #[test]
+    fn test_ndjson_lines_deserialize_to_matching_entries() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let paths = [dir.path().join("a.log"), dir.path().join("b.log")];
+        std::fs::write(
+            &paths[0],
+            "[2025-10-18T14:30:00Z] INFO: Invoked agent: architect\n\
+             not a log line\n\
+             [2025-10-18T14:30:01Z] ERROR: boom status=\"500 internal\"\n\
+             with a continuation line\n",
+        )
+        .unwrap();
+        std::fs::write(&paths[1], "[2025-10-18T14:31:00Z] ERROR: second file\n").unwrap();
+
+        let filter = query_args(&["--type", "error"]);
+        let filter = entry_filter(&filter, &options).unwrap();
+
+        let mut out = Vec::new();
+        assert_eq!(write_ndjson(&mut out, &paths, &filter, &options).unwrap(), 2);
+        let out = String::from_utf8(out).unwrap();
+
+        let expected: Vec<_> = paths
+            .iter()
+            .flat_map(|path| parse_log_file(path).unwrap())
+            .filter(|entry| entry.entry_type == EntryType::Error)
+            .collect();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), expected.len());
+
+        for (line, entry) in lines.iter().zip(&expected) {
+            let parsed: LogEntry = serde_json::from_str(line).unwrap();
+            let expected = serde_json::to_value(entry).unwrap();
+            assert_eq!(serde_json::to_value(&parsed).unwrap(), expected);
+        }
+        assert_eq!(expected[0].message, "boom status=\"500 internal\"\nwith a continuation line");
+    }
+
+    #[test]
+    fn test_ndjson_conflicts_with_listing_flags() {
+        assert!(query_args(&["--ndjson"]).ndjson);
+
+        for flag in [&["--count"][..], &["--limit", "5"], &["--sort", "time"], &["--relative"]] {
+            let mut argv = vec!["amplihack-logparse", "query", "--ndjson"];
+            argv.extend_from_slice(flag);
+            assert!(Cli::try_parse_from(argv).is_err(), "accepted {:?}", flag);
+        }
+    }
+
+    #[test]
+    fn test_context_prints_canonical_entry_lines() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        std::fs::write(
+            &path,
+            "[2025-10-18T14:30:00Z] INFO: starting\n\
+             [2025-10-18T14:30:01Z] ERROR: boom\n\
+             [2025-10-18T14:30:02Z] DBG: cleanup\n",
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        handle_context(&mut out, &path, 1, &options).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.contains("   [1] [2025-10-18T14:30:00Z] INFO: starting\n"));
+        assert!(out.contains(">> [2] [2025-10-18T14:30:01Z] ERROR: boom\n"));
+        assert!(out.contains("   [3] [2025-10-18T14:30:02Z] DEBUG: cleanup\n"));
+    }
+
+    #[test]
+    fn test_sample_flags_select_parse_mode() {
+        assert_eq!(analyze_args(&[]).parse_mode(), ParseMode::Cached);
+        assert_eq!(analyze_args(&["--no-cache"]).parse_mode(), ParseMode::Full);
+        assert_eq!(
+            analyze_args(&["--sample", "0.25"]).parse_mode(),
+            ParseMode::Sampled { rate: 0.25, seed: DEFAULT_SAMPLE_SEED }
+        );
+        assert_eq!(
+            analyze_args(&["--sample", "1", "--seed", "9"]).parse_mode(),
+            ParseMode::Sampled { rate: 1.0, seed: 9 }
+        );
+
+        for bad in [["--sample", "0"], ["--sample", "1.5"], ["--sample", "ten"], ["--seed", "3"]] {
+            let argv = ["amplihack-logparse", "analyze", bad[0], bad[1]];
+            assert!(Cli::try_parse_from(argv).is_err(), "accepted {:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_sampled_analyze_is_annotated_and_reproducible() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let contents: String = (0..2_000)
+            .map(|i| {
+                let (minute, second) = (i / 60 % 60, i % 60);
+                format!("[2025-10-18T14:{:02}:{:02}Z] INFO: entry {}\n", minute, second, i)
+            })
+            .collect();
+        std::fs::write(dir.path().join("a.log"), contents).unwrap();
+        let logs = dir.path().to_str().unwrap();
+
+        let run = || {
+            let args = analyze_args(&["--logs-dir", logs, "--sample", "0.2", "--seed", "5"]);
+            let mut out = Vec::new();
+            handle_analyze(&mut out, &args, &options).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let first = run();
+        assert!(first.contains("Sampling 20.0% of entries (seed 5); results are estimates\n"));
+        assert_eq!(first, run());
+
+        let count_line = first.lines().find(|l| l.starts_with("  Entry count: ")).unwrap();
+        let count: usize = count_line["  Entry count: ".len()..].parse().unwrap();
+        assert!((300..=500).contains(&count), "kept {}", count);
+    }
+
+    #[test]
+    fn test_cached_glob_analyze_skips_cache_sidecars() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let log = "[2025-10-18T14:30:45Z] INFO: Starting analysis\n\
+                   [2025-10-18T14:30:46Z] ERROR: Something failed\n";
+        std::fs::write(dir.path().join("a.log"), log).unwrap();
+        let logs = dir.path().to_str().unwrap();
+
+        let run = || {
+            let args = analyze_args(&["--logs-dir", logs, "--glob", "**/*.log*"]);
+            let mut out = Vec::new();
+            handle_analyze(&mut out, &args, &options).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let first = run();
+        assert!(dir.path().join("a.log.cache").exists());
+        assert!(first.contains("  Entry count: 2\n"));
+        assert_eq!(first, run());
+        assert!(!dir.path().join("a.log.cache.cache").exists());
+    }
+
+    #[test]
+    fn test_exclude_agent_drops_agent_stats_before_top() {
+        let options = ParseOptions::default();
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let entries = ["architect", "builder", "builder", "reviewer", "reviewer", "reviewer"]
+            .iter()
+            .enumerate()
+            .map(|(i, agent)| agent_entry_at(base + Duration::seconds(i as i64), Some(agent)))
+            .collect();
+        let session = LogSession::from_entries("exclude", entries);
+
+        let args = analyze_args(&["--exclude-agent", "Reviewer", "--top", "1"]);
+        let report = build_report(&session, &args, &options).unwrap();
+        let names: Vec<_> = report.agents.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["builder"]);
+
+        // Timing still covers every entry; only agent stats are filtered
+        assert_eq!(report.timing.entry_count, 6);
+
+        // Excluded names go through the configured aliases
+        let aliases = HashMap::from([("rev".to_string(), "reviewer".to_string())]);
+        let options = ParseOptions::new().with_agent_aliases(&aliases);
+        let args = analyze_args(&["--exclude-agent", "rev"]);
+        let report = build_report(&session, &args, &options).unwrap();
+        let names: Vec<_> = report.agents.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["architect", "builder"]);
+    }
+
+    fn repl_transcript(script: &str) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        std::fs::write(
+            &path,
+            "[2025-10-18T14:30:00Z] AGENT: agent=architect duration=2s\n\
+             [2025-10-18T14:30:01Z] ERROR: request timeout agent=builder\n\
+             [2025-10-18T14:30:02Z] WARNING: slow response\n\
+             [2025-10-18T14:30:03Z] ERROR: disk full\n",
+        )
+        .unwrap();
+        let entries = parse_log_file(&path).unwrap();
+
+        let mut out = Vec::new();
+        run_repl(script.as_bytes(), &mut out, &entries).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_repl_runs_scripted_queries() {
+        let transcript = repl_transcript(
+            "agent architect\n\
+             type error\n\
+             \n\
+             contains timeout\n\
+             regex ^(slow|disk)\n\
+             quit\n\
+             all\n",
+        );
+
+        assert_eq!(
+            transcript,
+            "> 1 matching entries\n\
+             \x20 [2025-10-18T14:30:00Z] AGENT: agent=architect duration=2s\n\
+             > 2 matching entries\n\
+             \x20 [2025-10-18T14:30:01Z] ERROR: request timeout agent=builder\n\
+             \x20 [2025-10-18T14:30:03Z] ERROR: disk full\n\
+             > > 1 matching entries\n\
+             \x20 [2025-10-18T14:30:01Z] ERROR: request timeout agent=builder\n\
+             > 2 matching entries\n\
+             \x20 [2025-10-18T14:30:02Z] WARNING: slow response\n\
+             \x20 [2025-10-18T14:30:03Z] ERROR: disk full\n\
+             > "
+        );
+    }
+
+    #[test]
+    fn test_repl_reports_bad_commands_and_stops_at_end_of_input() {
+        let transcript = repl_transcript("bogus\nagent\ntype fatal\nregex (\nhelp\nall");
+        let lines: Vec<&str> = transcript.lines().collect();
+
+        assert_eq!(lines[0], "> Unknown command 'bogus'; type 'help' for commands");
+        assert_eq!(lines[1], "> 'agent' needs an argument; type 'help' for commands");
+        assert!(lines[2].starts_with("> unknown entry type 'fatal'"));
+        assert!(lines[3].starts_with("> Invalid regex pattern '('"));
+        assert!(lines.contains(&"> Commands:"));
+        assert!(transcript.contains("> 4 matching entries\n"));
+        assert!(transcript.ends_with("> \n"));
+    }
+
+    #[test]
+    fn test_output_dir_writes_one_report_per_session() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let logs = dir.path().join("logs");
+        std::fs::create_dir(&logs).unwrap();
+        std::fs::write(
+            logs.join("batch.log"),
+            "[2025-10-18T14:30:00Z] AGENT: agent=architect duration=1s session_id=alpha\n\
+             [2025-10-18T14:30:01Z] AGENT: agent=builder duration=2s session_id=beta\n\
+             [2025-10-18T14:30:02Z] AGENT: agent=builder duration=4s session_id=beta\n",
+        )
+        .unwrap();
+
+        let reports = dir.path().join("reports");
+        std::fs::create_dir(&reports).unwrap();
+        std::fs::write(reports.join("notes.txt"), "keep me").unwrap();
+
+        let run = |format: &str| {
+            let args = analyze_args(&[
+                "--logs-dir",
+                logs.to_str().unwrap(),
+                "--per-session",
+                "--format",
+                format,
+                "--output-dir",
+                reports.to_str().unwrap(),
+            ]);
+            let mut out = Vec::new();
+            handle_analyze(&mut out, &args, &options).unwrap();
+            out
+        };
+
+        assert!(run("markdown").is_empty());
+
+        let mut names: Vec<_> = std::fs::read_dir(&reports)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["alpha.md", "beta.md", "notes.txt"]);
+
+        let alpha = std::fs::read_to_string(reports.join("alpha.md")).unwrap();
+        let beta = std::fs::read_to_string(reports.join("beta.md")).unwrap();
+        assert!(alpha.starts_with("# Log Analysis\n"));
+        assert!(alpha.contains("| architect | 1 | 1000 | 1000.00 |"));
+        assert!(!alpha.contains("builder"));
+        assert!(beta.contains("| builder | 2 | 6000 | 3000.00 |"));
+        assert!(!beta.contains("architect"));
+        assert_eq!(std::fs::read_to_string(reports.join("notes.txt")).unwrap(), "keep me");
+
+        run("json");
+        let beta: AnalysisReport =
+            serde_json::from_str(&std::fs::read_to_string(reports.join("beta.json")).unwrap())
+                .unwrap();
+        assert_eq!(beta.timing.entry_count, 2);
+        assert_eq!(beta.agents[0].name, "builder");
+    }
+
+    #[test]
+    fn test_output_dir_requires_session_splitting() {
+        let argv = ["amplihack-logparse", "analyze", "--output-dir", "out"];
+        assert!(Cli::try_parse_from(argv).is_err());
+
+        let args = analyze_args(&["--split-on-markers", "--output-dir", "out"]);
+        assert_eq!(args.output_dir, Some(PathBuf::from("out")));
+    }
+
+    #[test]
+    fn test_report_file_stem_is_safe() {
+        assert_eq!(report_file_stem("session-1_a.b"), "session-1_a.b");
+        assert_eq!(report_file_stem("../etc/passwd"), ".._etc_passwd");
+        assert_eq!(report_file_stem("a b:c"), "a_b_c");
+        assert_eq!(report_file_stem(".."), "session__");
+        assert_eq!(report_file_stem(""), "session");
+    }
+
+    /// Run `analyze` over a directory holding one log, returning the exit
+    /// code and the report
+    fn analyze_exit_code(log: &str, extra: &[&str]) -> (i32, String) {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("session.log"), log).unwrap();
+
+        let mut argv = vec!["--logs-dir", dir.path().to_str().unwrap()];
+        argv.extend_from_slice(extra);
+
+        let mut out = Vec::new();
+        let result = handle_analyze(&mut out, &analyze_args(&argv), &options);
+        (exit_code(&result), String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_fail_on_error_exits_with_code_2_after_report() {
+        let log = "[2025-10-18T14:30:00Z] INFO: starting\n\
+                   [2025-10-18T14:30:01Z] ERROR: boom\n\
+                   [2025-10-18T14:30:02Z] ERROR: boom again\n";
+
+        let (code, report) = analyze_exit_code(log, &["--fail-on", "error"]);
+        assert_eq!(code, FAIL_ON_EXIT_CODE);
+        assert!(report.contains("ANALYSIS RESULTS"));
+
+        let (code, _) = analyze_exit_code(log, &["--fail-on", "error", "--per-session"]);
+        assert_eq!(code, FAIL_ON_EXIT_CODE);
+
+        assert_eq!(analyze_exit_code(log, &[]).0, 0);
+    }
+
+    #[test]
+    fn test_fail_on_exits_0_below_level() {
+        let log = "[2025-10-18T14:30:00Z] INFO: starting\n\
+                   [2025-10-18T14:30:01Z] WARNING: disk almost full\n";
+
+        assert_eq!(analyze_exit_code(log, &["--fail-on", "error"]).0, 0);
+        assert_eq!(analyze_exit_code(log, &["--fail-on", "warning"]).0, FAIL_ON_EXIT_CODE);
+
+        assert_eq!(exit_code(&Err(ParseError::ValidationFailed { failures: 1 })), 1);
+        let argv = ["amplihack-logparse", "analyze", "--fail-on", "info"];
+        assert!(Cli::try_parse_from(argv).is_err());
+    }
+
+    #[test]
+    fn test_table_format_prints_only_agent_table() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("session.log"),
+            "[2025-10-18T14:30:00Z] AGENT: agent=architect duration=100ms\n\
+             [2025-10-18T14:30:01Z] AGENT: agent=builder duration=40ms\n\
+             [2025-10-18T14:30:02Z] AGENT: agent=builder duration=60ms\n",
+        )
+        .unwrap();
+
+        let args = analyze_args(&["--logs-dir", dir.path().to_str().unwrap(), "--format", "table"]);
+        assert_eq!(args.format(), OutputFormat::Table);
+
+        let mut out = Vec::new();
+        handle_analyze(&mut out, &args, &options).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        // No progress headers, just the header row and one row per agent
+        let rows: Vec<&str> = out.lines().filter(|line| line.starts_with("| ")).collect();
+        assert!(out.starts_with("+-"));
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with("| Agent "));
+        assert!(rows[1..].iter().any(|row| row.starts_with("| architect |           1 |")));
+        assert!(rows[1..].iter().any(|row| row.starts_with("| builder   |           2 |")));
+    }
+
+    #[test]
+    fn test_query_exclude_agent_flags_build_filter() {
+        let options = ParseOptions::default();
+        let filter = entry_filter(&query_args(&[
+            "--agent",
+            "er",
+            "--exclude-agent",
+            "builder",
+            "--exclude-agent",
+            "reviewer",
+        ]), &options)
+        .unwrap();
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+
+        let kept: Vec<bool> = ["builder", "reviewer", "planner", "architect"]
+            .iter()
+            .map(|agent| filter.matches(&agent_entry_at(base, Some(agent))))
+            .collect();
+        assert_eq!(kept, vec![false, false, true, false]);
+    }
+
+    #[test]
+    fn test_truncate_message_multibyte() {
+        // 70 multibyte chars; a byte slice at 60 would land mid-character
+        let message = "é".repeat(70);
+
+        let truncated = truncate_message(&message, DEFAULT_MESSAGE_WIDTH);
+        assert_eq!(truncated, format!("{}...", "é".repeat(60)));
+
+        assert_eq!(truncate_message("日本語のログ", 3), "日本語...");
+        assert_eq!(truncate_message("日本語", 3), "日本語");
+        assert_eq!(truncate_message(&message, 0), message);
+    }
+
+    #[test]
+    fn test_truncate_message_char_straddling_byte_60() {
+        // The 4-byte emoji occupies bytes 59..63, so byte 60 is mid-character
+        let message = format!("{}😀 and café after the cut", "a".repeat(59));
+        assert!(!message.is_char_boundary(60));
+
+        let truncated = truncate_message(&message, DEFAULT_MESSAGE_WIDTH);
+
+        assert_eq!(truncated, format!("{}😀...", "a".repeat(59)));
+        assert_eq!(truncated.chars().count(), DEFAULT_MESSAGE_WIDTH + 3);
+    }
+
+    #[test]
+    fn test_max_message_width_flag() {
+        assert_eq!(query_args(&[]).max_message_width, DEFAULT_MESSAGE_WIDTH);
+        assert_eq!(query_args(&["--max-message-width", "0"]).max_message_width, 0);
+
+        let cli = Cli::parse_from(["amplihack-logparse", "parse", "s", "--max-message-width", "20"]);
+        match cli.command {
+            Commands::Parse { max_message_width, .. } => assert_eq!(max_message_width, 20),
+            _ => panic!("Expected parse command"),
+        }
+    }
+
+    #[test]
+    fn test_relative_time_offsets() {
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let entries = [
+            entry_at(base, "start"),
+            entry_at(base + Duration::seconds(10), "later"),
+            entry_at(base + Duration::milliseconds(754_250), "much later"),
+        ];
+
+        let zero = relative_base(&entries);
+        assert_eq!(zero, Some(base));
+
+        let shown: Vec<_> = entries
+            .iter()
+            .map(|e| format_entry_time(e.timestamp, zero))
+            .collect();
+        assert_eq!(shown, vec!["+00:00.000", "+00:10.000", "+12:34.250"]);
+
+        assert_eq!(format_entry_time(base, None), "2025-10-18 14:30:00");
+    }
+
+    #[test]
+    fn test_output_flag_writes_analyze_report_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = dir.path().join("logs");
+        std::fs::create_dir(&logs).unwrap();
+        std::fs::write(
+            logs.join("a.log"),
+            "[2025-10-18T14:30:00Z] INFO: one\n[2025-10-18T14:30:05Z] ERROR: two\n",
+        )
+        .unwrap();
+        let report = dir.path().join("report.txt");
+
+        let cli = Cli::parse_from([
+            "amplihack-logparse",
+            "analyze",
+            "--no-cache",
+            "--logs-dir",
+            logs.to_str().unwrap(),
+            "--output",
+            report.to_str().unwrap(),
+        ]);
+        let mut out = open_output(cli.output.as_deref()).unwrap();
+        run_command(&cli, &Config::default(), &mut out).unwrap();
+        out.flush().unwrap();
+
+        // Headers and results all land in the file, leaving nothing for stdout
+        let written = std::fs::read_to_string(&report).unwrap();
+        assert!(written.starts_with("Analyzing logs in: "));
+        assert!(written.contains("ANALYSIS RESULTS"));
+        assert!(written.contains("  Entry count: 2\n"));
+    }
+
+    #[test]
+    fn test_cli_strips_ansi_unless_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("color.log");
+        std::fs::write(&path, "[2025-10-18T14:30:45Z] ERROR: \x1b[31mfailed\x1b[0m\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        let parse_output = |args: &[&str]| {
+            let mut argv = vec!["amplihack-logparse"];
+            argv.extend(args);
+            argv.extend(["parse", path]);
+            let mut out = Vec::new();
+            run_command(&Cli::parse_from(argv), &Config::default(), &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert!(parse_output(&[]).contains("ERROR | failed"));
+        assert!(parse_output(&["--strip-ansi", "false"]).contains("\x1b[31mfailed"));
+    }
+
+    #[test]
+    fn test_timestamp_format_flag_reaches_parsing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("custom.log");
+        std::fs::write(&path, "[18/10/2025 14:30:45] ERROR: custom failure\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        let cli = |format: &str| {
+            Cli::parse_from(["amplihack-logparse", "--timestamp-format", format, "parse", path])
+        };
+
+        let mut out = Vec::new();
+        run_command(&cli("%d/%m/%Y %H:%M:%S"), &Config::default(), &mut out).unwrap();
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("Parsed 1 log entries:"), "{}", output);
+
+        match run_command(&cli("%Q"), &Config::default(), &mut Vec::new()) {
+            Err(ParseError::InvalidTimestampFormat(format)) => assert_eq!(format, "%Q"),
+            other => panic!("expected InvalidTimestampFormat, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_output_to_missing_directory_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing").join("out.txt");
+
+        match open_output(Some(&path)) {
+            Err(ParseError::Io(e)) => assert!(e.to_string().contains("out.txt")),
+            Err(e) => panic!("Expected Io error, got {:?}", e),
+            Ok(_) => panic!("Expected an error"),
+        }
+    }
+
+    #[test]
+    fn test_quiet_disables_progress() {
+        let options = ParseOptions::default();
+        let args = analyze_args(&["--quiet"]);
+        assert!(args.quiet);
+        assert!(!ProgressBar::stderr(3, args.quiet).is_enabled());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.log");
+        std::fs::write(&path, "[2025-10-18T14:30:00Z] INFO: hi\n").unwrap();
+        let paths = [path];
+
+        let quiet = ProgressBar::new(Vec::new(), 1, !args.quiet);
+        let sessions = parse_files_parallel(&paths, ParseMode::Full, &options, &quiet);
+        assert_eq!(sessions[0].entries.len(), 1);
+        assert!(quiet.into_inner().is_empty());
+
+        let shown = ProgressBar::new(Vec::new(), 1, true);
+        parse_files_parallel(&paths, ParseMode::Full, &options, &shown);
+        let output = String::from_utf8(shown.into_inner()).unwrap();
+        assert!(output.contains("1/1 a.log"));
+        assert!(output.ends_with("\r\x1b[2K"));
+    }
+
+    #[test]
+    fn test_url_replaces_path_arguments() {
+        let parse = Cli::try_parse_from(["amplihack-logparse", "parse", "--url", "http://h/a.log"]);
+        assert!(parse.is_ok());
+
+        let neither = Cli::try_parse_from(["amplihack-logparse", "parse"]);
+        assert!(neither.is_err());
+
+        let both = Cli::try_parse_from([
+            "amplihack-logparse",
+            "analyze",
+            "--url",
+            "http://h/a.log",
+            "--logs-dir",
+            "logs",
+        ]);
+        assert!(both.is_err());
+
+        assert_eq!(
+            analyze_args(&["--url", "http://h/a.log"]).url.as_deref(),
+            Some("http://h/a.log")
+        );
+    }
+
+    #[test]
+    fn test_from_json_round_trip_matches_analysis() {
+        let options = ParseOptions::default();
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let entries: Vec<LogEntry> = [Some("builder"), None, Some("architect"), Some("builder")]
+            .into_iter()
+            .enumerate()
+            .map(|(i, agent)| LogEntry {
+                duration_ms: agent.map(|_| 100 * (i as u64 + 1)),
+                ..agent_entry_at(base + Duration::seconds(i as i64 * 7), agent)
+            })
+            .collect();
+        let session = LogSession::from_entries("original", entries.clone());
+
+        let dir = tempfile::tempdir().unwrap();
+        let session_path = dir.path().join("session.json");
+        let entries_path = dir.path().join("entries.json");
+        std::fs::write(&session_path, serde_json::to_string(&session).unwrap()).unwrap();
+        std::fs::write(&entries_path, serde_json::to_string(&entries).unwrap()).unwrap();
+
+        let args = analyze_args(&["--from-json", "unused.json"]);
+        let expected = build_report(&session, &args, &options).unwrap();
+        let expected = serde_json::to_value(expected).unwrap();
+
+        for path in [&session_path, &entries_path] {
+            let reloaded = load_json_session(path).unwrap();
+            let report = build_report(&reloaded, &args, &options).unwrap();
+            let report = serde_json::to_value(report).unwrap();
+            assert_eq!(report, expected, "mismatch loading {}", path.display());
+        }
+
+        assert_eq!(load_json_session(&session_path).unwrap().id, "original");
+        assert_eq!(load_json_session(&entries_path).unwrap().id, "entries.json");
+    }
+
+    #[test]
+    fn test_from_json_analyze_keeps_session_id() {
+        let options = ParseOptions::default();
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let entries = vec![
+            agent_entry_at(base, Some("architect")),
+            agent_entry_at(base + Duration::seconds(5), Some("builder")),
+        ];
+        let session = LogSession::from_entries("original", entries);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        std::fs::write(&path, serde_json::to_string(&session).unwrap()).unwrap();
+
+        let args = analyze_args(&["--from-json", path.to_str().unwrap(), "--format", "dot"]);
+        let mut out = Vec::new();
+        handle_analyze(&mut out, &args, &options).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("digraph \"original\" {\n"), "{}", out);
+    }
+
+    #[test]
+    fn test_json_report_agent_without_durations_has_null_min_max() {
+        let options = ParseOptions::default();
+        let base = parse_timestamp("2025-10-18T14:30:00Z").unwrap();
+        let session = LogSession::from_entries(
+            "untimed",
+            vec![
+                agent_entry_at(base, Some("architect")),
+                agent_entry_at(base + Duration::seconds(5), Some("architect")),
+            ],
+        );
+
+        let args = analyze_args(&["--format", "json"]);
+        let report = build_report(&session, &args, &options).unwrap();
+        let mut out = Vec::new();
+        write_report(&mut out, &args, &session, &report).unwrap();
+        let json = String::from_utf8(out).unwrap();
+
+        assert!(!json.contains(&u64::MAX.to_string()));
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let agent = &value["agents"][0];
+        assert_eq!(agent["invocation_count"], 2);
+        assert!(agent["min_duration_ms"].is_null());
+        assert!(agent["max_duration_ms"].is_null());
+
+        // The report reloads with the durations still unset
+        let reloaded: AnalysisReport = serde_json::from_str(&json).unwrap();
+        assert!(!reloaded.agents[0].has_durations());
+    }
+
+    #[test]
+    fn test_export_dry_run_writes_nothing() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let logs = dir.path().join("logs");
+        std::fs::create_dir(&logs).unwrap();
+        std::fs::write(
+            logs.join("a.log"),
+            "[2025-10-18T14:30:00Z] INFO: one\n[2025-10-18T14:30:01Z] INFO: two\n",
+        )
+        .unwrap();
+        std::fs::write(logs.join("b.log"), "[2025-10-18T14:30:02Z] ERROR: three\n").unwrap();
+        let db = dir.path().join("out.db");
+
+        let mut out = Vec::new();
+        handle_export(&mut out, &logs, &db, true, &options).unwrap();
+        assert!(!db.exists());
+
+        let summary = String::from_utf8(out).unwrap();
+        assert!(summary.contains("Rows: 3"));
+        assert!(summary.contains(&format!("Target: {}", db.display())));
+        assert!(summary.contains("Columns: timestamp, entry_type, message, agent_name, duration_ms"));
     }
 }
@@ -0,0 +1,151 @@
+// Live log following
+//
+// Tracks a byte offset into a growing log file so each poll only parses
+// newly appended data, like `tail -f`.
+//
+// Demonstrates:
+// - Stateful struct with owned buffers
+// - Seek + Read for incremental file access
+// - Handling partial writes by buffering until a newline appears
+
+use super::{parse_lines_from, LogFormat, ParseOptions};
+use crate::error::{ParseError, ParseResult};
+use crate::log_warn;
+use crate::types::LogEntry;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Follows a log file and yields entries as they are appended
+pub struct LogFollower {
+    path: PathBuf,
+
+    /// Byte offset of the next unread byte
+    offset: u64,
+
+    /// Bytes of an incomplete trailing line awaiting its newline
+    partial: Vec<u8>,
+
+    /// Number of complete lines consumed so far
+    lines_read: usize,
+
+    options: ParseOptions,
+}
+
+impl LogFollower {
+    /// Create a follower starting at the beginning of the file
+    pub fn new(path: &Path) -> ParseResult<Self> {
+        if !path.is_file() {
+            return Err(ParseError::FileNotFound(path.to_path_buf()));
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            offset: 0,
+            partial: Vec::new(),
+            lines_read: 0,
+            options: ParseOptions::default(),
+        })
+    }
+
+    /// Parse appended lines with the given options
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Read any newly appended complete lines and parse them
+    ///
+    /// Continuation lines are joined to their header entry when both
+    /// arrive in the same poll. Malformed lines are reported and skipped.
+    pub fn poll(&mut self) -> ParseResult<Vec<LogEntry>> {
+        let mut file = File::open(&self.path)
+            .map_err(|_| ParseError::FileNotFound(self.path.clone()))?;
+
+        // The file was truncated or rotated: start over
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+            self.lines_read = 0;
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut appended = Vec::new();
+        let bytes_read = file.read_to_end(&mut appended)?;
+        self.offset += bytes_read as u64;
+
+        self.partial.extend_from_slice(&appended);
+
+        // Only hand complete lines to the parser
+        let complete_len = match self.partial.iter().rposition(|&b| b == b'\n') {
+            Some(pos) => pos + 1,
+            None => return Ok(Vec::new()),
+        };
+        let complete: Vec<u8> = self.partial.drain(..complete_len).collect();
+
+        let first_line = self.lines_read + 1;
+        self.lines_read += complete.iter().filter(|&&b| b == b'\n').count();
+
+        let format = LogFormat::Bracket;
+        parse_lines_from(complete.as_slice(), first_line, format, &self.options, |line_num, e| {
+            log_warn!("Failed to parse line {}: {}", line_num, e);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_follower_emits_each_entry_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("live.log");
+        std::fs::write(&path, "[2025-10-18T14:30:45Z] INFO: first\n").unwrap();
+
+        let mut follower = LogFollower::new(&path).unwrap();
+
+        let batch = follower.poll().unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].message, "first");
+
+        // Nothing new yet
+        assert!(follower.poll().unwrap().is_empty());
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "[2025-10-18T14:30:46Z] INFO: second\n[2025-10-18T14:30:47Z] INFO: thi").unwrap();
+        file.flush().unwrap();
+
+        // The partial third line is held back
+        let batch = follower.poll().unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].message, "second");
+
+        writeln!(file, "rd").unwrap();
+        file.flush().unwrap();
+
+        let batch = follower.poll().unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].message, "third");
+
+        assert!(follower.poll().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_follower_restarts_after_truncation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("live.log");
+        std::fs::write(&path, "[2025-10-18T14:30:45Z] INFO: old entry here\n").unwrap();
+
+        let mut follower = LogFollower::new(&path).unwrap();
+        assert_eq!(follower.poll().unwrap().len(), 1);
+
+        std::fs::write(&path, "[2025-10-18T14:30:50Z] INFO: new\n").unwrap();
+
+        let batch = follower.poll().unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].message, "new");
+    }
+}
@@ -0,0 +1,307 @@
+// Glob matching for selecting log files in nested directories
+//
+// The glob crate isn't available to this build, so the matcher lives here.
+// It supports the common subset of glob syntax:
+// - `*` matches any run of characters within one path component
+// - `?` matches exactly one character within a path component
+// - `[abc]`, `[a-z]` match one listed character; `[!a-z]` or `[^a-z]`
+//   match one character not listed. A `]` first in the class is literal.
+// - `**` as a whole component matches zero or more directories
+// - `\*` (or `[*]`) matches a literal metacharacter
+//
+// A `[` without a closing `]` matches itself.
+//
+// Demonstrates:
+// - Compiling a pattern once into tokens, then matching recursively
+// - Recursive directory traversal with std::fs
+
+use crate::error::ParseResult;
+use std::path::{Path, PathBuf};
+
+/// One `/`-separated component of a compiled pattern
+#[derive(Debug, Clone, PartialEq)]
+enum Component {
+    /// `**`: zero or more whole path components
+    AnyDirs,
+
+    /// A single path component
+    Segment(Vec<Token>),
+}
+
+/// One element of a component pattern
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(char),
+
+    /// `?`
+    AnyChar,
+
+    /// `*`
+    AnyRun,
+
+    /// `[...]`: inclusive character ranges, possibly negated
+    Class { negated: bool, ranges: Vec<(char, char)> },
+}
+
+impl Token {
+    /// Whether this single-character token accepts `c`
+    fn accepts(&self, c: char) -> bool {
+        match self {
+            Token::Literal(literal) => *literal == c,
+            Token::AnyChar | Token::AnyRun => true,
+            Token::Class { negated, ranges } => {
+                ranges.iter().any(|&(low, high)| (low..=high).contains(&c)) != *negated
+            }
+        }
+    }
+}
+
+/// Find files under `root` whose path relative to `root` matches `pattern`
+///
+/// Results are sorted so callers get a deterministic order.
+pub fn find_matching_files(root: &Path, pattern: &str) -> ParseResult<Vec<PathBuf>> {
+    let pattern = compile(pattern);
+    let mut found = Vec::new();
+    walk(root, root, &pattern, &mut found)?;
+    found.sort();
+    Ok(found)
+}
+
+/// Check whether a relative path matches a glob pattern
+pub fn matches(pattern: &str, path: &Path) -> bool {
+    matches_relative(&compile(pattern), path)
+}
+
+/// Split a pattern into components and tokenize each one
+fn compile(pattern: &str) -> Vec<Component> {
+    pattern
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|segment| match segment {
+            "**" => Component::AnyDirs,
+            _ => Component::Segment(tokenize(segment)),
+        })
+        .collect()
+}
+
+fn tokenize(segment: &str) -> Vec<Token> {
+    let chars: Vec<char> = segment.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let token = match chars[i] {
+            '*' => Token::AnyRun,
+            '?' => Token::AnyChar,
+            '\\' if i + 1 < chars.len() => {
+                i += 1;
+                Token::Literal(chars[i])
+            }
+            '[' => match parse_class(&chars[i + 1..]) {
+                Some((class, used)) => {
+                    i += used;
+                    class
+                }
+                None => Token::Literal('['),
+            },
+            c => Token::Literal(c),
+        };
+
+        tokens.push(token);
+        i += 1;
+    }
+
+    tokens
+}
+
+/// Parse a character class from just after its `[`, returning the class
+/// and the number of characters used including the closing `]`
+fn parse_class(chars: &[char]) -> Option<(Token, usize)> {
+    let negated = matches!(chars.first(), Some('!' | '^'));
+    let first = negated as usize;
+    let mut ranges = Vec::new();
+    let mut i = first;
+
+    loop {
+        let c = *chars.get(i)?;
+        if c == ']' && i > first {
+            return Some((Token::Class { negated, ranges }, i + 1));
+        }
+
+        match (chars.get(i + 1), chars.get(i + 2)) {
+            (Some('-'), Some(&high)) if high != ']' => {
+                ranges.push((c, high));
+                i += 3;
+            }
+            _ => {
+                ranges.push((c, c));
+                i += 1;
+            }
+        }
+    }
+}
+
+fn walk(
+    root: &Path,
+    dir: &Path,
+    pattern: &[Component],
+    found: &mut Vec<PathBuf>,
+) -> ParseResult<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            walk(root, &path, pattern, found)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            if matches_relative(pattern, relative) {
+                found.push(path);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn matches_relative(pattern: &[Component], relative: &Path) -> bool {
+    let components: Vec<Vec<char>> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().chars().collect())
+        .collect();
+
+    match_components(pattern, &components)
+}
+
+/// Match pattern components against path components, expanding `**`
+fn match_components(pattern: &[Component], path: &[Vec<char>]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((Component::AnyDirs, rest)) => {
+            (0..=path.len()).any(|skip| match_components(rest, &path[skip..]))
+        }
+        Some((Component::Segment(tokens), rest)) => match path.split_first() {
+            Some((component, path_rest)) => {
+                match_tokens(tokens, component) && match_components(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Match a single path component against its tokens
+fn match_tokens(tokens: &[Token], text: &[char]) -> bool {
+    match tokens.split_first() {
+        None => text.is_empty(),
+        Some((Token::AnyRun, rest)) => {
+            (0..=text.len()).any(|skip| match_tokens(rest, &text[skip..]))
+        }
+        Some((token, rest)) => match text.split_first() {
+            Some((&c, text_rest)) => token.accepts(c) && match_tokens(rest, text_rest),
+            None => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_single_component() {
+        assert!(matches("*.log", Path::new("session.log")));
+        assert!(matches("session-?.log", Path::new("session-1.log")));
+        assert!(!matches("*.log", Path::new("session.log.gz")));
+        assert!(!matches("*.log", Path::new("nested/session.log")));
+    }
+
+    #[test]
+    fn test_matches_double_star() {
+        assert!(matches("**/*.log", Path::new("session.log")));
+        assert!(matches("**/*.log", Path::new("a/b/session.log")));
+        assert!(matches("a/**/*.log", Path::new("a/session.log")));
+        assert!(!matches("a/**/*.log", Path::new("b/session.log")));
+    }
+
+    #[test]
+    fn test_double_star_at_start_middle_and_end() {
+        // Start: any depth, including none
+        assert!(matches("**/session.log", Path::new("session.log")));
+        assert!(matches("**/session.log", Path::new("x/y/z/session.log")));
+        assert!(!matches("**/session.log", Path::new("x/other.log")));
+
+        // Middle: zero or more directories between fixed components
+        assert!(matches("logs/**/agents/*.log", Path::new("logs/agents/a.log")));
+        assert!(matches("logs/**/agents/*.log", Path::new("logs/s1/s2/agents/a.log")));
+        assert!(!matches("logs/**/agents/*.log", Path::new("logs/s1/agent/a.log")));
+        assert!(!matches("logs/**/agents/*.log", Path::new("logs/s1/agents/x/a.log")));
+
+        // End: everything below the prefix
+        assert!(matches("logs/**", Path::new("logs/a.log")));
+        assert!(matches("logs/**", Path::new("logs/s1/s2/b.log.gz")));
+        assert!(!matches("logs/**", Path::new("other/a.log")));
+
+        // Several in one pattern
+        assert!(matches("**/s?/**/*.log", Path::new("a/s1/b/c/x.log")));
+        assert!(!matches("**/s?/**/*.log", Path::new("a/s12/x.log")));
+    }
+
+    #[test]
+    fn test_character_classes() {
+        assert!(matches("session-[0-9].log", Path::new("session-7.log")));
+        assert!(!matches("session-[0-9].log", Path::new("session-x.log")));
+        assert!(matches("[abc]*.log", Path::new("builder.log")));
+        assert!(!matches("[abc]*.log", Path::new("reviewer.log")));
+        assert!(matches("s-[a-cx-z].log", Path::new("s-y.log")));
+        assert!(!matches("s-[a-cx-z].log", Path::new("s-m.log")));
+
+        // Negation with ! or ^
+        assert!(matches("s-[!0-9].log", Path::new("s-a.log")));
+        assert!(!matches("s-[!0-9].log", Path::new("s-5.log")));
+        assert!(matches("s-[^0-9].log", Path::new("s-a.log")));
+
+        // `]` first and `-` last are members, not syntax
+        assert!(matches("s[]x].log", Path::new("s].log")));
+        assert!(matches("s[!]].log", Path::new("sa.log")));
+        assert!(!matches("s[!]].log", Path::new("s].log")));
+        assert!(matches("s[a-].log", Path::new("s-.log")));
+
+        // A class matches exactly one character and never `/`
+        assert!(!matches("s[0-9].log", Path::new("s12.log")));
+        assert!(matches("**/[0-9]*/*.log", Path::new("logs/2025-10/a.log")));
+    }
+
+    #[test]
+    fn test_escaped_metacharacters() {
+        assert!(matches("\\*.log", Path::new("*.log")));
+        assert!(!matches("\\*.log", Path::new("session.log")));
+        assert!(matches("what\\?.log", Path::new("what?.log")));
+        assert!(!matches("what\\?.log", Path::new("whatx.log")));
+        assert!(matches("\\[draft].log", Path::new("[draft].log")));
+        assert!(!matches("\\[draft].log", Path::new("d.log")));
+
+        // Brackets also escape, as with the glob crate
+        assert!(matches("[*].log", Path::new("*.log")));
+        assert!(matches("[?][[].log", Path::new("?[.log")));
+        assert!(!matches("[*].log", Path::new("a.log")));
+
+        // An unclosed `[` is literal
+        assert!(matches("[draft.log", Path::new("[draft.log")));
+    }
+
+    #[test]
+    fn test_find_matching_files_in_nested_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("session-1").join("agents");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        std::fs::write(dir.path().join("top.log"), "").unwrap();
+        std::fs::write(nested.join("deep.log"), "").unwrap();
+        std::fs::write(nested.join("notes.txt"), "").unwrap();
+
+        let found = find_matching_files(dir.path(), "**/*.log").unwrap();
+
+        assert_eq!(
+            found,
+            vec![nested.join("deep.log"), dir.path().join("top.log")]
+        );
+    }
+}
@@ -0,0 +1,827 @@
+// Gzip decompression for rotated log files
+//
+// A small, dependency-free implementation of gzip (RFC 1952) and
+// DEFLATE (RFC 1951) decoding, enough to read `.log.gz` files. The flate2
+// crate isn't available to this build, so the decoder lives here:
+// - Stored, fixed-Huffman, and dynamic-Huffman blocks
+// - Multi-member gzip streams
+// - CRC32 and size verification of each member
+//
+// `GzDecoder` inflates on demand as it is read, so memory use stays at the
+// 32 KiB back-reference window plus one chunk of output.
+
+use std::io::{self, BufRead, Read};
+
+/// Gzip magic bytes found at the start of every member
+pub const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+const FLAG_HCRC: u8 = 0x02;
+const FLAG_EXTRA: u8 = 0x04;
+const FLAG_NAME: u8 = 0x08;
+const FLAG_COMMENT: u8 = 0x10;
+
+const MAX_BITS: usize = 15;
+
+/// Size of the DEFLATE back-reference window
+const WINDOW_SIZE: usize = 32 * 1024;
+
+/// Decompressed bytes produced per refill before they are handed out
+const OUTPUT_CHUNK: usize = 32 * 1024;
+
+/// Base lengths for length codes 257..=285
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115,
+    131, 163, 195, 227, 258,
+];
+
+/// Extra bits for length codes 257..=285
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Base distances for distance codes 0..=29
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+/// Extra bits for distance codes 0..=29
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Order in which code length code lengths are stored
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Check whether a buffer starts with the gzip magic bytes
+pub fn is_gzip(bytes: &[u8]) -> bool {
+    bytes.starts_with(&GZIP_MAGIC)
+}
+
+/// Where a decoder is within the gzip stream
+enum State {
+    /// Between members: a header, trailing zero padding, or end of input
+    Member,
+
+    /// Expecting a DEFLATE block header
+    BlockHeader,
+
+    /// Inside a stored block with this many bytes left to copy
+    Stored(usize),
+
+    /// Inside a Huffman-coded block, with its literal/length and distance
+    /// tables
+    Huffman(Box<(Huffman, Huffman)>),
+
+    /// The stream ended cleanly
+    Done,
+
+    /// Decoding failed; the error is repeated on every later read
+    Failed(io::ErrorKind, String),
+}
+
+/// Streaming gzip decoder (one or more members)
+///
+/// Compressed bytes are pulled from the inner reader only as decompressed
+/// output is requested. Each member's CRC32 and size are checked when its
+/// last block ends, so corrupt data surfaces as an `InvalidData` error
+/// partway through reading; truncated input surfaces as `UnexpectedEof`.
+///
+/// Demonstrates:
+/// - Implementing Read as an adapter over another reader
+/// - A state machine that can pause between any two symbols
+pub struct GzDecoder<R> {
+    bits: BitReader<R>,
+    state: State,
+
+    /// Whether the current block is the last one of its member
+    final_block: bool,
+
+    /// Members started so far
+    members: usize,
+
+    /// Recent output: the back-reference window plus bytes not yet read
+    history: Vec<u8>,
+
+    /// Index in `history` of the next byte to hand out
+    read_pos: usize,
+
+    /// Index in `history` up to which `crc` has been computed
+    crc_pos: usize,
+
+    /// CRC32 of the current member's output up to `crc_pos`
+    crc: u32,
+
+    /// Bytes of output produced by the current member
+    member_len: u64,
+}
+
+impl<R: BufRead> GzDecoder<R> {
+    /// Decode the gzip stream read from `reader`
+    pub fn new(reader: R) -> Self {
+        Self {
+            bits: BitReader::new(reader),
+            state: State::Member,
+            final_block: false,
+            members: 0,
+            history: Vec::new(),
+            read_pos: 0,
+            crc_pos: 0,
+            crc: 0,
+            member_len: 0,
+        }
+    }
+
+    /// Decode until a chunk of output is ready or the stream ends
+    ///
+    /// On failure, output not yet read is discarded and the error is
+    /// remembered for later reads.
+    fn fill(&mut self) -> io::Result<()> {
+        let result = self.decode_chunk();
+        if let Err(e) = &result {
+            self.history.truncate(self.read_pos);
+            self.state = State::Failed(e.kind(), e.to_string());
+        }
+        result
+    }
+
+    fn decode_chunk(&mut self) -> io::Result<()> {
+        self.compact();
+
+        while self.history.len() - self.read_pos < OUTPUT_CHUNK {
+            self.state = match std::mem::replace(&mut self.state, State::Done) {
+                State::Member if self.start_member()? => State::BlockHeader,
+                State::Member | State::Done => return Ok(()),
+                State::BlockHeader => self.read_block_header()?,
+                State::Stored(remaining) => {
+                    let count = remaining.min(OUTPUT_CHUNK);
+                    for _ in 0..count {
+                        let byte = self.bits.read_bits(8)? as u8;
+                        self.push(byte);
+                    }
+
+                    if remaining > count {
+                        State::Stored(remaining - count)
+                    } else {
+                        self.end_block()?
+                    }
+                }
+                State::Huffman(tables) => {
+                    if self.inflate_symbols(&tables.0, &tables.1)? {
+                        self.end_block()?
+                    } else {
+                        State::Huffman(tables)
+                    }
+                }
+                State::Failed(kind, message) => {
+                    self.state = State::Failed(kind, message.clone());
+                    return Err(io::Error::new(kind, message));
+                }
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Drop output that has been read and fallen out of the window
+    fn compact(&mut self) {
+        if self.read_pos > WINDOW_SIZE {
+            self.update_crc();
+
+            let drop = self.read_pos - WINDOW_SIZE;
+            self.history.drain(..drop);
+            self.read_pos -= drop;
+            self.crc_pos -= drop;
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.history.push(byte);
+        self.member_len += 1;
+    }
+
+    /// Fold output produced since the last update into the member's CRC
+    fn update_crc(&mut self) {
+        self.crc = crc32_update(self.crc, &self.history[self.crc_pos..]);
+        self.crc_pos = self.history.len();
+    }
+
+    /// Read the next member header, returning false at the end of the stream
+    fn start_member(&mut self) -> io::Result<bool> {
+        let first = match self.bits.next_byte()? {
+            None if self.members == 0 => return Err(truncated()),
+            None => return Ok(false),
+            Some(0) if self.members > 0 => {
+                // Trailing zero padding is common after the final member
+                while let Some(byte) = self.bits.next_byte()? {
+                    if byte != 0 {
+                        return Err(invalid("garbage after gzip padding"));
+                    }
+                }
+                return Ok(false);
+            }
+            Some(byte) => byte,
+        };
+
+        if [first, self.bits.read_byte()?] != GZIP_MAGIC {
+            return Err(invalid("missing gzip header"));
+        }
+
+        let mut header = [0u8; 8];
+        for byte in &mut header {
+            *byte = self.bits.read_byte()?;
+        }
+        if header[0] != 8 {
+            return Err(invalid("unsupported gzip compression method"));
+        }
+
+        let flags = header[1];
+        if flags & FLAG_EXTRA != 0 {
+            let len = u16::from_le_bytes([self.bits.read_byte()?, self.bits.read_byte()?]);
+            for _ in 0..len {
+                self.bits.read_byte()?;
+            }
+        }
+
+        for flag in [FLAG_NAME, FLAG_COMMENT] {
+            if flags & flag != 0 {
+                while self.bits.read_byte()? != 0 {}
+            }
+        }
+
+        if flags & FLAG_HCRC != 0 {
+            self.bits.read_byte()?;
+            self.bits.read_byte()?;
+        }
+
+        self.members += 1;
+        self.crc = 0;
+        self.crc_pos = self.history.len();
+        self.member_len = 0;
+        Ok(true)
+    }
+
+    /// Read a DEFLATE block header and enter the block
+    fn read_block_header(&mut self) -> io::Result<State> {
+        self.final_block = self.bits.read_bits(1)? == 1;
+
+        match self.bits.read_bits(2)? {
+            0 => {
+                self.bits.align_to_byte();
+
+                let len = self.bits.read_bits(16)? as u16;
+                let nlen = self.bits.read_bits(16)? as u16;
+                if len != !nlen {
+                    return Err(invalid("stored block length mismatch"));
+                }
+
+                if len == 0 {
+                    self.end_block()
+                } else {
+                    Ok(State::Stored(len as usize))
+                }
+            }
+            1 => Ok(State::Huffman(Box::new(fixed_tables()))),
+            2 => Ok(State::Huffman(Box::new(dynamic_tables(&mut self.bits)?))),
+            _ => Err(invalid("invalid deflate block type")),
+        }
+    }
+
+    /// Decode literal/length and distance symbols until end-of-block
+    /// (returning true) or until a chunk of output is ready
+    fn inflate_symbols(&mut self, lit: &Huffman, dist: &Huffman) -> io::Result<bool> {
+        while self.history.len() - self.read_pos < OUTPUT_CHUNK {
+            let symbol = lit.decode(&mut self.bits)?;
+
+            match symbol {
+                0..=255 => self.push(symbol as u8),
+                256 => return Ok(true),
+                257..=285 => {
+                    let idx = symbol - 257;
+                    let length = LENGTH_BASE[idx] as usize
+                        + self.bits.read_bits(LENGTH_EXTRA[idx])? as usize;
+
+                    let dist_symbol = dist.decode(&mut self.bits)?;
+                    if dist_symbol >= DIST_BASE.len() {
+                        return Err(invalid("invalid distance symbol"));
+                    }
+                    let distance = DIST_BASE[dist_symbol] as usize
+                        + self.bits.read_bits(DIST_EXTRA[dist_symbol])? as usize;
+
+                    // Back-references never reach into a previous member
+                    if distance as u64 > self.member_len {
+                        return Err(invalid("distance exceeds output"));
+                    }
+
+                    // Copy byte by byte: the source may overlap the destination
+                    let start = self.history.len() - distance;
+                    for i in 0..length {
+                        let byte = self.history[start + i];
+                        self.push(byte);
+                    }
+                }
+                _ => return Err(invalid("invalid literal/length symbol")),
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Move past a finished block, checking the trailer after the last one
+    fn end_block(&mut self) -> io::Result<State> {
+        if !self.final_block {
+            return Ok(State::BlockHeader);
+        }
+
+        self.update_crc();
+        self.bits.align_to_byte();
+
+        let expected_crc = self.bits.read_u32_le()?;
+        let expected_size = self.bits.read_u32_le()?;
+
+        if self.crc != expected_crc {
+            return Err(invalid("gzip CRC32 mismatch"));
+        }
+        // ISIZE holds the size modulo 2^32
+        if self.member_len as u32 != expected_size {
+            return Err(invalid("gzip size mismatch"));
+        }
+
+        Ok(State::Member)
+    }
+}
+
+impl<R: BufRead> Read for GzDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.read_pos == self.history.len() {
+            self.fill()?;
+        }
+
+        let available = &self.history[self.read_pos..];
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.read_pos += count;
+        Ok(count)
+    }
+}
+
+/// Build the fixed Huffman tables from RFC 1951 section 3.2.6
+fn fixed_tables() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+
+    (Huffman::new(&lengths), Huffman::new(&[5u8; 30]))
+}
+
+/// Read dynamic Huffman tables from the block header
+fn dynamic_tables<R: BufRead>(bits: &mut BitReader<R>) -> io::Result<(Huffman, Huffman)> {
+    let hlit = bits.read_bits(5)? as usize + 257;
+    let hdist = bits.read_bits(5)? as usize + 1;
+    let hclen = bits.read_bits(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for &idx in CODE_LENGTH_ORDER.iter().take(hclen) {
+        code_lengths[idx] = bits.read_bits(3)? as u8;
+    }
+    let code_length_huffman = Huffman::new(&code_lengths);
+
+    let mut lengths = Vec::with_capacity(hlit + hdist);
+    while lengths.len() < hlit + hdist {
+        let symbol = code_length_huffman.decode(bits)?;
+
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let prev = *lengths
+                    .last()
+                    .ok_or_else(|| invalid("repeat with no previous length"))?;
+                (prev, 3 + bits.read_bits(2)? as usize)
+            }
+            17 => (0, 3 + bits.read_bits(3)? as usize),
+            18 => (0, 11 + bits.read_bits(7)? as usize),
+            _ => return Err(invalid("invalid code length symbol")),
+        };
+
+        lengths.extend(std::iter::repeat_n(value, repeat));
+    }
+
+    if lengths.len() > hlit + hdist {
+        return Err(invalid("code lengths overflow"));
+    }
+
+    Ok((
+        Huffman::new(&lengths[..hlit]),
+        Huffman::new(&lengths[hlit..]),
+    ))
+}
+
+/// Canonical Huffman decoding table
+///
+/// Stores the number of codes of each bit length and the symbols sorted
+/// by code, which is enough to decode one bit at a time.
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<usize>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0usize; MAX_BITS + 2];
+        for len in 1..=MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len] as usize;
+        }
+
+        let mut symbols = vec![0; offsets[MAX_BITS + 1]];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize]] = symbol;
+                offsets[len as usize] += 1;
+            }
+        }
+
+        Self { counts, symbols }
+    }
+
+    fn decode<R: BufRead>(&self, bits: &mut BitReader<R>) -> io::Result<usize> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+
+        for len in 1..=MAX_BITS {
+            code |= bits.read_bits(1)? as i32;
+            let count = self.counts[len] as i32;
+
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err(invalid("invalid Huffman code"))
+    }
+}
+
+/// LSB-first bit reader pulling bytes from a buffered reader
+struct BitReader<R> {
+    inner: R,
+    bit_buf: u32,
+    bit_count: u8,
+}
+
+impl<R: BufRead> BitReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            bit_buf: 0,
+            bit_count: 0,
+        }
+    }
+
+    /// Take one byte from the inner reader, or None at end of input
+    fn fetch(&mut self) -> io::Result<Option<u8>> {
+        let byte = loop {
+            match self.inner.fill_buf() {
+                Ok(buf) => break buf.first().copied(),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        };
+
+        if byte.is_some() {
+            self.inner.consume(1);
+        }
+        Ok(byte)
+    }
+
+    fn read_bits(&mut self, count: u8) -> io::Result<u32> {
+        while self.bit_count < count {
+            let byte = self.fetch()?.ok_or_else(truncated)?;
+            self.bit_buf |= (byte as u32) << self.bit_count;
+            self.bit_count += 8;
+        }
+
+        let value = self.bit_buf & ((1u32 << count) - 1);
+        self.bit_buf >>= count;
+        self.bit_count -= count;
+        Ok(value)
+    }
+
+    fn align_to_byte(&mut self) {
+        let drop = self.bit_count % 8;
+        self.bit_buf >>= drop;
+        self.bit_count -= drop;
+    }
+
+    /// Next whole byte, or None at end of input; only valid when aligned
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        if self.bit_count >= 8 {
+            return self.read_bits(8).map(|byte| Some(byte as u8));
+        }
+        self.fetch()
+    }
+
+    /// Next whole byte, failing at end of input; only valid when aligned
+    fn read_byte(&mut self) -> io::Result<u8> {
+        self.next_byte()?.ok_or_else(truncated)
+    }
+
+    fn read_u32_le(&mut self) -> io::Result<u32> {
+        let mut bytes = [0u8; 4];
+        for byte in &mut bytes {
+            *byte = self.read_byte()?;
+        }
+        Ok(u32::from_le_bytes(bytes))
+    }
+}
+
+/// Compute the CRC32 (IEEE) checksum used by gzip
+pub fn crc32(data: &[u8]) -> u32 {
+    crc32_update(0, data)
+}
+
+/// Extend a CRC32 computed over earlier bytes with more data
+fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+    let mut crc = !crc;
+
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+fn truncated() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of gzip stream")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    /// `printf 'hello hello hello\n' | gzip -9 -n`
+    const HELLO_GZ: [u8; 29] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xcb, 0x48, 0xcd, 0xc9,
+        0xc9, 0x57, 0xc8, 0x40, 0x90, 0x5c, 0x00, 0x3b, 0x7c, 0x8a, 0xdf, 0x12, 0x00, 0x00,
+        0x00,
+    ];
+
+    /// `steps_log()` compressed with zlib level 9 into a single member with
+    /// dynamic-Huffman blocks
+    const STEPS_GZ: [u8; 508] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xed, 0xd2, 0xbb, 0x8d,
+        0x02, 0x31, 0x14, 0x86, 0xd1, 0x9c, 0x2a, 0xa6, 0x01, 0x24, 0x5f, 0xcf, 0x83, 0x99,
+        0x29, 0x00, 0x89, 0x84, 0x4d, 0x36, 0x5a, 0xb4, 0x21, 0x39, 0x12, 0xf4, 0x2f, 0x62,
+        0x9b, 0xdb, 0x01, 0x27, 0xff, 0xe4, 0xc7, 0xaf, 0x73, 0xab, 0xa5, 0xce, 0xc7, 0x28,
+        0xc7, 0x58, 0x7f, 0x63, 0xda, 0xc7, 0xb2, 0x97, 0xf2, 0xf7, 0x3f, 0x5c, 0xae, 0xe7,
+        0x9f, 0x7d, 0x78, 0xbe, 0xee, 0x8f, 0xa1, 0x1c, 0x6e, 0x9f, 0x4d, 0xb4, 0x4d, 0x64,
+        0x4d, 0x6d, 0x9b, 0x29, 0x6b, 0xc6, 0xb6, 0xd9, 0xb2, 0x66, 0xea, 0xee, 0x5a, 0xb2,
+        0x68, 0x6e, 0xa3, 0x3a, 0x67, 0xd1, 0xd2, 0x46, 0x63, 0x7a, 0xd2, 0xa9, 0x7b, 0x76,
+        0xfa, 0xa6, 0xb5, 0x8d, 0x96, 0xf4, 0x73, 0x5b, 0x1b, 0xad, 0xd9, 0x4a, 0xd1, 0xad,
+        0x1d, 0x25, 0xdb, 0x3b, 0xfa, 0xbd, 0x6b, 0x7a, 0x56, 0xb7, 0x78, 0x4c, 0xd9, 0xb3,
+        0x62, 0xec, 0xf7, 0xcc, 0x7e, 0x18, 0xfd, 0xea, 0x5b, 0x36, 0x56, 0xf4, 0xb3, 0xe7,
+        0xbb, 0x13, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14,
+        0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45,
+        0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11,
+        0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44,
+        0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51,
+        0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14,
+        0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45,
+        0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11,
+        0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44,
+        0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51,
+        0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14,
+        0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45,
+        0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11,
+        0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44,
+        0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51,
+        0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14,
+        0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45,
+        0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11,
+        0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44,
+        0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51,
+        0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14,
+        0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45,
+        0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11,
+        0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0x45, 0x14, 0x51, 0x44,
+        0x11, 0x45, 0x14, 0x51, 0x44, 0x11, 0xf5, 0x35, 0xa2, 0xde, 0x19, 0x6c, 0x3b, 0x9f,
+        0x70, 0x16, 0x01, 0x00
+    ];
+
+    /// 120 repetitions of 16 distinct lines (71280 bytes), enough output
+    /// to cross several chunk and window boundaries
+    fn steps_log() -> Vec<u8> {
+        let block: String = (0..16)
+            .map(|i| format!("[2025-10-18T14:30:{:02}Z] INFO: step {}\n", i, i * i))
+            .collect();
+        block.repeat(120).into_bytes()
+    }
+
+    fn gunzip(data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut output = Vec::new();
+        GzDecoder::new(data).read_to_end(&mut output)?;
+        Ok(output)
+    }
+
+    /// Gzip `data` as uncompressed (stored) blocks of at most `block_len`
+    fn gzip_stored(data: &[u8], block_len: usize) -> Vec<u8> {
+        let mut out = vec![0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, 0, 0xff];
+
+        let mut blocks: Vec<&[u8]> = data.chunks(block_len).collect();
+        if blocks.is_empty() {
+            blocks.push(&[]);
+        }
+        for (i, block) in blocks.iter().enumerate() {
+            let len = block.len() as u16;
+            out.push((i + 1 == blocks.len()) as u8); // BFINAL, BTYPE=00 (stored)
+            out.extend_from_slice(&len.to_le_bytes());
+            out.extend_from_slice(&(!len).to_le_bytes());
+            out.extend_from_slice(block);
+        }
+
+        out.extend_from_slice(&crc32(data).to_le_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out
+    }
+
+    fn error_kind(data: &[u8]) -> io::ErrorKind {
+        gunzip(data).expect_err("corrupt input should fail").kind()
+    }
+
+    #[test]
+    fn test_decompress_fixed_huffman() {
+        let output = gunzip(&HELLO_GZ).unwrap();
+        assert_eq!(output, b"hello hello hello\n");
+    }
+
+    #[test]
+    fn test_decompress_stored_blocks() {
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        assert_eq!(gunzip(&gzip_stored(&data, 40_000)).unwrap(), data);
+        assert_eq!(gunzip(&gzip_stored(b"", 1)).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_decompress_dynamic_huffman_in_bounded_memory() {
+        let mut decoder = GzDecoder::new(&STEPS_GZ[..]);
+        let mut output = Vec::new();
+        decoder.read_to_end(&mut output).unwrap();
+
+        assert_eq!(output, steps_log());
+        // Only the window and one chunk (plus a final match) are retained
+        assert!(decoder.history.len() <= WINDOW_SIZE + OUTPUT_CHUNK + 258);
+    }
+
+    #[test]
+    fn test_decompress_through_tiny_buffers() {
+        let mut decoder = GzDecoder::new(BufReader::with_capacity(1, &STEPS_GZ[..]));
+        let mut output = Vec::new();
+        let mut buf = [0u8; 7];
+        loop {
+            match decoder.read(&mut buf).unwrap() {
+                0 => break,
+                n => output.extend_from_slice(&buf[..n]),
+            }
+        }
+
+        assert_eq!(output, steps_log());
+    }
+
+    #[test]
+    fn test_multi_member_with_zero_padding() {
+        let mut data = HELLO_GZ.to_vec();
+        data.extend_from_slice(&gzip_stored(b"bye\n", 16));
+        data.extend_from_slice(&[0; 8]);
+
+        assert_eq!(gunzip(&data).unwrap(), b"hello hello hello\nbye\n");
+    }
+
+    #[test]
+    fn test_skips_optional_header_fields() {
+        let mut data = vec![0x1f, 0x8b, 0x08];
+        data.push(FLAG_HCRC | FLAG_EXTRA | FLAG_NAME | FLAG_COMMENT);
+        data.extend_from_slice(&[0, 0, 0, 0, 0x02, 0x03]);
+        data.extend_from_slice(&[3, 0, b'x', b'y', b'z']);
+        data.extend_from_slice(b"session.log\0");
+        data.extend_from_slice(b"rotated\0");
+        data.extend_from_slice(&[0xab, 0xcd]);
+        data.extend_from_slice(&HELLO_GZ[10..]);
+
+        assert_eq!(gunzip(&data).unwrap(), b"hello hello hello\n");
+    }
+
+    #[test]
+    fn test_rejects_every_truncation() {
+        for len in 0..HELLO_GZ.len() {
+            assert_eq!(error_kind(&HELLO_GZ[..len]), io::ErrorKind::UnexpectedEof, "len {}", len);
+        }
+
+        let stored = gzip_stored(b"some stored text", 4);
+        for len in 0..stored.len() {
+            assert_eq!(error_kind(&stored[..len]), io::ErrorKind::UnexpectedEof, "len {}", len);
+        }
+
+        // A second member cut off inside its header
+        let mut data = HELLO_GZ.to_vec();
+        data.extend_from_slice(&HELLO_GZ[..5]);
+        assert_eq!(error_kind(&data), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_rejects_bad_crc_and_size() {
+        let mut bad_crc = HELLO_GZ;
+        bad_crc[HELLO_GZ.len() - 8] ^= 0xff;
+        let err = gunzip(&bad_crc).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("CRC32"));
+
+        let mut bad_size = HELLO_GZ;
+        bad_size[HELLO_GZ.len() - 4] += 1;
+        let err = gunzip(&bad_size).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("size"));
+    }
+
+    #[test]
+    fn test_yields_output_before_a_corrupt_trailer() {
+        let mut data = STEPS_GZ;
+        data[STEPS_GZ.len() - 8] ^= 0xff;
+
+        let mut decoder = GzDecoder::new(&data[..]);
+        let mut start = vec![0u8; 1000];
+        decoder.read_exact(&mut start).unwrap();
+        assert_eq!(start, steps_log()[..1000]);
+
+        let mut rest = Vec::new();
+        assert!(decoder.read_to_end(&mut rest).is_err());
+        // The failure is repeated rather than turning into a clean EOF
+        assert!(decoder.read(&mut start).is_err());
+    }
+
+    #[test]
+    fn test_rejects_corrupt_deflate_data() {
+        let with_body = |body: &[u8]| -> Vec<u8> {
+            let mut data = HELLO_GZ[..10].to_vec();
+            data.extend_from_slice(body);
+            data.extend_from_slice(&[0; 8]);
+            data
+        };
+
+        // BFINAL=1 with the reserved block type 3
+        assert_eq!(error_kind(&with_body(&[0x07])), io::ErrorKind::InvalidData);
+
+        // Stored block whose NLEN isn't the complement of LEN
+        let bad_nlen = with_body(&[0x01, 2, 0, 0, 0, b'h', b'i']);
+        assert_eq!(error_kind(&bad_nlen), io::ErrorKind::InvalidData);
+
+        // Fixed-Huffman match of distance 1 before any output
+        assert_eq!(error_kind(&with_body(&[0x03, 0x02, 0x00])), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_rejects_garbage_after_stream() {
+        let mut data = HELLO_GZ.to_vec();
+        data.extend_from_slice(b"junk");
+        assert_eq!(error_kind(&data), io::ErrorKind::InvalidData);
+
+        let mut data = HELLO_GZ.to_vec();
+        data.extend_from_slice(&[0, 0, 1]);
+        assert_eq!(error_kind(&data), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32_update(crc32(b"1234"), b"56789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_is_gzip() {
+        assert!(is_gzip(&HELLO_GZ));
+        assert!(!is_gzip(b"[2025-10-18T14:30:45Z] INFO: plain"));
+    }
+}
@@ -0,0 +1,246 @@
+// Reading logs over HTTP (requires the `http` feature)
+//
+// A minimal blocking GET client over std::net::TcpStream, enough to pull
+// a log body from an internal endpoint without downloading it first.
+// Only plain `http://` URLs are supported; HTTPS would need a TLS stack.
+//
+// Demonstrates:
+// - Speaking a text protocol over a raw TcpStream
+// - Splitting a byte buffer into headers and body
+// - Decoding chunked transfer encoding
+
+use super::{parse_log_reader_with_format, LogFormat, ParseOptions};
+use crate::error::{ParseError, ParseResult};
+use crate::types::LogEntry;
+use std::io::{Cursor, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// How long to wait on a stalled connection before giving up
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Fetch a log over HTTP and parse it as bracket-format entries
+pub fn parse_log_url(url: &str, options: &ParseOptions) -> ParseResult<Vec<LogEntry>> {
+    let body = fetch(url)?;
+    parse_log_reader_with_format(Cursor::new(body), LogFormat::Bracket, options)
+}
+
+/// GET `url` and return the response body, failing on any non-200 status
+pub fn fetch(url: &str) -> ParseResult<Vec<u8>> {
+    let target = Target::parse(url)?;
+
+    let mut stream = TcpStream::connect((target.host.as_str(), target.port))
+        .map_err(|e| ParseError::Http(format!("cannot connect to {}: {}", url, e)))?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nAccept-Encoding: identity\r\n\r\n",
+        target.path, target.host
+    )?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+
+    read_response(url, &response)
+}
+
+/// Why `https://` URLs are refused
+const HTTPS_UNSUPPORTED: &str =
+    "https:// is not supported; only plain-HTTP (http://) endpoints can be fetched";
+
+/// The parts of an `http://` URL needed to make a request
+#[derive(Debug, PartialEq)]
+struct Target {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Target {
+    fn parse(url: &str) -> ParseResult<Self> {
+        let invalid = |details: &str| ParseError::Http(format!("{}: {}", url, details));
+
+        let rest = match url.split_once("://") {
+            Some((scheme, rest)) if scheme.eq_ignore_ascii_case("http") => rest,
+            Some((scheme, _)) if scheme.eq_ignore_ascii_case("https") => {
+                return Err(invalid(HTTPS_UNSUPPORTED))
+            }
+            _ => return Err(invalid("expected a plain http:// URL")),
+        };
+
+        // Fragments are never sent to the server
+        let rest = rest.split('#').next().unwrap_or_default();
+
+        let (authority, path) = match rest.find('/') {
+            Some(idx) => (&rest[..idx], &rest[idx..]),
+            None => (rest, "/"),
+        };
+
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse().map_err(|_| invalid("invalid port"))?,
+            ),
+            None => (authority, 80),
+        };
+
+        if host.is_empty() {
+            return Err(invalid("missing host"));
+        }
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+/// Check the status line and extract the (de-chunked) body
+fn read_response(url: &str, response: &[u8]) -> ParseResult<Vec<u8>> {
+    let malformed = || ParseError::Http(format!("{}: malformed HTTP response", url));
+
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(malformed)?;
+    let head = std::str::from_utf8(&response[..header_end]).map_err(|_| malformed())?;
+    let body = &response[header_end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status_line = lines.next().unwrap_or_default();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(malformed)?;
+
+    if status != 200 {
+        let reason = status_line.splitn(3, ' ').nth(2).unwrap_or_default();
+        return Err(ParseError::Http(format!(
+            "GET {} returned {} {}",
+            url, status, reason
+        )));
+    }
+
+    let chunked = lines.any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+
+    if chunked {
+        decode_chunked(body).ok_or_else(malformed)
+    } else {
+        Ok(body.to_vec())
+    }
+}
+
+/// Reassemble a `Transfer-Encoding: chunked` body
+fn decode_chunked(mut data: &[u8]) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+
+    loop {
+        let line_end = data.windows(2).position(|w| w == b"\r\n")?;
+        let size_line = std::str::from_utf8(&data[..line_end]).ok()?;
+
+        // Chunk extensions (`1a;name=value`) are ignored
+        let size_hex = size_line.split(';').next()?.trim();
+        let size = usize::from_str_radix(size_hex, 16).ok()?;
+        data = &data[line_end + 2..];
+
+        if size == 0 {
+            return Some(body);
+        }
+
+        body.extend_from_slice(data.get(..size)?);
+        data = data.get(size + 2..)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serve one canned response on a local port, returning its base URL
+    fn serve_once(response: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            // Drain the request headers before answering
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_parse_log_url_from_mock_server() {
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\r\n\
+             [2025-10-18T14:30:45Z] INFO: Starting session\n\
+             [2025-10-18T14:30:46Z] ERROR: Connection lost\n",
+        );
+
+        let url = format!("{}/logs/session.log", url);
+        let entries = parse_log_url(&url, &ParseOptions::default()).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].message, "Connection lost");
+    }
+
+    #[test]
+    fn test_non_200_is_an_error() {
+        let url = serve_once("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+
+        match fetch(&url) {
+            Err(ParseError::Http(details)) => assert!(details.contains("404 Not Found")),
+            other => panic!("Expected Http error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_chunked_body_is_decoded() {
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+             5\r\nhello\r\n7;ext=1\r\n, world\r\n0\r\n\r\n",
+        );
+
+        assert_eq!(fetch(&url).unwrap(), b"hello, world");
+    }
+
+    #[test]
+    fn test_target_parsing() {
+        assert_eq!(
+            Target::parse("http://logs.internal:8080/a/b.log#top").unwrap(),
+            Target {
+                host: "logs.internal".to_string(),
+                port: 8080,
+                path: "/a/b.log".to_string(),
+            }
+        );
+        assert_eq!(Target::parse("http://logs.internal").unwrap().path, "/");
+        match Target::parse("https://logs.internal/x") {
+            Err(ParseError::Http(details)) => {
+                assert_eq!(details, format!("https://logs.internal/x: {}", HTTPS_UNSUPPORTED))
+            }
+            other => panic!("expected an Http error, got {:?}", other),
+        }
+        assert!(Target::parse("logs.internal/x").is_err());
+    }
+}
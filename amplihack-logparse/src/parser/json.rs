@@ -0,0 +1,205 @@
+// JSON-lines (NDJSON) parsing
+//
+// Handles lines like:
+//   {"ts":"2025-10-18T14:30:45Z","level":"info","msg":"starting","agent":"architect"}
+//
+// Common key aliases are accepted (`ts`/`time`/`timestamp`,
+// `msg`/`message`); every other key is kept in `LogEntry.fields`.
+//
+// Also extracts JSON objects embedded at the end of bracket-format
+// messages, e.g. `AGENT: result {"status":"ok","tokens":1500}`.
+
+use super::logfmt::parse_duration_ms;
+use super::{parse_entry_type, ParseOptions};
+use crate::error::{ParseError, ParseResult};
+use crate::types::{EntryType, LogEntry};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Intermediate shape of one JSON log line
+///
+/// Demonstrates:
+/// - serde aliases for tolerant field names
+/// - #[serde(flatten)] to capture unknown keys
+#[derive(Deserialize)]
+struct JsonLine {
+    #[serde(alias = "ts", alias = "time")]
+    timestamp: String,
+
+    #[serde(default)]
+    level: Option<String>,
+
+    #[serde(default, alias = "msg")]
+    message: String,
+
+    #[serde(default, alias = "agent_name")]
+    agent: Option<String>,
+
+    #[serde(default, alias = "duration_ms")]
+    duration: Option<Value>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+/// Parse a single JSON line into a LogEntry
+pub(super) fn parse_json_entry(
+    line: &str,
+    line_num: usize,
+    options: &ParseOptions,
+) -> ParseResult<LogEntry> {
+    let parsed: JsonLine =
+        serde_json::from_str(line).map_err(|e| ParseError::MalformedEntry {
+            line: line_num,
+            details: format!("invalid JSON log line: {}", e),
+        })?;
+
+    let timestamp = options.parse_timestamp(&parsed.timestamp)?;
+
+    let entry_type = parsed
+        .level
+        .map(|level| parse_entry_type(&level))
+        .unwrap_or(EntryType::Unknown);
+
+    // Fractional milliseconds are rounded, as in `parse_human_duration`
+    let duration_ms = parsed.duration.and_then(|value| match value {
+        Value::Number(n) => n.as_u64().or_else(|| {
+            n.as_f64().filter(|ms| *ms >= 0.0).map(|ms| ms.round() as u64)
+        }),
+        Value::String(s) => parse_duration_ms(&s),
+        _ => None,
+    });
+
+    let fields = parsed
+        .extra
+        .into_iter()
+        .map(|(key, value)| (key, field_value(value)))
+        .collect();
+
+    Ok(LogEntry {
+        timestamp,
+        entry_type,
+        message: parsed.message,
+        agent_name: parsed
+            .agent
+            .map(|agent| options.canonical_agent_name(&agent))
+            .filter(|agent| !agent.is_empty()),
+        duration_ms,
+        fields,
+        byte_offset: None,
+    })
+}
+
+/// Fields of the JSON object that ends a message, if there is one
+///
+/// The outermost object is found by trying each `{` from the left until
+/// the rest of the message parses, so nested objects are kept whole. A
+/// message that doesn't end in a valid object yields no fields.
+///
+/// Demonstrates:
+/// - Option combinators with find_map for a first-success search
+/// - Parsing into serde_json::Map to accept only objects
+pub(super) fn extract_embedded_object(message: &str) -> HashMap<String, String> {
+    let message = message.trim_end();
+    if !message.ends_with('}') {
+        return HashMap::new();
+    }
+
+    message
+        .match_indices('{')
+        .find_map(|(start, _)| {
+            serde_json::from_str::<serde_json::Map<String, Value>>(&message[start..]).ok()
+        })
+        .map(|object| object.into_iter().map(|(key, value)| (key, field_value(value))).collect())
+        .unwrap_or_default()
+}
+
+/// Field text for a JSON value: strings as-is, anything else as compact JSON
+fn field_value(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_timestamp;
+
+    #[test]
+    fn test_parse_json_entry_full() {
+        let line = r#"{"ts":"2025-10-18T14:30:45Z","level":"agent","msg":"architect done","agent":"architect","duration":1234,"tokens_in":42}"#;
+        let entry = parse_json_entry(line, 1, &ParseOptions::default()).unwrap();
+
+        assert_eq!(entry.timestamp, parse_timestamp("2025-10-18T14:30:45Z").unwrap());
+        assert_eq!(entry.entry_type, EntryType::AgentInvocation);
+        assert_eq!(entry.message, "architect done");
+        assert_eq!(entry.agent_name, Some("architect".to_string()));
+        assert_eq!(entry.duration_ms, Some(1234));
+        assert_eq!(entry.fields.get("tokens_in"), Some(&"42".to_string()));
+    }
+
+    #[test]
+    fn test_parse_json_entry_duration_forms() {
+        let duration = |value: &str| {
+            let line = format!(r#"{{"ts":"2025-10-18T14:30:45Z","duration":{}}}"#, value);
+            parse_json_entry(&line, 1, &ParseOptions::default()).unwrap().duration_ms
+        };
+
+        assert_eq!(duration("1234"), Some(1234));
+        assert_eq!(duration("12.6"), Some(13));
+        assert_eq!(duration(r#""1234""#), Some(1234));
+        assert_eq!(duration(r#""1.2s""#), Some(1200));
+        assert_eq!(duration(r#""2m30s""#), Some(150_000));
+        assert_eq!(duration("-5"), None);
+        assert_eq!(duration("true"), None);
+    }
+
+    #[test]
+    fn test_parse_json_entry_missing_optional_fields() {
+        let line = r#"{"time":"2025-10-18T14:30:45Z"}"#;
+        let entry = parse_json_entry(line, 1, &ParseOptions::default()).unwrap();
+
+        assert_eq!(entry.entry_type, EntryType::Unknown);
+        assert_eq!(entry.message, "");
+        assert_eq!(entry.agent_name, None);
+        assert_eq!(entry.duration_ms, None);
+        assert!(entry.fields.is_empty());
+    }
+
+    #[test]
+    fn test_extract_embedded_object() {
+        let fields = extract_embedded_object(
+            r#"result {"status":"ok","tokens":1500,"usage":{"in":1,"out":2},"ok":true}"#,
+        );
+
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields["status"], "ok");
+        assert_eq!(fields["tokens"], "1500");
+        assert_eq!(fields["usage"], r#"{"in":1,"out":2}"#);
+        assert_eq!(fields["ok"], "true");
+    }
+
+    #[test]
+    fn test_extract_embedded_object_ignores_non_objects() {
+        for message in [
+            r#"result {"status":"ok","tokens":}"#,
+            r#"result {"status":"ok" and more text"#,
+            r#"{"status":"ok"} followed by text"#,
+            r#"result ["not", "an", "object"]"#,
+            "set {x} to {y}",
+            "plain message",
+            "",
+        ] {
+            assert!(extract_embedded_object(message).is_empty(), "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_parse_json_entry_rejects_invalid_json() {
+        let result = parse_json_entry("not json at all", 7, &ParseOptions::default());
+        assert!(matches!(result, Err(ParseError::MalformedEntry { line: 7, .. })));
+    }
+}
@@ -0,0 +1,172 @@
+// logfmt line parsing
+//
+// Handles lines like:
+//   time=2025-10-18T14:30:45Z level=info msg="starting" agent=architect
+//
+// Well-known keys map onto LogEntry fields; every other key is kept in
+// `LogEntry.fields`.
+
+use super::{parse_entry_type, parse_human_duration, ParseOptions};
+use crate::error::{ParseError, ParseResult};
+use crate::types::{EntryType, LogEntry};
+use std::collections::HashMap;
+
+/// Parse a single logfmt line into a LogEntry
+///
+/// Demonstrates:
+/// - Moving values out of a HashMap with remove()
+/// - Option combinators for optional keys
+pub(super) fn parse_logfmt_entry(
+    line: &str,
+    line_num: usize,
+    options: &ParseOptions,
+) -> ParseResult<LogEntry> {
+    let mut fields: HashMap<String, String> = tokenize(line).into_iter().collect();
+
+    let time = fields.remove("time").ok_or_else(|| ParseError::MalformedEntry {
+        line: line_num,
+        details: "logfmt line has no 'time' key".to_string(),
+    })?;
+    let timestamp = options.parse_timestamp(&time)?;
+
+    let entry_type = fields
+        .remove("level")
+        .map(|level| parse_entry_type(&level))
+        .unwrap_or(EntryType::Unknown);
+
+    let message = fields.remove("msg").unwrap_or_default();
+    let agent_name = fields
+        .remove("agent")
+        .map(|agent| options.canonical_agent_name(&agent))
+        .filter(|agent| !agent.is_empty());
+    let duration_ms = fields.remove("duration").and_then(|d| parse_duration_ms(&d));
+
+    Ok(LogEntry {
+        timestamp,
+        entry_type,
+        message,
+        agent_name,
+        duration_ms,
+        fields,
+        byte_offset: None,
+    })
+}
+
+/// Parse a duration value into milliseconds
+///
+/// Accepts every form `parse_human_duration` does (`1234ms`, `1.2s`,
+/// `2m30s`), as bracket-format messages do, plus a bare number of
+/// milliseconds (`1234`).
+pub(super) fn parse_duration_ms(value: &str) -> Option<u64> {
+    value.trim().parse().ok().or_else(|| parse_human_duration(value))
+}
+
+/// Split a logfmt line into key/value pairs
+///
+/// Values may be double-quoted (with `\"` and `\\` escapes). A bare key
+/// with no `=` gets an empty value.
+///
+/// Demonstrates:
+/// - Peekable char iterators for hand-written tokenizers
+fn tokenize(line: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '=') {
+            key.push(c);
+        }
+
+        if key.is_empty() {
+            // Skip a stray '=' or stop at end of input
+            if chars.next().is_none() {
+                break;
+            }
+            continue;
+        }
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            if chars.next_if_eq(&'"').is_some() {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                value.push(escaped);
+                            }
+                        }
+                        _ => value.push(c),
+                    }
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    value.push(c);
+                }
+            }
+        }
+
+        pairs.push((key, value));
+    }
+
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let pairs = tokenize(r#"a=1 b="two words" c="esc \"q\"" flag"#);
+        assert_eq!(
+            pairs,
+            vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "two words".to_string()),
+                ("c".to_string(), "esc \"q\"".to_string()),
+                ("flag".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_logfmt_entry() {
+        let line = r#"time=2025-10-18T14:30:45Z level=info msg="starting the build" agent=architect duration=1234ms session_id=abc"#;
+        let entry = parse_logfmt_entry(line, 1, &ParseOptions::default()).unwrap();
+
+        assert_eq!(entry.timestamp.to_rfc3339(), "2025-10-18T14:30:45+00:00");
+        assert_eq!(entry.entry_type, EntryType::Info);
+        assert_eq!(entry.message, "starting the build");
+        assert_eq!(entry.agent_name.as_deref(), Some("architect"));
+        assert_eq!(entry.duration_ms, Some(1234));
+        assert_eq!(entry.fields.len(), 1);
+        assert_eq!(entry.fields["session_id"], "abc");
+    }
+
+    #[test]
+    fn test_parse_duration_ms_accepts_human_durations() {
+        assert_eq!(parse_duration_ms("1234"), Some(1234));
+        assert_eq!(parse_duration_ms("500ms"), Some(500));
+        assert_eq!(parse_duration_ms("1.2s"), Some(1200));
+        assert_eq!(parse_duration_ms("2m30s"), Some(150_000));
+        assert_eq!(parse_duration_ms("soon"), None);
+        assert_eq!(parse_duration_ms("5h"), None);
+
+        let line = "time=2025-10-18T14:30:45Z level=agent agent=builder duration=1.5s";
+        let entry = parse_logfmt_entry(line, 1, &ParseOptions::default()).unwrap();
+        assert_eq!(entry.duration_ms, Some(1500));
+    }
+
+    #[test]
+    fn test_parse_logfmt_missing_time() {
+        let result = parse_logfmt_entry("level=info msg=hello", 7, &ParseOptions::default());
+        match result {
+            Err(ParseError::MalformedEntry { line, .. }) => assert_eq!(line, 7),
+            _ => panic!("Expected MalformedEntry"),
+        }
+    }
+}
@@ -7,12 +7,151 @@
 // - Error handling: Result with custom ParseError
 // - Iterators: Processing lines efficiently
 
+pub mod follow;
+pub mod glob;
+pub mod gzip;
+#[cfg(feature = "http")]
+pub mod http;
+mod json;
+mod logfmt;
+pub mod recovery;
+pub mod sample;
+
+use crate::cache::CACHE_EXTENSION;
 use crate::error::{ParseError, ParseResult};
+use crate::log_warn;
 use crate::types::{LogEntry, EntryType};
+use recovery::{
+    ParseReport, INVALID_UTF8, MISSING_LEVEL, NO_CLOSING_BRACKET, NO_OPENING_BRACKET,
+    UNRECOGNIZED_LEVEL,
+};
+use sample::Sampler;
 use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Supported log line formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// `[TIMESTAMP] LEVEL: MESSAGE`
+    #[default]
+    Bracket,
+
+    /// `time=... level=... msg="..." agent=... duration=...`
+    Logfmt,
+
+    /// One JSON object per line: `{"ts":"...","level":"...","msg":"..."}`
+    Json,
+}
+
+/// Settings that change how log lines are parsed
+///
+/// Handed to the `parse_log_*` functions and `LogEntryStream` rather than
+/// kept in process-wide state, so one process can parse logs from
+/// differently configured producers side by side.
+///
+/// Demonstrates:
+/// - Builder pattern: consuming `with_*` methods, validated up front
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Custom strftime format tried before the built-in timestamp formats
+    timestamp_format: Option<String>,
+
+    /// Whether ANSI escape sequences are removed from lines before parsing
+    strip_ansi: bool,
+
+    /// Whether bracket-format lines with unrecognized levels are errors
+    strict_levels: bool,
+
+    /// Agent aliases, keyed and valued by normalized names
+    agent_aliases: HashMap<String, String>,
+}
+
+impl ParseOptions {
+    /// Options matching the built-in parsing behavior
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Try a custom strftime `format` before the built-in timestamp formats
+    ///
+    /// The format is validated here so a bad format string fails once, up
+    /// front, rather than on every line.
+    pub fn with_timestamp_format(mut self, format: &str) -> ParseResult<Self> {
+        validate_timestamp_format(format)?;
+        self.timestamp_format = Some(format.to_string());
+        Ok(self)
+    }
+
+    /// The custom timestamp format, if one was set
+    pub fn timestamp_format(&self) -> Option<&str> {
+        self.timestamp_format.as_deref()
+    }
+
+    /// Parse a timestamp with the custom format, then the built-in ones
+    pub fn parse_timestamp(&self, s: &str) -> ParseResult<DateTime<Utc>> {
+        parse_timestamp_with_format(s, self.timestamp_format())
+    }
+
+    /// Remove ANSI escape sequences (colors) from lines before parsing
+    /// them (default off)
+    pub fn with_strip_ansi(mut self, enabled: bool) -> Self {
+        self.strip_ansi = enabled;
+        self
+    }
+
+    /// Whether ANSI escape sequences are stripped
+    pub fn strip_ansi(&self) -> bool {
+        self.strip_ansi
+    }
+
+    /// Treat unrecognized levels (`FATAL:`) as malformed entries instead of
+    /// `EntryType::Unknown` (default off)
+    pub fn with_strict_levels(mut self, enabled: bool) -> Self {
+        self.strict_levels = enabled;
+        self
+    }
+
+    /// Whether unrecognized levels are rejected
+    pub fn strict_levels(&self) -> bool {
+        self.strict_levels
+    }
+
+    /// Resolve agent names through `aliases` (e.g. `arch` -> `architect`)
+    ///
+    /// Keys and values are normalized first, so an alias matches however
+    /// a producer capitalizes or prefixes the name.
+    pub fn with_agent_aliases(mut self, aliases: &HashMap<String, String>) -> Self {
+        self.agent_aliases = aliases
+            .iter()
+            .map(|(alias, name)| (normalize_agent_name(alias), normalize_agent_name(name)))
+            .collect();
+        self
+    }
+
+    /// Normalize an agent name and resolve it through the aliases
+    pub fn canonical_agent_name(&self, raw: &str) -> String {
+        let name = normalize_agent_name(raw);
+        match self.agent_aliases.get(&name) {
+            Some(canonical) => canonical.clone(),
+            None => name,
+        }
+    }
+
+    /// Apply ANSI stripping if enabled, borrowing when nothing changes
+    fn clean_line<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        if self.strip_ansi && line.contains('\x1b') {
+            Cow::Owned(strip_ansi(line))
+        } else {
+            Cow::Borrowed(line)
+        }
+    }
+}
 
 /// Parse a log file and return all entries
 ///
@@ -20,61 +159,621 @@ use std::path::Path;
 /// - Borrowing: Takes &Path, doesn't need ownership
 /// - Error handling: Returns Result with ?
 /// - Iterators: Chain operations efficiently
+///
+/// Gzip-compressed files (`.log.gz`) are detected by their magic bytes
+/// and decompressed transparently.
 pub fn parse_log_file(path: &Path) -> ParseResult<Vec<LogEntry>> {
-    let file = File::open(path)
-        .map_err(|_| ParseError::FileNotFound(path.to_path_buf()))?;
+    parse_log_file_with_options(path, &ParseOptions::default())
+}
+
+/// Parse a log file with the given parse options
+pub fn parse_log_file_with_options(
+    path: &Path,
+    options: &ParseOptions,
+) -> ParseResult<Vec<LogEntry>> {
+    parse_log_file_with_format(path, LogFormat::Bracket, options)
+}
+
+/// Parse a log file, also tallying why malformed lines failed
+///
+/// Malformed lines are still reported and skipped as in `parse_log_file`;
+/// the returned `ParseReport` counts them by kind.
+pub fn parse_log_file_report(
+    path: &Path,
+    options: &ParseOptions,
+) -> ParseResult<(Vec<LogEntry>, ParseReport)> {
+    collect_report(open_log_stream(path, LogFormat::Bracket, options)?)
+}
+
+/// Parse bracket-format entries from a reader, tallying failures by kind
+pub fn parse_log_reader_report<R: BufRead>(
+    reader: R,
+    options: &ParseOptions,
+) -> ParseResult<(Vec<LogEntry>, ParseReport)> {
+    let stream = LogEntryStream::new(reader, 1, LogFormat::Bracket).with_options(options.clone());
+    collect_report(stream)
+}
+
+/// Drain a stream, warning about and tallying each malformed line
+fn collect_report<R: BufRead>(
+    stream: LogEntryStream<R>,
+) -> ParseResult<(Vec<LogEntry>, ParseReport)> {
+    let mut report = ParseReport::default();
+    let entries = collect_stream(stream, |line_num, e| {
+        report.record_failure(&e);
+        warn_malformed(line_num, e);
+    })?;
+
+    report.entries = entries.len();
+    Ok((entries, report))
+}
+
+/// Parse a log file whose lines use the given format
+pub fn parse_log_file_with_format(
+    path: &Path,
+    format: LogFormat,
+    options: &ParseOptions,
+) -> ParseResult<Vec<LogEntry>> {
+    collect_stream(open_log_stream(path, format, options)?, warn_malformed)
+}
+
+/// Parse a log file, keeping a reproducible random sample of its entries
+///
+/// Each entry survives with probability `rate`; the same `seed` always
+/// keeps the same entries. Every line is still parsed (continuation lines
+/// must be attached before an entry can be dropped), but only the sample
+/// is held in memory. Malformed lines are reported and skipped.
+pub fn parse_log_file_sampled(
+    path: &Path,
+    rate: f64,
+    seed: u64,
+    options: &ParseOptions,
+) -> ParseResult<Vec<LogEntry>> {
+    let mut stream = open_log_stream(path, LogFormat::Bracket, options)?;
+    let mut sampler = Sampler::new(rate, seed);
+    let mut entries = Vec::new();
+
+    while let Some(result) = stream.next() {
+        match result {
+            Ok(entry) => {
+                if sampler.keep() {
+                    entries.push(entry);
+                }
+            }
+            Err(ParseError::Io(e)) => return Err(ParseError::Io(e)),
+            Err(e) => warn_malformed(stream.line_num(), e),
+        }
+    }
+
+    Ok(entries)
+}
 
-    let reader = BufReader::new(file);
+/// Parse a log file, failing once too many lines are malformed
+///
+/// Like `parse_log_file`, malformed lines are reported and skipped, but
+/// parsing aborts with `ErrorBudgetExceeded` as soon as more than
+/// `max_errors` lines have failed.
+pub fn parse_log_file_strict(
+    path: &Path,
+    max_errors: usize,
+    options: &ParseOptions,
+) -> ParseResult<Vec<LogEntry>> {
+    let mut stream = open_log_stream(path, LogFormat::Bracket, options)?;
     let mut entries = Vec::new();
+    let mut errors = 0;
+
+    while let Some(result) = stream.next() {
+        match result {
+            Ok(entry) => entries.push(entry),
+            Err(ParseError::Io(e)) => return Err(ParseError::Io(e)),
+            Err(e) => {
+                log_warn!("Failed to parse line {}: {}", stream.line_num(), e);
+                errors += 1;
+
+                if errors > max_errors {
+                    return Err(ParseError::ErrorBudgetExceeded { errors, max_errors });
+                }
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Parse a log file by memory-mapping it (requires the `mmap` feature)
+///
+/// Avoids the per-line String allocations of the buffered reader, which
+/// matters for large single logs. Falls back to buffered parsing when the
+/// feature is disabled, the file can't be mapped, or it is gzip-compressed.
+/// Only the bracket format is supported.
+pub fn parse_log_file_mmap(path: &Path, options: &ParseOptions) -> ParseResult<Vec<LogEntry>> {
+    #[cfg(feature = "mmap")]
+    {
+        let file = File::open(path)
+            .map_err(|_| ParseError::FileNotFound(path.to_path_buf()))?;
+
+        // SAFETY: the map is read-only and dropped before returning. If
+        // another process truncates the file meanwhile, reads may fault;
+        // that's the accepted trade-off of mmap for log files.
+        if let Ok(map) = unsafe { memmap2::Mmap::map(&file) } {
+            if !gzip::is_gzip(&map) {
+                return Ok(parse_log_bytes(&map, options));
+            }
+        }
+    }
+
+    parse_log_file_with_options(path, options)
+}
+
+/// Parse bracket-format entries from an in-memory buffer
+///
+/// Lines are split on `\n` without allocating per line. Continuation
+/// lines and malformed lines are handled as in `parse_log_reader`. Each
+/// entry's `byte_offset` is the position of its first line in `data`.
+///
+/// Demonstrates:
+/// - Zero-copy iteration with slice::split
+pub fn parse_log_bytes(data: &[u8], options: &ParseOptions) -> Vec<LogEntry> {
+    let mut entries: Vec<LogEntry> = Vec::new();
+    let mut offset = 0;
 
-    for (line_num, line_result) in reader.lines().enumerate() {
-        let line = line_result?;  // ? operator for error propagation
+    for (idx, raw) in data.split(|&b| b == b'\n').enumerate() {
+        let line_num = idx + 1;
+        let line_start = offset;
+        offset += raw.len() as u64 + 1;
+        let line = raw.strip_suffix(b"\r").unwrap_or(raw);
 
         // Skip empty lines
-        if line.trim().is_empty() {
+        if line.trim_ascii().is_empty() {
             continue;
         }
 
-        // Parse each line into a LogEntry
-        match parse_log_entry(&line) {
-            Ok(entry) => entries.push(entry),
-            Err(e) => {
-                // Log parsing error but continue (resilient parsing)
-                eprintln!("Warning: Failed to parse line {}: {}", line_num + 1, e);
+        // Continuation of the previous entry
+        if !line.starts_with(b"[") {
+            if let (Some(previous), Ok(text)) = (entries.last_mut(), std::str::from_utf8(line)) {
+                let text = options.clean_line(text);
+                if !starts_bracket_entry(&text, options) {
+                    previous.message.push('\n');
+                    previous.message.push_str(text.trim_end());
+                    continue;
+                }
             }
         }
+
+        match parse_log_entry_bytes(line, line_num, options) {
+            Ok(entry) => entries.push(LogEntry {
+                byte_offset: Some(line_start),
+                ..entry
+            }),
+            Err(e) => log_warn!("Failed to parse line {}: {}", line_num, e),
+        }
+    }
+
+    entries
+}
+
+/// Parse a single bracket-format line given as raw bytes
+pub fn parse_log_entry_bytes(
+    line: &[u8],
+    line_num: usize,
+    options: &ParseOptions,
+) -> ParseResult<LogEntry> {
+    let line = std::str::from_utf8(line).map_err(|e| ParseError::MalformedEntry {
+        line: line_num,
+        details: format!("{}: {}", INVALID_UTF8, e),
+    })?;
+
+    parse_log_entry(&options.clean_line(line), line_num, options)
+}
+
+/// Outcome of checking every line of a log file
+#[derive(Debug)]
+pub struct ValidationReport {
+    /// Entries that parsed successfully
+    pub valid_entries: usize,
+
+    /// Failed lines as (1-based line number, error)
+    pub failures: Vec<(usize, ParseError)>,
+}
+
+impl ValidationReport {
+    /// True if no line failed to parse
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// The failures tallied by kind
+    pub fn parse_report(&self) -> ParseReport {
+        let mut report = ParseReport {
+            entries: self.valid_entries,
+            ..ParseReport::default()
+        };
+        for (_, error) in &self.failures {
+            report.record_failure(error);
+        }
+        report
+    }
+}
+
+/// Parse a log file, recording every malformed line instead of warning
+pub fn validate_log_file(path: &Path, options: &ParseOptions) -> ParseResult<ValidationReport> {
+    let mut failures = Vec::new();
+    let (reader, _) = open_log_reader(path)?;
+    let entries = parse_lines_from(reader, 1, LogFormat::Bracket, options, |line_num, e| {
+        failures.push((line_num, e))
+    })?;
+
+    Ok(ValidationReport {
+        valid_entries: entries.len(),
+        failures,
+    })
+}
+
+/// Lazily parse a log file, yielding entries one at a time
+///
+/// Unlike `parse_log_file`, entries are never collected, so memory use
+/// stays bounded by the longest entry. Malformed lines are yielded as
+/// `Err` items and iteration continues; a file that cannot be opened
+/// yields a single `Err`. Gzip-compressed files are decompressed as they
+/// are read.
+pub fn parse_log_entries_streaming(
+    path: &Path,
+    options: &ParseOptions,
+) -> impl Iterator<Item = ParseResult<LogEntry>> {
+    let (stream, open_error) = match open_log_stream(path, LogFormat::Bracket, options) {
+        Ok(stream) => (Some(stream), None),
+        Err(e) => (None, Some(Err(e))),
+    };
+
+    open_error.into_iter().chain(stream.into_iter().flatten())
+}
+
+/// Open a log file for buffered reading, transparently decompressing gzip
+///
+/// Also returns whether the file was compressed.
+fn open_log_reader(path: &Path) -> ParseResult<(Box<dyn BufRead>, bool)> {
+    let file = File::open(path)
+        .map_err(|_| ParseError::FileNotFound(path.to_path_buf()))?;
+
+    let mut reader = BufReader::new(file);
+
+    // Peek at the first bytes without consuming them
+    if gzip::is_gzip(reader.fill_buf()?) {
+        let decoder = gzip::GzDecoder::new(reader);
+        return Ok((Box::new(BufReader::new(decoder)), true));
+    }
+
+    Ok((Box::new(reader), false))
+}
+
+/// Open a log file as an entry stream
+///
+/// Byte offsets are only tracked for uncompressed files, since offsets
+/// into decompressed data don't correspond to positions in the file.
+fn open_log_stream(
+    path: &Path,
+    format: LogFormat,
+    options: &ParseOptions,
+) -> ParseResult<LogEntryStream<Box<dyn BufRead>>> {
+    let (reader, compressed) = open_log_reader(path)?;
+    let stream = LogEntryStream::new(reader, 1, format).with_options(options.clone());
+
+    Ok(if compressed { stream } else { stream.with_byte_offsets(0) })
+}
+
+/// Check whether a path looks like a log file (`.log` or `.log.gz`)
+///
+/// Demonstrates:
+/// - Borrowing: Takes &Path
+/// - Option chaining over path components
+pub fn is_log_file(path: &Path) -> bool {
+    if !path.is_file() {
+        return false;
+    }
+
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(".log") || name.ends_with(".log.gz"))
+}
+
+/// Check whether a path is a parse cache sidecar written next to a log
+fn is_cache_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == CACHE_EXTENSION)
+}
+
+/// List log files in a directory
+///
+/// Without a pattern only the top level of `dir` is scanned for `.log`
+/// and `.log.gz` files. With a glob pattern (e.g. `**/*.log`) the tree
+/// is searched recursively and paths relative to `dir` are matched.
+/// Parse cache sidecars (`.cache`) are never returned, even when a
+/// pattern like `**/*.log*` would match them. Results are sorted by path.
+pub fn find_log_files(dir: &Path, pattern: Option<&str>) -> ParseResult<Vec<PathBuf>> {
+    if let Some(pattern) = pattern {
+        let mut files = glob::find_matching_files(dir, pattern)?;
+        files.retain(|path| !is_cache_file(path));
+        return Ok(files);
+    }
+
+    let mut log_files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_log_file(path))
+        .collect();
+
+    log_files.sort();
+    Ok(log_files)
+}
+
+/// Remove entries with the same timestamp, type, and message
+///
+/// Overlapping inputs (a rotated file and its tail) repeat entries; the
+/// first occurrence of each is kept and order is otherwise preserved.
+///
+/// Demonstrates:
+/// - HashSet::insert as a combined "seen before?" check
+/// - Vec::retain for in-place filtering
+pub fn dedupe_entries(mut entries: Vec<LogEntry>) -> Vec<LogEntry> {
+    let mut seen = HashSet::new();
+    entries.retain(|e| seen.insert((e.timestamp, e.entry_type, e.message.clone())));
+    entries
+}
+
+/// Parse log entries from any buffered reader
+///
+/// Demonstrates:
+/// - Generics: Works with files, in-memory buffers, or any BufRead
+/// - Resilient parsing: Malformed lines are reported and skipped
+pub fn parse_log_reader<R: BufRead>(reader: R) -> ParseResult<Vec<LogEntry>> {
+    parse_log_reader_with_format(reader, LogFormat::Bracket, &ParseOptions::default())
+}
+
+/// Parse log entries in the given format from any buffered reader
+pub fn parse_log_reader_with_format<R: BufRead>(
+    reader: R,
+    format: LogFormat,
+    options: &ParseOptions,
+) -> ParseResult<Vec<LogEntry>> {
+    parse_lines_from(reader, 1, format, options, warn_malformed)
+}
+
+/// Report a malformed line and continue (resilient parsing)
+fn warn_malformed(line_num: usize, e: ParseError) {
+    log_warn!("Failed to parse line {}: {}", line_num, e);
+}
+
+/// Parse every bracket-format line from a reader, handing failures to a callback
+///
+/// Demonstrates:
+/// - Closures: FnMut lets callers decide how to handle bad lines
+/// - Iterators: enumerate() provides 1-based line numbers
+#[cfg(test)]
+fn parse_lines<R, F>(reader: R, on_error: F) -> ParseResult<Vec<LogEntry>>
+where
+    R: BufRead,
+    F: FnMut(usize, ParseError),
+{
+    parse_lines_from(reader, 1, LogFormat::Bracket, &ParseOptions::default(), on_error)
+}
+
+/// Parse lines whose numbering starts at `first_line`
+///
+/// I/O errors abort parsing; per-line parse errors go to `on_error`.
+///
+/// Demonstrates:
+/// - Driving an iterator manually so its state can be inspected mid-loop
+fn parse_lines_from<R, F>(
+    reader: R,
+    first_line: usize,
+    format: LogFormat,
+    options: &ParseOptions,
+    on_error: F,
+) -> ParseResult<Vec<LogEntry>>
+where
+    R: BufRead,
+    F: FnMut(usize, ParseError),
+{
+    let stream = LogEntryStream::new(reader, first_line, format).with_options(options.clone());
+    collect_stream(stream, on_error)
+}
+
+/// Drain a stream into a Vec, aborting on I/O errors
+fn collect_stream<R, F>(
+    mut stream: LogEntryStream<R>,
+    mut on_error: F,
+) -> ParseResult<Vec<LogEntry>>
+where
+    R: BufRead,
+    F: FnMut(usize, ParseError),
+{
+    let mut entries = Vec::new();
+
+    while let Some(result) = stream.next() {
+        match result {
+            Ok(entry) => entries.push(entry),
+            Err(ParseError::Io(e)) => return Err(ParseError::Io(e)),
+            Err(e) => on_error(stream.line_num(), e),
+        }
     }
 
     Ok(entries)
 }
 
+/// Iterator that parses entries lazily from a buffered reader
+///
+/// In the bracket format, lines that don't start with a `[TIMESTAMP]`
+/// header are treated as continuations (stack traces, multi-line
+/// decisions) and appended to the previous entry's message. They are only
+/// errors if no entry exists yet. Because of this, an entry is yielded
+/// only once the next header line (or end of input) is seen.
+///
+/// Demonstrates:
+/// - Implementing Iterator with one-entry lookahead
+/// - Generics: Works with files, in-memory buffers, or any BufRead
+pub struct LogEntryStream<R> {
+    reader: R,
+    format: LogFormat,
+    options: ParseOptions,
+
+    /// Buffer reused for each line read
+    line: String,
+
+    /// Number of the most recently read line
+    line_num: usize,
+
+    /// Bytes consumed so far, when offsets are being tracked
+    offset: Option<u64>,
+
+    /// Entry still collecting continuation lines
+    pending: Option<LogEntry>,
+}
+
+impl<R: BufRead> LogEntryStream<R> {
+    /// Create a stream whose first line is numbered `first_line`
+    ///
+    /// Entries carry no `byte_offset` unless `with_byte_offsets` is used,
+    /// and lines are parsed with default options unless `with_options` is.
+    pub fn new(reader: R, first_line: usize, format: LogFormat) -> Self {
+        Self {
+            reader,
+            format,
+            options: ParseOptions::default(),
+            line: String::new(),
+            line_num: first_line.saturating_sub(1),
+            offset: None,
+            pending: None,
+        }
+    }
+
+    /// Record each entry's `byte_offset`, counting from `start` for the
+    /// reader's first byte
+    pub fn with_byte_offsets(mut self, start: u64) -> Self {
+        self.offset = Some(start);
+        self
+    }
+
+    /// Parse lines with the given options
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 1-based number of the line most recently read
+    pub fn line_num(&self) -> usize {
+        self.line_num
+    }
+}
+
+impl<R: BufRead> Iterator for LogEntryStream<R> {
+    type Item = ParseResult<LogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line_start = self.offset;
+
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return self.pending.take().map(Ok),
+                Ok(read) => {
+                    if let Some(offset) = self.offset.as_mut() {
+                        *offset += read as u64;
+                    }
+                }
+                Err(e) => return Some(Err(e.into())),
+            }
+            self.line_num += 1;
+
+            // Drop the terminator as BufRead::lines does
+            let raw = match self.line.strip_suffix('\n') {
+                Some(line) => line.strip_suffix('\r').unwrap_or(line),
+                None => &self.line,
+            };
+            let line = self.options.clean_line(raw);
+
+            // Skip empty lines
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // Continuation of the previous entry
+            if self.format == LogFormat::Bracket && !starts_bracket_entry(&line, &self.options) {
+                if let Some(previous) = self.pending.as_mut() {
+                    previous.message.push('\n');
+                    previous.message.push_str(line.trim_end());
+                    continue;
+                }
+            }
+
+            let parsed = match self.format {
+                LogFormat::Bracket => parse_log_entry(&line, self.line_num, &self.options),
+                LogFormat::Logfmt => {
+                    logfmt::parse_logfmt_entry(&line, self.line_num, &self.options)
+                }
+                LogFormat::Json => json::parse_json_entry(&line, self.line_num, &self.options),
+            };
+
+            match parsed {
+                Ok(entry) => {
+                    let entry = LogEntry {
+                        byte_offset: line_start,
+                        ..entry
+                    };
+                    if let Some(previous) = self.pending.replace(entry) {
+                        return Some(Ok(previous));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
 /// Parse a single log line into a LogEntry
 ///
 /// Demonstrates:
 /// - Borrowing: Takes &str, doesn't need ownership of line
 /// - Error handling: Returns Result
 /// - String handling: Parses and creates owned Strings
-fn parse_log_entry(line: &str) -> ParseResult<LogEntry> {
+///
+/// `line_num` is the 1-based position used in error reports. With
+/// `ParseOptions::with_strict_levels`, unrecognized levels are rejected.
+fn parse_log_entry(line: &str, line_num: usize, options: &ParseOptions) -> ParseResult<LogEntry> {
     // Simple log format: [TIMESTAMP] LEVEL: MESSAGE
     // Example: [2025-10-18T14:30:45Z] INFO: Starting analysis
+    //
+    // Some producers put the level first: LEVEL [TIMESTAMP] MESSAGE
+    // Example: INFO [2025-10-18T14:30:45Z] Starting analysis
+
+    if let Some((level_str, timestamp, message)) = split_level_first(line, options) {
+        let entry_type = parse_entry_type(level_str);
+
+        if options.strict_levels && entry_type == EntryType::Unknown {
+            return Err(ParseError::MalformedEntry {
+                line: line_num,
+                details: format!("{} '{}'", UNRECOGNIZED_LEVEL, level_str),
+            });
+        }
+
+        return Ok(bracket_entry(timestamp, entry_type, message.to_string(), options));
+    }
 
     if !line.starts_with('[') {
         return Err(ParseError::MalformedEntry {
-            line: 0,
-            details: "Line doesn't start with '['".to_string(),
+            line: line_num,
+            details: NO_OPENING_BRACKET.to_string(),
         });
     }
 
     // Find timestamp end
     let timestamp_end = line.find(']')
         .ok_or_else(|| ParseError::MalformedEntry {
-            line: 0,
-            details: "No closing ']' for timestamp".to_string(),
+            line: line_num,
+            details: NO_CLOSING_BRACKET.to_string(),
         })?;
 
     // Extract and parse timestamp
     let timestamp_str = &line[1..timestamp_end];
-    let timestamp = parse_timestamp(timestamp_str)?;
+    let timestamp = options.parse_timestamp(timestamp_str)?;
 
     // Rest of line after timestamp
     let rest = &line[timestamp_end + 1..].trim();
@@ -84,59 +783,333 @@ fn parse_log_entry(line: &str) -> ParseResult<LogEntry> {
         let level_str = &rest[..colon_pos].trim();
         let msg = rest[colon_pos + 1..].trim().to_string();
         let entry_type = parse_entry_type(level_str);
+
+        if options.strict_levels && entry_type == EntryType::Unknown {
+            return Err(ParseError::MalformedEntry {
+                line: line_num,
+                details: format!("{} '{}'", UNRECOGNIZED_LEVEL, level_str),
+            });
+        }
+
         (entry_type, msg)
     } else {
+        if options.strict_levels {
+            return Err(ParseError::MalformedEntry {
+                line: line_num,
+                details: MISSING_LEVEL.to_string(),
+            });
+        }
+
         (EntryType::Unknown, rest.to_string())
     };
 
-    Ok(LogEntry {
+    Ok(bracket_entry(timestamp, entry_type, message, options))
+}
+
+/// Whether a bracket-format line starts a new entry rather than
+/// continuing the previous one
+fn starts_bracket_entry(line: &str, options: &ParseOptions) -> bool {
+    line.starts_with('[') || split_level_first(line, options).is_some()
+}
+
+/// Split a level-first line (`LEVEL [TIMESTAMP] MESSAGE`) into its level,
+/// timestamp, and message
+///
+/// Only a single alphabetic token followed by a bracketed timestamp that
+/// parses counts, so continuation text such as `see [docs]` is left alone.
+fn split_level_first<'a>(
+    line: &'a str,
+    options: &ParseOptions,
+) -> Option<(&'a str, DateTime<Utc>, &'a str)> {
+    let (level, rest) = line.split_once(char::is_whitespace)?;
+    if level.is_empty() || !level.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let (timestamp, message) = rest.trim_start().strip_prefix('[')?.split_once(']')?;
+    let timestamp = options.parse_timestamp(timestamp).ok()?;
+
+    Some((level, timestamp, message.trim()))
+}
+
+/// Build an entry from parsed bracket-format parts, extracting agent
+/// metadata, `key=value` fields, and the fields of a trailing JSON object
+/// from the message
+///
+/// A `key=value` field wins over a JSON field with the same key.
+fn bracket_entry(
+    timestamp: DateTime<Utc>,
+    entry_type: EntryType,
+    message: String,
+    options: &ParseOptions,
+) -> LogEntry {
+    let (agent_name, duration_ms) = extract_agent_metadata(&message, options);
+    let mut fields = json::extract_embedded_object(&message);
+    fields.extend(extract_fields(&message));
+
+    LogEntry {
         timestamp,
         entry_type,
         message,
-        agent_name: None,  // Could be extracted from message
-        duration_ms: None, // Could be extracted from message
-    })
+        agent_name,
+        duration_ms,
+        fields,
+        byte_offset: None,
+    }
 }
 
-/// Parse timestamp string into DateTime
+/// Extract structured `key=value` fields from a log message
+///
+/// Values may be double-quoted to include spaces (`model="claude sonnet"`),
+/// with `\"` escaping a quote inside. The message itself is left untouched.
 ///
 /// Demonstrates:
-/// - Borrowing: Takes &str
-/// - Error handling: Maps parse errors to our error type
-fn parse_timestamp(s: &str) -> ParseResult<DateTime<Utc>> {
-    use chrono::NaiveDateTime;
+/// - Regex capture iteration
+/// - Building an owned HashMap from borrowed input
+fn extract_fields(message: &str) -> HashMap<String, String> {
+    static FIELD_RE: OnceLock<Regex> = OnceLock::new();
 
-    // Try standard ISO 8601 format first
-    if let Ok(dt) = s.parse::<DateTime<Utc>>() {
-        return Ok(dt);
+    let field_re = FIELD_RE.get_or_init(|| {
+        Regex::new(r#"(?:^|\s)([A-Za-z_][\w.-]*)=("(?:[^"\\]|\\.)*"|[^\s"]\S*)"#).unwrap()
+    });
+
+    field_re
+        .captures_iter(message)
+        .map(|caps| (caps[1].to_string(), unquote(&caps[2])))
+        .collect()
+}
+
+/// Strip surrounding quotes and unescape `\"` and `\\`
+fn unquote(value: &str) -> String {
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(inner) => inner.replace("\\\"", "\"").replace("\\\\", "\\"),
+        None => value.to_string(),
     }
+}
 
-    // Try format with microseconds without timezone (e.g., "2025-10-18T11:25:37.950859")
-    // Parse as naive datetime and assume UTC
-    if let Ok(naive_dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f") {
-        return Ok(DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc));
+/// Regex fragment matching a human-readable duration such as `1.2s` or `2m30s`
+const HUMAN_DURATION_PATTERN: &str = r"(?:\d+(?:\.\d+)?(?:ms|s|m))+";
+
+/// Parse a human-readable duration into milliseconds
+///
+/// Accepts one or more `<number><unit>` components with units `ms`, `s`,
+/// and `m`, so `500ms`, `1.2s`, and `2m30s` are all valid. Fractional
+/// milliseconds are rounded. A bare number without a unit is rejected.
+///
+/// Demonstrates:
+/// - Consuming a &str piecewise with find() and slicing
+pub fn parse_human_duration(s: &str) -> Option<u64> {
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return None;
     }
 
-    // Try format with Z timezone
-    if let Ok(dt) = DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.fZ") {
-        return Ok(dt.with_timezone(&Utc));
+    let mut total_ms = 0.0;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let value: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let factor = match &rest[..unit_len] {
+            "ms" => 1.0,
+            "s" => 1_000.0,
+            "m" => 60_000.0,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+
+        total_ms += value * factor;
     }
 
-    Err(ParseError::InvalidTimestamp(s.to_string()))
+    Some(total_ms.round() as u64)
 }
 
-/// Parse entry type from level string
+/// Extract agent name and duration from a log message
+///
+/// Recognizes:
+/// - `<name> completed in <duration>` (name must lead the message)
+/// - `agent=<name>`
+/// - `duration=<duration>`
+///
+/// Durations may use any form accepted by `parse_human_duration`.
+///
+/// Demonstrates:
+/// - Lazily compiled regexes with OnceLock
+/// - Returning a tuple of Options
+fn extract_agent_metadata(
+    message: &str,
+    options: &ParseOptions,
+) -> (Option<String>, Option<u64>) {
+    static COMPLETED_RE: OnceLock<Regex> = OnceLock::new();
+    static AGENT_RE: OnceLock<Regex> = OnceLock::new();
+    static DURATION_RE: OnceLock<Regex> = OnceLock::new();
+
+    let completed_re = COMPLETED_RE.get_or_init(|| {
+        Regex::new(&format!(r"^([\w.-]+) completed in ({})\b", HUMAN_DURATION_PATTERN)).unwrap()
+    });
+    let agent_re = AGENT_RE
+        .get_or_init(|| Regex::new(r"(?:^|\s)agent=((?i:agent:)?[\w.-]+)").unwrap());
+    let duration_re = DURATION_RE.get_or_init(|| {
+        Regex::new(&format!(r"(?:^|\s)duration=({})\b", HUMAN_DURATION_PATTERN)).unwrap()
+    });
+
+    if let Some(caps) = completed_re.captures(message) {
+        let duration = parse_human_duration(&caps[2]);
+        return (Some(options.canonical_agent_name(&caps[1])), duration);
+    }
+
+    let agent_name = agent_re
+        .captures(message)
+        .map(|caps| options.canonical_agent_name(&caps[1]));
+    let duration_ms = duration_re
+        .captures(message)
+        .and_then(|caps| parse_human_duration(&caps[1]));
+
+    (agent_name, duration_ms)
+}
+
+/// Prefix some producers put before agent names (`agent:architect`)
+const AGENT_NAME_PREFIX: &str = "agent:";
+
+/// Canonicalize an agent name: trimmed, lowercase, without `agent:`
+///
+/// `Architect`, ` architect ` and `agent:architect` all become `architect`.
+pub fn normalize_agent_name(raw: &str) -> String {
+    let name = raw.trim().to_lowercase();
+    match name.strip_prefix(AGENT_NAME_PREFIX) {
+        Some(rest) => rest.trim().to_string(),
+        None => name,
+    }
+}
+
+/// Remove ANSI escape sequences (colors, cursor movement, titles)
+///
+/// Handles CSI sequences (`ESC [ ... final`), OSC sequences (`ESC ] ...`
+/// ended by BEL or `ESC \\`), and two-character escapes.
+///
+/// Demonstrates:
+/// - A small state machine over a Peekable char iterator
+pub fn strip_ansi(message: &str) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI: parameter and intermediate bytes, then one final byte
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: runs until BEL or the ESC \\ string terminator
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Any other escape is two characters long
+            _ => {}
+        }
+    }
+
+    out
+}
+
+/// Check that a chrono strftime format string is well-formed
+pub fn validate_timestamp_format(format: &str) -> ParseResult<()> {
+    use chrono::format::{Item, StrftimeItems};
+
+    if format.is_empty() || StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(ParseError::InvalidTimestampFormat(format.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Parse timestamp string into DateTime using the built-in formats
+///
+/// `ParseOptions::parse_timestamp` also tries a custom format first.
+///
+/// Demonstrates:
+/// - Borrowing: Takes &str
+/// - Error handling: Maps parse errors to our error type
+pub fn parse_timestamp(s: &str) -> ParseResult<DateTime<Utc>> {
+    parse_timestamp_with_format(s, None)
+}
+
+/// Parse a timestamp, trying a custom strftime format first
+///
+/// Custom formats without an offset are interpreted as UTC. If the custom
+/// format doesn't match, the built-in formats are tried as usual.
+pub fn parse_timestamp_with_format(s: &str, format: Option<&str>) -> ParseResult<DateTime<Utc>> {
+    use chrono::NaiveDateTime;
+
+    if let Some(format) = format {
+        if let Ok(dt) = DateTime::parse_from_str(s, format) {
+            return Ok(dt.with_timezone(&Utc));
+        }
+        if let Ok(naive_dt) = NaiveDateTime::parse_from_str(s, format) {
+            return Ok(DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc));
+        }
+    }
+
+    // Try standard ISO 8601 format first
+    if let Ok(dt) = s.parse::<DateTime<Utc>>() {
+        return Ok(dt);
+    }
+
+    // Try RFC 3339 with an explicit UTC offset (e.g., "2025-10-18T14:30:45+02:00")
+    // and normalize to UTC
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    // Try format with microseconds without timezone (e.g., "2025-10-18T11:25:37.950859")
+    // Parse as naive datetime and assume UTC
+    if let Ok(naive_dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Ok(DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc));
+    }
+
+    // Try format with Z timezone
+    if let Ok(dt) = DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.fZ") {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    Err(ParseError::InvalidTimestamp(s.to_string()))
+}
+
+/// Parse entry type from level string
 ///
 /// Demonstrates:
 /// - Borrowing: Takes &str
 /// - Pattern matching: Match string to enum variant
-fn parse_entry_type(s: &str) -> EntryType {
+pub fn parse_entry_type(s: &str) -> EntryType {
     match s.to_uppercase().as_str() {
         "INFO" => EntryType::Info,
         "WARN" | "WARNING" => EntryType::Warning,
         "ERROR" => EntryType::Error,
         "AGENT" => EntryType::AgentInvocation,
         "DECISION" => EntryType::Decision,
+        "DEBUG" | "DBG" => EntryType::Debug,
+        "TRACE" => EntryType::Trace,
         _ => EntryType::Unknown,
     }
 }
@@ -144,6 +1117,7 @@ fn parse_entry_type(s: &str) -> EntryType {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Cursor;
 
     #[test]
     fn test_parse_entry_type() {
@@ -153,16 +1127,55 @@ mod tests {
         assert_eq!(parse_entry_type("unknown"), EntryType::Unknown);
     }
 
+    #[test]
+    fn test_parse_debug_and_trace_levels() {
+        let options = ParseOptions::default();
+        assert_eq!(parse_entry_type("DEBUG"), EntryType::Debug);
+        assert_eq!(parse_entry_type("debug"), EntryType::Debug);
+        assert_eq!(parse_entry_type("DBG"), EntryType::Debug);
+        assert_eq!(parse_entry_type("TRACE"), EntryType::Trace);
+        assert_eq!(parse_entry_type("trace"), EntryType::Trace);
+
+        let line = "[2025-10-18T14:30:45Z] DBG: cache miss";
+        let entry = parse_log_entry(line, 1, &options).unwrap();
+        assert_eq!(entry.entry_type, EntryType::Debug);
+        assert_eq!(entry.message, "cache miss");
+
+        let line = "[2025-10-18T14:30:45Z] TRACE: enter parse";
+        let entry = parse_log_entry(line, 1, &options).unwrap();
+        assert_eq!(entry.entry_type, EntryType::Trace);
+    }
+
     #[test]
     fn test_parse_timestamp() {
         let result = parse_timestamp("2025-10-18T14:30:45Z");
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_timestamp_naive_assumes_utc() {
+        let dt = parse_timestamp("2025-10-18T11:25:37.950859").unwrap();
+        assert_eq!(dt.to_rfc3339(), "2025-10-18T11:25:37.950859+00:00");
+    }
+
+    #[test]
+    fn test_parse_timestamp_positive_offset() {
+        let dt = parse_timestamp("2025-10-18T14:30:45+02:00").unwrap();
+        assert_eq!(dt, parse_timestamp("2025-10-18T12:30:45Z").unwrap());
+    }
+
+    #[test]
+    fn test_parse_timestamp_negative_offset() {
+        // Crosses midnight into the next UTC day
+        let dt = parse_timestamp("2025-10-18T22:15:00-05:30").unwrap();
+        assert_eq!(dt, parse_timestamp("2025-10-19T03:45:00Z").unwrap());
+    }
+
     #[test]
     fn test_parse_log_entry() {
+        let options = ParseOptions::default();
         let line = "[2025-10-18T14:30:45Z] INFO: Test message";
-        let result = parse_log_entry(line);
+        let result = parse_log_entry(line, 1, &options);
 
         assert!(result.is_ok());
         let entry = result.unwrap();
@@ -170,10 +1183,765 @@ mod tests {
         assert_eq!(entry.message, "Test message");
     }
 
+    #[test]
+    fn test_display_round_trips_through_parse_log_entry() {
+        let options = ParseOptions::default();
+        let lines = [
+            "[2025-10-18T14:30:45Z] INFO: Starting analysis",
+            "[2025-10-18T14:30:45.950859Z] AGENT: Invoked agent: architect duration=1.5s",
+            "[2025-10-18T16:30:45+02:00] ERROR: status=500 path=\"/api/v1\"",
+            "[2025-10-18T14:30:45Z] Unlabelled message",
+            "WARN [2025-10-18T14:30:45Z] level first",
+        ];
+
+        for line in lines {
+            let entry = parse_log_entry(line, 1, &options).unwrap();
+            let reparsed = parse_log_entry(&entry.to_string(), 1, &options).unwrap();
+
+            assert_eq!(reparsed.timestamp, entry.timestamp, "{}", line);
+            assert_eq!(reparsed.entry_type, entry.entry_type, "{}", line);
+            assert_eq!(reparsed.message, entry.message, "{}", line);
+            assert_eq!(reparsed.agent_name, entry.agent_name, "{}", line);
+            assert_eq!(reparsed.duration_ms, entry.duration_ms, "{}", line);
+            assert_eq!(reparsed.fields, entry.fields, "{}", line);
+            assert_eq!(reparsed.to_string(), entry.to_string());
+        }
+
+        let entry = parse_log_entry("[2025-10-18T16:30:45+02:00] DBG: x", 1, &options).unwrap();
+        assert_eq!(entry.to_string(), "[2025-10-18T14:30:45Z] DEBUG: x");
+    }
+
+    #[test]
+    fn test_level_first_matches_bracket_first() {
+        let options = ParseOptions::default();
+        let bracket_first = "[2025-10-18T14:30:45Z] AGENT: architect completed in 1234ms task=plan";
+        let level_first = "AGENT [2025-10-18T14:30:45Z] architect completed in 1234ms task=plan";
+
+        let expected = parse_log_entry(bracket_first, 1, &options).unwrap();
+        let entry = parse_log_entry(level_first, 1, &options).unwrap();
+
+        assert_eq!(entry.timestamp, expected.timestamp);
+        assert_eq!(entry.entry_type, EntryType::AgentInvocation);
+        assert_eq!(entry.message, expected.message);
+        assert_eq!(entry.agent_name.as_deref(), Some("architect"));
+        assert_eq!(entry.duration_ms, Some(1234));
+        assert_eq!(entry.fields, expected.fields);
+    }
+
+    #[test]
+    fn test_level_first_levels() {
+        let options = ParseOptions::default();
+        let line = "warn  [2025-10-18T14:30:45Z]   disk nearly full";
+        let warn = parse_log_entry(line, 1, &options).unwrap();
+        assert_eq!(warn.entry_type, EntryType::Warning);
+        assert_eq!(warn.message, "disk nearly full");
+
+        let fatal = "FATAL [2025-10-18T14:30:45Z] out of memory";
+        let strict = ParseOptions::new().with_strict_levels(true);
+        assert_eq!(parse_log_entry(fatal, 1, &options).unwrap().entry_type, EntryType::Unknown);
+        assert!(parse_log_entry(fatal, 1, &strict).is_err());
+
+        // A level token needs a parseable bracketed timestamp after it
+        assert!(split_level_first("INFO [not a time] message", &options).is_none());
+        assert!(split_level_first("at main (main.rs:5)", &options).is_none());
+        assert!(parse_log_entry("INFO message", 1, &options).is_err());
+    }
+
+    #[test]
+    fn test_level_first_lines_start_new_entries() {
+        let options = ParseOptions::default();
+        let input = "INFO [2025-10-18T14:30:45Z] Traceback follows\n    at main (main.rs:5)\n\
+                     [2025-10-18T14:30:46Z] WARNING: bracket first\n\
+                     ERROR [2025-10-18T14:30:47Z] level first\n";
+
+        let mut errors = Vec::new();
+        let entries = parse_lines(input.as_bytes(), |line_num, e| errors.push((line_num, e))).unwrap();
+
+        assert!(errors.is_empty());
+        let types: Vec<EntryType> = entries.iter().map(|e| e.entry_type).collect();
+        assert_eq!(types, vec![EntryType::Info, EntryType::Warning, EntryType::Error]);
+        assert_eq!(entries[0].message, "Traceback follows\n    at main (main.rs:5)");
+
+        let from_bytes = parse_log_bytes(input.as_bytes(), &options);
+        assert_eq!(from_bytes.len(), 3);
+        assert_eq!(from_bytes[2].message, "level first");
+    }
+
+    #[test]
+    fn test_extract_completed_in() {
+        let options = ParseOptions::default();
+        let (agent, duration) = extract_agent_metadata("architect completed in 1234ms", &options);
+        assert_eq!(agent.as_deref(), Some("architect"));
+        assert_eq!(duration, Some(1234));
+    }
+
+    #[test]
+    fn test_extract_key_value_metadata() {
+        let options = ParseOptions::default();
+        let line = "Finished task agent=builder duration=250ms";
+        let (agent, duration) = extract_agent_metadata(line, &options);
+        assert_eq!(agent.as_deref(), Some("builder"));
+        assert_eq!(duration, Some(250));
+
+        let (agent, duration) = extract_agent_metadata("Invoking agent=reviewer", &options);
+        assert_eq!(agent.as_deref(), Some("reviewer"));
+        assert_eq!(duration, None);
+
+        let (agent, duration) = extract_agent_metadata("Step took duration=42ms", &options);
+        assert_eq!(agent, None);
+        assert_eq!(duration, Some(42));
+    }
+
+    #[test]
+    fn test_parse_human_duration_units() {
+        assert_eq!(parse_human_duration("500ms"), Some(500));
+        assert_eq!(parse_human_duration("1.2s"), Some(1_200));
+        assert_eq!(parse_human_duration("3s"), Some(3_000));
+        assert_eq!(parse_human_duration("2m"), Some(120_000));
+        assert_eq!(parse_human_duration("2m30s"), Some(150_000));
+        assert_eq!(parse_human_duration("1m1s5ms"), Some(61_005));
+    }
+
+    #[test]
+    fn test_parse_human_duration_rejects_garbage() {
+        assert_eq!(parse_human_duration("soon"), None);
+        assert_eq!(parse_human_duration("1234"), None);
+        assert_eq!(parse_human_duration("5h"), None);
+        assert_eq!(parse_human_duration("1..2s"), None);
+        assert_eq!(parse_human_duration(""), None);
+    }
+
+    #[test]
+    fn test_extract_human_durations() {
+        let options = ParseOptions::default();
+        let (agent, duration) = extract_agent_metadata("architect completed in 2m30s", &options);
+        assert_eq!(agent.as_deref(), Some("architect"));
+        assert_eq!(duration, Some(150_000));
+
+        let line = "Finished agent=builder duration=1.2s.";
+        let (_, duration) = extract_agent_metadata(line, &options);
+        assert_eq!(duration, Some(1_200));
+
+        // A plain number carries no unit and isn't treated as a duration
+        assert_eq!(extract_agent_metadata("Step took duration=42", &options).1, None);
+    }
+
+    #[test]
+    fn test_normalize_agent_name_variants_collapse() {
+        let options = ParseOptions::default();
+        for raw in ["architect", "Architect", "agent:architect", " AGENT: Architect "] {
+            assert_eq!(normalize_agent_name(raw), "architect", "raw = {:?}", raw);
+        }
+
+        let (agent, _) = extract_agent_metadata("Invoking agent=Architect", &options);
+        assert_eq!(agent.as_deref(), Some("architect"));
+
+        let (agent, _) = extract_agent_metadata("Invoking agent=AGENT:architect", &options);
+        assert_eq!(agent.as_deref(), Some("architect"));
+    }
+
+    #[test]
+    fn test_agent_aliases_apply_after_normalizing() {
+        let aliases = HashMap::from([
+            ("arch".to_string(), "architect".to_string()),
+            ("bldr".to_string(), "builder".to_string()),
+        ]);
+
+        let options = ParseOptions::new().with_agent_aliases(&aliases);
+        let resolve = |raw: &str| options.canonical_agent_name(raw);
+
+        assert_eq!(resolve("arch"), "architect");
+        assert_eq!(resolve("agent:ARCH"), "architect");
+        assert_eq!(resolve("Architect"), "architect");
+        assert_eq!(resolve("reviewer"), "reviewer");
+        assert_eq!(ParseOptions::default().canonical_agent_name("arch"), "arch");
+
+        let line = "[2025-10-18T14:30:45Z] AGENT: ARCH completed in 5ms";
+        let entry = parse_log_entry(line, 1, &options).unwrap();
+        assert_eq!(entry.agent_name.as_deref(), Some("architect"));
+    }
+
+    #[test]
+    fn test_extract_no_metadata() {
+        let options = ParseOptions::default();
+        assert_eq!(extract_agent_metadata("Starting analysis", &options), (None, None));
+        // The word "agent" in prose must not be mistaken for metadata
+        let prose = "The agent completed in record time, agent selection done";
+        assert_eq!(extract_agent_metadata(prose, &options), (None, None));
+    }
+
+    #[test]
+    fn test_extract_fields_multiple() {
+        let fields = extract_fields("Request done session_id=abc tokens=1500 model=claude");
+
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields["session_id"], "abc");
+        assert_eq!(fields["tokens"], "1500");
+        assert_eq!(fields["model"], "claude");
+    }
+
+    #[test]
+    fn test_extract_fields_quoted_values() {
+        let fields = extract_fields(r#"note="two words here" path="C:\\logs" quote="say \"hi\"" n=1"#);
+
+        assert_eq!(fields["note"], "two words here");
+        assert_eq!(fields["path"], "C:\\logs");
+        assert_eq!(fields["quote"], "say \"hi\"");
+        assert_eq!(fields["n"], "1");
+    }
+
+    #[test]
+    fn test_extract_fields_none() {
+        assert!(extract_fields("Plain message with no pairs").is_empty());
+        assert!(extract_fields("a == b and x= y").is_empty());
+    }
+
+    #[test]
+    fn test_parse_log_entry_keeps_message_with_fields() {
+        let options = ParseOptions::default();
+        let line = "[2025-10-18T14:30:45Z] INFO: Done session_id=abc tokens=1500";
+        let entry = parse_log_entry(line, 1, &options).unwrap();
+
+        assert_eq!(entry.message, "Done session_id=abc tokens=1500");
+        assert_eq!(entry.fields["session_id"], "abc");
+        assert_eq!(entry.fields["tokens"], "1500");
+
+        let json = serde_json::to_string(&entry).unwrap();
+        let roundtrip: LogEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(roundtrip.fields, entry.fields);
+    }
+
+    #[test]
+    fn test_embedded_json_object_merges_into_fields() {
+        let options = ParseOptions::default();
+        let message = r#"result session_id=abc status=done {"status":"ok","tokens":1500}"#;
+        let line = format!("[2025-10-18T14:30:45Z] AGENT: {}", message);
+        let entry = parse_log_entry(&line, 1, &options).unwrap();
+
+        assert_eq!(entry.message, message);
+        assert_eq!(entry.fields.len(), 3);
+        assert_eq!(entry.fields["session_id"], "abc");
+        assert_eq!(entry.fields["tokens"], "1500");
+        assert_eq!(entry.fields["status"], "done");
+    }
+
+    #[test]
+    fn test_malformed_embedded_json_is_ignored() {
+        let options = ParseOptions::default();
+        let line = r#"[2025-10-18T14:30:45Z] AGENT: result n=1 {"status":"ok","tokens":}"#;
+        let entry = parse_log_entry(line, 1, &options).unwrap();
+
+        assert_eq!(entry.message, r#"result n=1 {"status":"ok","tokens":}"#);
+        assert_eq!(entry.fields, HashMap::from([("n".to_string(), "1".to_string())]));
+    }
+
+    #[test]
+    fn test_parse_log_entry_with_agent() {
+        let options = ParseOptions::default();
+        let line = "[2025-10-18T14:30:45Z] AGENT: architect completed in 1234ms";
+        let entry = parse_log_entry(line, 1, &options).unwrap();
+
+        assert_eq!(entry.entry_type, EntryType::AgentInvocation);
+        assert_eq!(entry.agent_name.as_deref(), Some("architect"));
+        assert_eq!(entry.duration_ms, Some(1234));
+    }
+
+    #[test]
+    fn test_multiline_entry_continuation() {
+        let input = "[2025-10-18T14:30:45Z] INFO: Traceback follows\n    at parse (parser.rs:10)\n    at main (main.rs:5)\n[2025-10-18T14:30:46Z] ERROR: Next entry\n";
+
+        let mut errors = Vec::new();
+        let entries = parse_lines(input.as_bytes(), |line_num, e| errors.push((line_num, e))).unwrap();
+
+        assert!(errors.is_empty());
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].message,
+            "Traceback follows\n    at parse (parser.rs:10)\n    at main (main.rs:5)"
+        );
+        assert_eq!(entries[1].message, "Next entry");
+    }
+
+    #[test]
+    fn test_continuation_before_first_entry_is_error() {
+        let input = "orphan line\n[2025-10-18T14:30:45Z] INFO: First\n";
+
+        let mut errors = Vec::new();
+        let entries = parse_lines(input.as_bytes(), |line_num, e| errors.push((line_num, e))).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 1);
+    }
+
+    #[test]
+    fn test_parse_log_file_with_logfmt_format() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logfmt.log");
+        std::fs::write(
+            &path,
+            "time=2025-10-18T14:30:45Z level=info msg=\"starting up\" agent=architect\n\
+             time=2025-10-18T14:30:46Z level=error msg=boom duration=15\n",
+        )
+        .unwrap();
+
+        let entries = parse_log_file_with_format(&path, LogFormat::Logfmt, &options).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].message, "starting up");
+        assert_eq!(entries[0].agent_name.as_deref(), Some("architect"));
+        assert_eq!(entries[1].entry_type, EntryType::Error);
+        assert_eq!(entries[1].duration_ms, Some(15));
+    }
+
+    #[test]
+    fn test_parse_log_file_with_json_format_skips_invalid_lines() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.ndjson");
+        std::fs::write(
+            &path,
+            "{\"ts\":\"2025-10-18T14:30:45Z\",\"level\":\"info\",\"msg\":\"starting\"}\n\
+             {broken\n\
+             {\"timestamp\":\"2025-10-18T14:30:46Z\",\"level\":\"error\",\"message\":\"boom\"}\n",
+        )
+        .unwrap();
+
+        let entries = parse_log_file_with_format(&path, LogFormat::Json, &options).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].message, "starting");
+        assert_eq!(entries[1].entry_type, EntryType::Error);
+        assert_eq!(entries[1].message, "boom");
+    }
+
+    #[test]
+    fn test_parse_gzip_matches_plain() {
+        let input = "[2025-10-18T14:30:45Z] INFO: Starting analysis\n\
+                     [2025-10-18T14:30:46Z] AGENT: architect completed in 1234ms\n\
+                     [2025-10-18T14:30:47Z] ERROR: Something failed\n";
+
+        let dir = tempfile::tempdir().unwrap();
+        let plain_path = dir.path().join("session.log");
+        let gz_path = dir.path().join("session.log.gz");
+        std::fs::write(&plain_path, input).unwrap();
+        std::fs::write(&gz_path, gzip_stored(input.as_bytes())).unwrap();
+
+        let plain = parse_log_file(&plain_path).unwrap();
+        let compressed = parse_log_file(&gz_path).unwrap();
+
+        assert_eq!(plain.len(), 3);
+        assert_eq!(compressed.len(), plain.len());
+        for (a, b) in plain.iter().zip(compressed.iter()) {
+            assert_eq!(a.timestamp, b.timestamp);
+            assert_eq!(a.entry_type, b.entry_type);
+            assert_eq!(a.message, b.message);
+            assert_eq!(a.agent_name, b.agent_name);
+            assert_eq!(a.duration_ms, b.duration_ms);
+        }
+
+        assert!(is_log_file(&plain_path));
+        assert!(is_log_file(&gz_path));
+    }
+
+    #[test]
+    fn test_byte_offsets_match_line_starts() {
+        let options = ParseOptions::default();
+        let input = "[2025-10-18T14:30:45Z] INFO: Starting analysis\r\n\
+                     [2025-10-18T14:30:46Z] ERROR: Something failed\n\
+                     \tat stack frame\n\
+                     [bad line\n\
+                     \n\
+                     [2025-10-18T14:30:47Z] AGENT: architect completed in 12ms\n";
+        let expected: Vec<Option<u64>> = ["14:30:45Z]", "14:30:46Z]", "14:30:47Z]"]
+            .iter()
+            .map(|time| input.find(&format!("[2025-10-18T{}", time)).map(|pos| pos as u64))
+            .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        std::fs::write(&path, input).unwrap();
+
+        let offsets = |entries: Vec<LogEntry>| -> Vec<Option<u64>> {
+            entries.iter().map(|e| e.byte_offset).collect()
+        };
+
+        assert_eq!(offsets(parse_log_file(&path).unwrap()), expected);
+        assert_eq!(offsets(parse_log_bytes(input.as_bytes(), &options)), expected);
+
+        let stream = LogEntryStream::new(Cursor::new(input), 1, LogFormat::Bracket)
+            .with_byte_offsets(100);
+        let shifted: Vec<_> = stream.filter_map(Result::ok).map(|e| e.byte_offset).collect();
+        assert_eq!(shifted[0], Some(100));
+        assert_eq!(shifted[1], expected[1].map(|o| o + 100));
+
+        // Readers that can't report offsets leave them unset
+        let from_reader = parse_log_reader(Cursor::new(input)).unwrap();
+        assert!(from_reader.iter().all(|e| e.byte_offset.is_none()));
+
+        let gz_path = dir.path().join("session.log.gz");
+        std::fs::write(&gz_path, gzip_stored(input.as_bytes())).unwrap();
+        assert!(parse_log_file(&gz_path).unwrap().iter().all(|e| e.byte_offset.is_none()));
+    }
+
+    #[test]
+    fn test_streaming_matches_parse_log_file() {
+        let options = ParseOptions::default();
+        let input = "[2025-10-18T14:30:45Z] INFO: Starting analysis\n\
+                     [2025-10-18T14:30:46Z] ERROR: Something failed\n\
+                     \tat stack frame\n\
+                     [bad line\n\
+                     \n\
+                     [2025-10-18T14:30:47Z] AGENT: architect completed in 12ms\n";
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        std::fs::write(&path, input).unwrap();
+
+        let streamed: Vec<_> = parse_log_entries_streaming(&path, &options).collect();
+        let entries: Vec<_> = streamed.iter().filter_map(|r| r.as_ref().ok()).collect();
+        let collected = parse_log_file(&path).unwrap();
+
+        assert_eq!(streamed.iter().filter(|r| r.is_err()).count(), 1);
+        assert_eq!(entries.len(), collected.len());
+        assert_eq!(entries.len(), 3);
+        for (a, b) in entries.iter().zip(collected.iter()) {
+            assert_eq!(a.timestamp, b.timestamp);
+            assert_eq!(a.message, b.message);
+        }
+        assert_eq!(entries[1].message, "Something failed\n\tat stack frame");
+    }
+
+    #[test]
+    fn test_streaming_missing_file_yields_error() {
+        let options = ParseOptions::default();
+        let path = Path::new("/nonexistent/session.log");
+        let mut stream = parse_log_entries_streaming(path, &options);
+        assert!(matches!(stream.next(), Some(Err(ParseError::FileNotFound(_)))));
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn test_find_log_files_flat_vs_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("nested");
+        std::fs::create_dir(&nested).unwrap();
+        std::fs::write(dir.path().join("top.log"), "").unwrap();
+        std::fs::write(nested.join("deep.log"), "").unwrap();
+
+        let flat = find_log_files(dir.path(), None).unwrap();
+        assert_eq!(flat, vec![dir.path().join("top.log")]);
+
+        let globbed = find_log_files(dir.path(), Some("**/*.log")).unwrap();
+        assert_eq!(globbed, vec![nested.join("deep.log"), dir.path().join("top.log")]);
+
+        // Cache sidecars are skipped even when the pattern matches them
+        std::fs::write(dir.path().join("top.log.cache"), "").unwrap();
+        let globbed = find_log_files(dir.path(), Some("**/*.log*")).unwrap();
+        assert_eq!(globbed, vec![nested.join("deep.log"), dir.path().join("top.log")]);
+    }
+
+    #[test]
+    fn test_parse_log_file_strict_under_budget() {
+        let options = ParseOptions::default();
+        let input = "[2025-10-18T14:30:45Z] INFO: ok\n\
+                     [not-a-timestamp] INFO: bad\n\
+                     [2025-10-18T14:30:46Z] INFO: ok again\n";
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        std::fs::write(&path, input).unwrap();
+
+        let entries = parse_log_file_strict(&path, 1, &options).unwrap();
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_log_file_strict_over_budget() {
+        let options = ParseOptions::default();
+        let input = "[bad-1] INFO: x\n[bad-2] INFO: y\n[bad-3] INFO: z\n";
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("corrupt.log");
+        std::fs::write(&path, input).unwrap();
+
+        match parse_log_file_strict(&path, 1, &options) {
+            Err(ParseError::ErrorBudgetExceeded { errors, max_errors }) => {
+                assert_eq!(errors, 2);
+                assert_eq!(max_errors, 1);
+            }
+            other => panic!("Expected ErrorBudgetExceeded, got {:?}", other),
+        }
+
+        // The lenient parser still succeeds on the same file
+        assert!(parse_log_file(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mmap_path_matches_buffered_path() {
+        let options = ParseOptions::default();
+        let input = "[2025-10-18T14:30:45Z] INFO: Starting analysis\r\n\
+                     [2025-10-18T14:30:46Z] ERROR: Something failed\n\
+                     \tat stack frame\n\
+                     [bad line\n\
+                     \n\
+                     [2025-10-18T14:30:47Z] AGENT: architect completed in 12ms";
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        std::fs::write(&path, input).unwrap();
+
+        let buffered = parse_log_file(&path).unwrap();
+        let mapped = parse_log_file_mmap(&path, &options).unwrap();
+        let from_bytes = parse_log_bytes(input.as_bytes(), &options);
+
+        assert_eq!(buffered.len(), 3);
+        for other in [&mapped, &from_bytes] {
+            assert_eq!(other.len(), buffered.len());
+            for (a, b) in buffered.iter().zip(other.iter()) {
+                assert_eq!(a.timestamp, b.timestamp);
+                assert_eq!(a.entry_type, b.entry_type);
+                assert_eq!(a.message, b.message);
+                assert_eq!(a.agent_name, b.agent_name);
+                assert_eq!(a.duration_ms, b.duration_ms);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_log_entry_bytes_rejects_invalid_utf8() {
+        let options = ParseOptions::default();
+        let result = parse_log_entry_bytes(b"[2025-10-18T14:30:45Z] INFO: \xff", 3, &options);
+        assert!(matches!(result, Err(ParseError::MalformedEntry { line: 3, .. })));
+    }
+
+    #[test]
+    fn test_validate_log_file_lists_failures() {
+        let options = ParseOptions::default();
+        let input = "[2025-10-18T14:30:45Z] INFO: ok\n\
+                     [not-a-timestamp] INFO: bad\n\
+                     [2025-10-18T14:30:46Z] INFO: ok again\n\
+                     [also bad\n";
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log");
+        std::fs::write(&path, input).unwrap();
+
+        let report = validate_log_file(&path, &options).unwrap();
+
+        assert!(!report.is_valid());
+        assert_eq!(report.valid_entries, 2);
+        let lines: Vec<usize> = report.failures.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![2, 4]);
+    }
+
+    #[test]
+    fn test_parse_timestamp_with_custom_format() {
+        let dt = parse_timestamp_with_format("18/10/2025 14:30:45", Some("%d/%m/%Y %H:%M:%S")).unwrap();
+        assert_eq!(dt, parse_timestamp("2025-10-18T14:30:45Z").unwrap());
+
+        // Built-in formats still work as fallbacks
+        assert!(parse_timestamp_with_format("2025-10-18T14:30:45Z", Some("%d/%m/%Y")).is_ok());
+        assert!(parse_timestamp_with_format("18/10/2025 14:30:45", None).is_err());
+    }
+
+    #[test]
+    fn test_validate_timestamp_format() {
+        assert!(validate_timestamp_format("%d/%m/%Y %H:%M:%S").is_ok());
+        assert!(matches!(
+            validate_timestamp_format("%Q"),
+            Err(ParseError::InvalidTimestampFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_file_with_custom_timestamp_format() {
+        let options = ParseOptions::new().with_timestamp_format("%d/%m/%Y %H:%M:%S").unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("custom.log");
+        std::fs::write(
+            &path,
+            "[18/10/2025 14:30:45] INFO: custom start\n\
+             [18/10/2025 14:31:00] ERROR: custom failure\n",
+        )
+        .unwrap();
+
+        let entries = parse_log_file_with_options(&path, &options).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].timestamp, parse_timestamp("2025-10-18T14:31:00Z").unwrap());
+        assert!(ParseOptions::new().with_timestamp_format("%Q").is_err());
+
+        // Without the options the custom timestamps are malformed
+        assert!(parse_log_file(&path).unwrap().is_empty());
+    }
+
+    /// Gzip a buffer in memory using uncompressed (stored) deflate blocks
+    fn gzip_stored(data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, 0, 0xff];
+
+        let len = data.len() as u16;
+        out.push(1); // BFINAL=1, BTYPE=00 (stored)
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(data);
+
+        out.extend_from_slice(&gzip::crc32(data).to_le_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out
+    }
+
     #[test]
     fn test_parse_malformed_entry() {
+        let options = ParseOptions::default();
         let line = "This is not a valid log line";
-        let result = parse_log_entry(line);
+        let result = parse_log_entry(line, 1, &options);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_malformed_entry_reports_line_number() {
+        let input = "[2025-10-18T14:30:45Z] INFO: First\n\
+                     [2025-10-18T14:30:46Z] INFO: Second\n\
+                     [2025-10-18T14:30:47Z INFO: missing bracket\n\
+                     [2025-10-18T14:30:47Z] INFO: Fourth\n";
+
+        let mut errors = Vec::new();
+        let entries = parse_lines(input.as_bytes(), |line_num, e| errors.push((line_num, e))).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(errors.len(), 1);
+
+        let (line_num, err) = &errors[0];
+        assert_eq!(*line_num, 3);
+        match err {
+            ParseError::MalformedEntry { line, .. } => assert_eq!(*line, 3),
+            other => panic!("Wrong error type: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_dedupe_entries_removes_only_exact_duplicates() {
+        let options = ParseOptions::default();
+        let lines = [
+            "[2025-10-18T14:30:45Z] INFO: Starting session",
+            "[2025-10-18T14:30:46Z] ERROR: Connection lost",
+            "[2025-10-18T14:30:45Z] INFO: Starting session",
+            "[2025-10-18T14:30:46Z] WARNING: Connection lost",
+            "[2025-10-18T14:30:47Z] ERROR: Connection lost",
+            "[2025-10-18T14:30:46Z] ERROR: Connection lost",
+        ];
+        let entries: Vec<_> = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| parse_log_entry(line, i + 1, &options).unwrap())
+            .collect();
+
+        let deduped = dedupe_entries(entries);
+
+        let summary: Vec<_> = deduped
+            .iter()
+            .map(|e| (e.timestamp.timestamp() % 60, e.entry_type))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (45, EntryType::Info),
+                (46, EntryType::Error),
+                (46, EntryType::Warning),
+                (47, EntryType::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi_removes_color_codes() {
+        assert_eq!(
+            strip_ansi("\x1b[1;31mERROR\x1b[0m: build \x1b[32mok\x1b[m"),
+            "ERROR: build ok"
+        );
+        assert_eq!(strip_ansi("\x1b]0;title\x07plain"), "plain");
+        assert_eq!(strip_ansi("no escapes here"), "no escapes here");
+    }
+
+    #[test]
+    fn test_parse_strips_ansi_from_messages() {
+        let options = ParseOptions::new().with_strip_ansi(true);
+        let input = "[2025-10-18T14:30:45Z] INFO: Invoking \x1b[36magent=architect\x1b[0m\n\
+                     \x1b[2m  at step 2\x1b[0m\n";
+
+        let format = LogFormat::Bracket;
+        let entries = parse_log_reader_with_format(Cursor::new(input), format, &options).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "Invoking agent=architect\n  at step 2");
+        assert_eq!(entries[0].agent_name.as_deref(), Some("architect"));
+
+        let from_bytes = parse_log_bytes(input.as_bytes(), &options);
+        assert_eq!(from_bytes[0].message, entries[0].message);
+
+        // Escape sequences are kept by default
+        let raw = parse_log_reader(Cursor::new(input)).unwrap();
+        assert!(raw[0].message.starts_with("Invoking \x1b[36magent=architect"));
+    }
+
+    #[test]
+    fn test_sampled_parse_keeps_expected_fraction() {
+        let options = ParseOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.log");
+        let contents: String = (0..10_000)
+            .map(|i| format!("[2025-10-18T14:30:00Z] INFO: entry {}\n", i))
+            .collect();
+        std::fs::write(&path, contents).unwrap();
+
+        let sample = parse_log_file_sampled(&path, 0.1, 42, &options).unwrap();
+
+        // Binomial(10000, 0.1) has a standard deviation of 30
+        assert!((850..=1150).contains(&sample.len()), "kept {}", sample.len());
+
+        let again = parse_log_file_sampled(&path, 0.1, 42, &options).unwrap();
+        let messages = |entries: &[LogEntry]| -> Vec<String> {
+            entries.iter().map(|e| e.message.clone()).collect()
+        };
+        assert_eq!(messages(&sample), messages(&again));
+        let reseeded = parse_log_file_sampled(&path, 0.1, 43, &options).unwrap();
+        assert_ne!(messages(&sample), messages(&reseeded));
+
+        assert_eq!(parse_log_file_sampled(&path, 1.0, 0, &options).unwrap().len(), 10_000);
+    }
+
+    #[test]
+    fn test_strict_levels_rejects_unknown_level() {
+        let lenient = ParseOptions::default();
+        let strict = ParseOptions::new().with_strict_levels(true);
+        let line = "[2025-10-18T14:30:45Z] FATAL: out of memory";
+
+        let entry = parse_log_entry(line, 7, &lenient).unwrap();
+        assert_eq!(entry.entry_type, EntryType::Unknown);
+        assert_eq!(entry.message, "out of memory");
+
+        match parse_log_entry(line, 7, &strict) {
+            Err(ParseError::MalformedEntry { line, details }) => {
+                assert_eq!(line, 7);
+                assert!(details.contains("FATAL"));
+            }
+            other => panic!("Expected MalformedEntry, got {:?}", other),
+        }
+
+        let known = "[2025-10-18T14:30:45Z] warn: disk nearly full";
+        assert_eq!(
+            parse_log_entry(known, 1, &strict).unwrap().entry_type,
+            EntryType::Warning
+        );
+        assert!(parse_log_entry("[2025-10-18T14:30:45Z] no level", 1, &strict).is_err());
+
+        // Strictness applies to whole files as well as single lines
+        let input = format!("{}\n{}\n", known, line);
+        let format = LogFormat::Bracket;
+        let mut failures = Vec::new();
+        let stream = LogEntryStream::new(input.as_bytes(), 1, format).with_options(strict);
+        let entries = collect_stream(stream, |line_num, _| failures.push(line_num)).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(failures, vec![2]);
+    }
 }
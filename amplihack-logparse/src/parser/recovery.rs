@@ -0,0 +1,197 @@
+// Tallies of why lines failed to parse
+//
+// Resilient parsing skips malformed lines with a warning each; a
+// `ParseReport` adds up those failures by kind so commands can print one
+// categorized summary instead of leaving users to read the warnings.
+//
+// Demonstrates:
+// - Classifying error values into a small Copy enum
+// - BTreeMap counters for stable, ordered summaries
+
+use crate::error::ParseError;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Details of a line that starts neither with `[` nor with `LEVEL [`
+pub(crate) const NO_OPENING_BRACKET: &str = "Line doesn't start with '[' or 'LEVEL ['";
+
+/// Details of a line whose timestamp bracket is never closed
+pub(crate) const NO_CLOSING_BRACKET: &str = "No closing ']' for timestamp";
+
+/// Details of a strict-mode line without a `LEVEL:` prefix
+pub(crate) const MISSING_LEVEL: &str = "Missing 'LEVEL:' after timestamp";
+
+/// Details prefix of a strict-mode line with an unknown level
+pub(crate) const UNRECOGNIZED_LEVEL: &str = "Unrecognized level";
+
+/// Details prefix of a line that isn't valid UTF-8
+pub(crate) const INVALID_UTF8: &str = "invalid UTF-8";
+
+/// Why a line failed to parse, ordered as summaries list them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FailureKind {
+    NoOpeningBracket,
+    NoClosingBracket,
+    InvalidTimestamp,
+    MissingLevel,
+    UnrecognizedLevel,
+    InvalidUtf8,
+
+    /// Any other failure, e.g. a malformed JSON or logfmt line
+    Other,
+}
+
+impl FailureKind {
+    /// Classify a per-line parse error
+    pub fn of(error: &ParseError) -> Self {
+        let details = match error {
+            ParseError::InvalidTimestamp(_) => return FailureKind::InvalidTimestamp,
+            ParseError::MalformedEntry { details, .. } => details.as_str(),
+            _ => return FailureKind::Other,
+        };
+
+        if details == NO_OPENING_BRACKET {
+            FailureKind::NoOpeningBracket
+        } else if details == NO_CLOSING_BRACKET {
+            FailureKind::NoClosingBracket
+        } else if details == MISSING_LEVEL {
+            FailureKind::MissingLevel
+        } else if details.starts_with(UNRECOGNIZED_LEVEL) {
+            FailureKind::UnrecognizedLevel
+        } else if details.starts_with(INVALID_UTF8) {
+            FailureKind::InvalidUtf8
+        } else {
+            FailureKind::Other
+        }
+    }
+
+    /// Short label used in summaries
+    pub fn description(self) -> &'static str {
+        match self {
+            FailureKind::NoOpeningBracket => "no opening bracket",
+            FailureKind::NoClosingBracket => "no closing bracket",
+            FailureKind::InvalidTimestamp => "invalid timestamp",
+            FailureKind::MissingLevel => "missing level",
+            FailureKind::UnrecognizedLevel => "unrecognized level",
+            FailureKind::InvalidUtf8 => "invalid UTF-8",
+            FailureKind::Other => "other",
+        }
+    }
+}
+
+/// Entries parsed and failed lines counted by kind
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseReport {
+    /// Entries that parsed successfully
+    pub entries: usize,
+
+    /// Failed lines per kind; kinds that never occurred are absent
+    pub failures: BTreeMap<FailureKind, usize>,
+}
+
+impl ParseReport {
+    /// Count one failed line
+    pub fn record_failure(&mut self, error: &ParseError) {
+        *self.failures.entry(FailureKind::of(error)).or_insert(0) += 1;
+    }
+
+    /// Failed lines of one kind
+    pub fn count(&self, kind: FailureKind) -> usize {
+        self.failures.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Failed lines of every kind
+    pub fn failure_count(&self) -> usize {
+        self.failures.values().sum()
+    }
+
+    /// True if no line failed
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Add another report's tallies to this one
+    pub fn merge(&mut self, other: &ParseReport) {
+        self.entries += other.entries;
+        for (&kind, &count) in &other.failures {
+            *self.failures.entry(kind).or_insert(0) += count;
+        }
+    }
+
+    /// One line per failure kind, e.g. `  invalid timestamp: 2`
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+
+        // Writing to a String cannot fail, so results are ignored
+        for (kind, count) in &self.failures {
+            let _ = writeln!(out, "  {}: {}", kind.description(), count);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_log_reader_report, ParseOptions};
+
+    #[test]
+    fn test_report_tallies_each_failure_kind() {
+        let input = "orphan line before any entry\n\
+                     [2025-10-18T14:30:45Z] INFO: fine\n\
+                     [2025-10-18T14:30:46Z INFO: never closed\n\
+                     [yesterday] INFO: bad time\n\
+                     [2025-13-45T99:00:00Z] ERROR: also bad time\n\
+                     [2025-10-18T14:30:47Z] WARNING: fine too\n";
+
+        let options = ParseOptions::default();
+        let (entries, report) = parse_log_reader_report(input.as_bytes(), &options).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(report.entries, 2);
+        assert_eq!(report.count(FailureKind::NoOpeningBracket), 1);
+        assert_eq!(report.count(FailureKind::NoClosingBracket), 1);
+        assert_eq!(report.count(FailureKind::InvalidTimestamp), 2);
+        assert_eq!(report.count(FailureKind::MissingLevel), 0);
+        assert_eq!(report.failure_count(), 4);
+        assert_eq!(
+            report.summary(),
+            "  no opening bracket: 1\n  no closing bracket: 1\n  invalid timestamp: 2\n"
+        );
+    }
+
+    #[test]
+    fn test_classifies_strict_and_byte_level_errors() {
+        let options = ParseOptions::new().with_strict_levels(true);
+        let strict = |line: &str| crate::parser::parse_log_entry(line, 1, &options).unwrap_err();
+
+        let missing = strict("[2025-10-18T14:30:45Z] no level here");
+        assert_eq!(FailureKind::of(&missing), FailureKind::MissingLevel);
+
+        let unknown = strict("[2025-10-18T14:30:45Z] FATAL: boom");
+        assert_eq!(FailureKind::of(&unknown), FailureKind::UnrecognizedLevel);
+
+        let utf8 = crate::parser::parse_log_entry_bytes(b"[\xff] INFO: x", 1, &options);
+        let utf8 = utf8.unwrap_err();
+        assert_eq!(FailureKind::of(&utf8), FailureKind::InvalidUtf8);
+
+        assert_eq!(FailureKind::of(&ParseError::Unknown("?".into())), FailureKind::Other);
+    }
+
+    #[test]
+    fn test_merge_adds_tallies() {
+        let mut a = ParseReport { entries: 3, ..ParseReport::default() };
+        a.record_failure(&ParseError::InvalidTimestamp("x".into()));
+
+        let mut b = ParseReport { entries: 1, ..ParseReport::default() };
+        b.record_failure(&ParseError::InvalidTimestamp("y".into()));
+        b.record_failure(&ParseError::Unknown("z".into()));
+
+        a.merge(&b);
+        assert_eq!(a.entries, 4);
+        assert_eq!(a.count(FailureKind::InvalidTimestamp), 2);
+        assert_eq!(a.count(FailureKind::Other), 1);
+        assert!(!a.is_clean());
+        assert!(ParseReport::default().is_clean());
+    }
+}
@@ -0,0 +1,69 @@
+// Reproducible random sampling of parsed entries
+//
+// Characterizing a huge log doesn't need every entry. A `Sampler` keeps
+// each entry with a fixed probability, drawing from a `StdRng` seeded
+// explicitly so the same seed always keeps the same entries.
+//
+// Demonstrates:
+// - Seeding rand's `StdRng` with `SeedableRng::seed_from_u64`
+// - Deterministic behavior from explicit state instead of global RNGs
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Keeps each entry with probability `rate`, reproducibly for a given seed
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    rate: f64,
+    rng: StdRng,
+}
+
+impl Sampler {
+    /// Create a sampler; `rate` is clamped to `[0.0, 1.0]`
+    pub fn new(rate: f64, seed: u64) -> Self {
+        Self {
+            rate: rate.clamp(0.0, 1.0),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Fraction of entries kept on average
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Decide whether to keep the next entry
+    pub fn keep(&mut self) -> bool {
+        self.rng.random_bool(self.rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kept(rate: f64, seed: u64, draws: usize) -> Vec<usize> {
+        let mut sampler = Sampler::new(rate, seed);
+        (0..draws).filter(|_| sampler.keep()).collect()
+    }
+
+    #[test]
+    fn test_same_seed_keeps_same_entries() {
+        assert_eq!(kept(0.3, 7, 1_000), kept(0.3, 7, 1_000));
+        assert_ne!(kept(0.3, 7, 1_000), kept(0.3, 8, 1_000));
+    }
+
+    #[test]
+    fn test_keeps_about_rate_of_entries() {
+        let count = kept(0.3, 42, 10_000).len();
+        assert!((2_700..=3_300).contains(&count), "kept {}", count);
+    }
+
+    #[test]
+    fn test_rate_bounds() {
+        assert!(kept(0.0, 1, 1_000).is_empty());
+        assert_eq!(kept(1.0, 1, 1_000).len(), 1_000);
+        assert_eq!(Sampler::new(2.5, 0).rate(), 1.0);
+        assert_eq!(Sampler::new(-1.0, 0).rate(), 0.0);
+    }
+}
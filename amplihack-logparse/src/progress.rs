@@ -0,0 +1,143 @@
+// Terminal progress bar
+//
+// A minimal single-line bar redrawn in place with `\r`, used while
+// parsing many files. It is shared across rayon workers, so all state
+// sits behind a Mutex and `inc` takes `&self`.
+//
+// Demonstrates:
+// - Interior mutability for progress reported from parallel iterators
+// - Generic writers so output can be captured in tests
+// - std::io::IsTerminal to stay silent when output is redirected
+
+use std::io::{self, IsTerminal, Stderr, Write};
+use std::sync::Mutex;
+
+/// Width of the bar itself, excluding counters and label
+const BAR_WIDTH: usize = 30;
+
+/// Progress bar advancing once per completed item
+pub struct ProgressBar<W = Stderr> {
+    total: usize,
+    enabled: bool,
+    state: Mutex<ProgressState<W>>,
+}
+
+struct ProgressState<W> {
+    current: usize,
+    out: W,
+}
+
+impl ProgressBar<Stderr> {
+    /// Bar drawn on stderr, unless `quiet` is set or either stdout or
+    /// stderr isn't a terminal (so piped output stays clean)
+    pub fn stderr(total: usize, quiet: bool) -> Self {
+        let enabled = !quiet && io::stdout().is_terminal() && io::stderr().is_terminal();
+        Self::new(io::stderr(), total, enabled)
+    }
+
+    /// Bar that never draws anything
+    pub fn hidden() -> Self {
+        Self::new(io::stderr(), 0, false)
+    }
+}
+
+impl<W: Write> ProgressBar<W> {
+    /// Create a bar writing to `out`; a disabled bar writes nothing
+    pub fn new(out: W, total: usize, enabled: bool) -> Self {
+        Self {
+            total,
+            enabled,
+            state: Mutex::new(ProgressState { current: 0, out }),
+        }
+    }
+
+    /// Whether the bar draws any output
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Record one finished item and redraw, showing `label` after the counts
+    pub fn inc(&self, label: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.current += 1;
+
+        if !self.enabled {
+            return;
+        }
+
+        let filled = (state.current * BAR_WIDTH)
+            .checked_div(self.total)
+            .unwrap_or(BAR_WIDTH)
+            .min(BAR_WIDTH);
+
+        let line = format!(
+            "\r[{}{}] {}/{} {}\x1b[K",
+            "=".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled),
+            state.current,
+            self.total,
+            label
+        );
+
+        // Progress is best-effort; a failed write shouldn't stop parsing
+        let _ = state.out.write_all(line.as_bytes());
+        let _ = state.out.flush();
+    }
+
+    /// Erase the bar so following output starts on a clean line
+    pub fn finish(&self) {
+        if !self.enabled {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = state.out.write_all(b"\r\x1b[2K");
+        let _ = state.out.flush();
+    }
+
+    /// Consume the bar, returning its writer
+    pub fn into_inner(self) -> W {
+        self.state
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+            .out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_draws_counts_and_label() {
+        let bar = ProgressBar::new(Vec::new(), 4, true);
+        bar.inc("a.log");
+        bar.inc("b.log");
+
+        let out = String::from_utf8(bar.into_inner()).unwrap();
+        let last = out.rsplit('\r').next().unwrap();
+
+        assert!(last.starts_with(&format!("[{}{}]", "=".repeat(15), " ".repeat(15))));
+        assert!(last.contains(" 2/4 b.log"));
+    }
+
+    #[test]
+    fn test_finish_clears_line() {
+        let bar = ProgressBar::new(Vec::new(), 1, true);
+        bar.inc("a.log");
+        bar.finish();
+
+        let out = String::from_utf8(bar.into_inner()).unwrap();
+        assert!(out.ends_with("\r\x1b[2K"));
+    }
+
+    #[test]
+    fn test_disabled_bar_writes_nothing() {
+        let bar = ProgressBar::new(Vec::new(), 3, false);
+        bar.inc("a.log");
+        bar.inc("b.log");
+        bar.finish();
+
+        assert!(bar.into_inner().is_empty());
+    }
+}
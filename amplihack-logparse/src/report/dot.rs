@@ -0,0 +1,59 @@
+// Graphviz DOT rendering of entry-type transitions
+//
+// Pipe the output through `dot -Tsvg` to visualize which entry types
+// tend to follow which.
+
+use crate::analyzer::TransitionCounts;
+use std::fmt::Write;
+
+/// Render transition counts as a DOT digraph
+///
+/// Edges are sorted by (from, to) so output is stable across runs, and
+/// each edge is labelled with its count.
+///
+/// Demonstrates:
+/// - Sorting borrowed HashMap entries for deterministic output
+pub fn render_dot(name: &str, transitions: &TransitionCounts) -> String {
+    let mut edges: Vec<_> = transitions.iter().collect();
+    edges.sort();
+
+    let mut out = String::new();
+
+    // Writing to a String cannot fail, so results are ignored
+    let _ = writeln!(out, "digraph \"{}\" {{", escape_id(name));
+    for ((from, to), count) in edges {
+        let _ = writeln!(out, "    {:?} -> {:?} [label=\"{}\"];", from, to, count);
+    }
+    let _ = writeln!(out, "}}");
+
+    out
+}
+
+/// Escape a string for use inside a quoted DOT identifier
+fn escape_id(id: &str) -> String {
+    id.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EntryType;
+
+    #[test]
+    fn test_render_dot_sorted_edges() {
+        let transitions = TransitionCounts::from([
+            ((EntryType::Error, EntryType::Info), 1),
+            ((EntryType::Info, EntryType::Error), 2),
+        ]);
+
+        let dot = render_dot("session \"1\"", &transitions);
+
+        assert_eq!(
+            dot,
+            "digraph \"session \\\"1\\\"\" {\n\
+             \x20   Info -> Error [label=\"2\"];\n\
+             \x20   Error -> Info [label=\"1\"];\n\
+             }\n"
+        );
+    }
+}
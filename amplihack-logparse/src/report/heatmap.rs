@@ -0,0 +1,118 @@
+// ASCII heatmap of activity by weekday and hour
+//
+// One row per UTC weekday and one column per hour, shaded relative to the
+// busiest slot, e.g.
+//
+//       000000000011111111112222
+//       012345678901234567890123
+//   Mon          :=@#-
+//   Tue           .+*
+
+use crate::analyzer::ActivityHeatmap;
+use std::fmt::Write;
+
+/// Weekday labels in `ActivityHeatmap` row order
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Marker for a slot with no entries
+const EMPTY_SHADE: char = ' ';
+
+/// Shades for non-empty slots, from least to most active
+const SHADES: [char; 9] = ['.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// Render a heatmap with hour headings and a scale line
+///
+/// Any slot with entries gets at least the lightest shade, and only the
+/// busiest slot(s) get the darkest, so quiet hours stay distinguishable
+/// from idle ones.
+///
+/// Demonstrates:
+/// - Scaling counts into a fixed palette with integer math
+/// - Building multi-line output with fmt::Write
+pub fn render_heatmap(heatmap: &ActivityHeatmap) -> String {
+    let peak = heatmap.iter().flatten().copied().max().unwrap_or(0);
+
+    if peak == 0 {
+        return "Activity heatmap: no entries\n".to_string();
+    }
+
+    let mut out = String::new();
+
+    // Writing to a String cannot fail, so results are ignored
+    let _ = writeln!(out, "Activity heatmap (UTC, peak {} entries/hour):", peak);
+    let tens: String = (0..24).map(|hour| char::from(b'0' + hour / 10)).collect();
+    let ones: String = (0..24).map(|hour| char::from(b'0' + hour % 10)).collect();
+    let _ = writeln!(out, "    {}", tens);
+    let _ = writeln!(out, "    {}", ones);
+
+    for (label, hours) in WEEKDAYS.iter().zip(heatmap) {
+        let row: String = hours.iter().map(|&count| shade(count, peak)).collect();
+        let _ = writeln!(out, "{} {}", label, row);
+    }
+
+    let _ = writeln!(
+        out,
+        "Scale: '{}' = none, '{}' = fewest .. '{}' = {} entries",
+        EMPTY_SHADE,
+        SHADES[0],
+        SHADES[SHADES.len() - 1],
+        peak
+    );
+
+    out
+}
+
+/// Shade for `count` entries relative to the busiest slot `peak`
+fn shade(count: usize, peak: usize) -> char {
+    if count == 0 {
+        return EMPTY_SHADE;
+    }
+    SHADES[(count * SHADES.len()).div_ceil(peak) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row<'a>(rendered: &'a str, label: &str) -> &'a str {
+        rendered
+            .lines()
+            .find_map(|line| line.strip_prefix(label))
+            .map(|rest| &rest[1..])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_heatmap_rows_and_shades() {
+        let mut heatmap = [[0; 24]; 7];
+        heatmap[0][9] = 9;
+        heatmap[0][10] = 1;
+        heatmap[2][13] = 5;
+        heatmap[6][23] = 9;
+
+        let rendered = render_heatmap(&heatmap);
+
+        assert!(rendered.starts_with("Activity heatmap (UTC, peak 9 entries/hour):\n"));
+        assert_eq!(row(&rendered, "Mon"), format!("{:9}@.{:13}", "", ""));
+        assert_eq!(row(&rendered, "Wed"), format!("{:13}+{:10}", "", ""));
+        assert_eq!(row(&rendered, "Sun"), format!("{:23}@", ""));
+        assert_eq!(row(&rendered, "Tue"), " ".repeat(24));
+        assert!(rendered.contains("    000000000011111111112222\n"));
+        assert!(rendered.contains("    012345678901234567890123\n"));
+        assert!(rendered.ends_with("Scale: ' ' = none, '.' = fewest .. '@' = 9 entries\n"));
+    }
+
+    #[test]
+    fn test_shade_bounds() {
+        assert_eq!(shade(0, 100), EMPTY_SHADE);
+        assert_eq!(shade(1, 100), '.');
+        assert_eq!(shade(100, 100), '@');
+        assert_eq!(shade(99, 100), '@');
+        assert_eq!(shade(88, 100), '%');
+    }
+
+    #[test]
+    fn test_empty_heatmap() {
+        assert_eq!(render_heatmap(&[[0; 24]; 7]), "Activity heatmap: no entries\n");
+    }
+}
@@ -0,0 +1,116 @@
+// Markdown rendering of analysis results
+//
+// Produces headed sections and tables suitable for pasting into PR
+// descriptions and GitHub issues.
+
+use crate::analyzer::PatternAnalysis;
+use crate::types::{AgentStats, TimingStats};
+use std::fmt::Write;
+
+/// Render timing, agent, and pattern results as Markdown
+///
+/// Demonstrates:
+/// - Borrowing several inputs to build one owned String
+/// - fmt::Write for formatted appends without extra allocations
+pub fn render_markdown(
+    timing: &TimingStats,
+    agents: &[AgentStats],
+    patterns: &PatternAnalysis,
+) -> String {
+    let mut out = String::new();
+
+    // Writing to a String cannot fail, so results are ignored
+    let _ = writeln!(out, "# Log Analysis\n");
+
+    let _ = writeln!(out, "## Timing\n");
+    let _ = writeln!(out, "- **Total duration:** {:.2}s", timing.total_duration_secs);
+    let _ = writeln!(out, "- **Entries:** {}", timing.entry_count);
+    let _ = writeln!(
+        out,
+        "- **Avg gap:** {:.2}s (p50 {:.2}s, p95 {:.2}s, p99 {:.2}s)",
+        timing.avg_time_between_entries,
+        timing.p50_gap_secs,
+        timing.p95_gap_secs,
+        timing.p99_gap_secs
+    );
+    let _ = writeln!(out, "- **Gap stddev:** {:.2}s\n", timing.stddev_gap_secs);
+
+    let _ = writeln!(out, "## Agents\n");
+    if agents.is_empty() {
+        let _ = writeln!(out, "_No agent invocations found._\n");
+    } else {
+        let _ = writeln!(out, "| name | invocations | total duration (ms) | avg duration (ms) |");
+        let _ = writeln!(out, "|------|-------------|---------------------|-------------------|");
+        for stats in agents {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {:.2} |",
+                escape_cell(&stats.name),
+                stats.invocation_count,
+                stats.total_duration_ms,
+                stats.avg_duration_ms
+            );
+        }
+        out.push('\n');
+    }
+
+    let _ = writeln!(out, "## Patterns\n");
+    if patterns.patterns.is_empty() {
+        let _ = writeln!(out, "_No significant patterns detected._");
+    } else {
+        for pattern in &patterns.patterns {
+            let _ = writeln!(out, "- `{:?}`", pattern);
+        }
+    }
+
+    out
+}
+
+/// Escape characters that would break a Markdown table cell
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::LogPattern;
+
+    fn timing() -> TimingStats {
+        TimingStats {
+            total_duration_secs: 30.0,
+            entry_count: 4,
+            avg_time_between_entries: 10.0,
+            p50_gap_secs: 10.0,
+            p95_gap_secs: 10.0,
+            p99_gap_secs: 10.0,
+            stddev_gap_secs: 0.0,
+            per_type_avg_duration: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_render_markdown_sections() {
+        let mut stats = AgentStats::new("architect".to_string());
+        stats.add_duration(100);
+        let patterns = PatternAnalysis {
+            patterns: vec![LogPattern::NoAgentActivity],
+        };
+
+        let md = render_markdown(&timing(), &[stats], &patterns);
+
+        assert!(md.contains("## Timing"));
+        assert!(md.contains("| name | invocations |"));
+        assert!(md.contains("| architect | 1 | 100 | 100.00 |"));
+        assert!(md.contains("- `NoAgentActivity`"));
+    }
+
+    #[test]
+    fn test_render_markdown_escapes_pipes() {
+        let stats = AgentStats::new("a|b".to_string());
+        let md = render_markdown(&timing(), &[stats], &PatternAnalysis { patterns: vec![] });
+
+        assert!(md.contains("| a\\|b |"));
+        assert!(md.contains("_No significant patterns detected._"));
+    }
+}
@@ -0,0 +1,29 @@
+// Report module
+//
+// Renders analysis results for humans and machines. The combined
+// `AnalysisReport` is what structured formats (JSON, YAML) serialize.
+
+pub mod dot;
+pub mod heatmap;
+pub mod markdown;
+pub mod table;
+pub mod timeline;
+pub mod yaml;
+
+use crate::analyzer::{DecisionSummary, PatternAnalysis, WarningSummary};
+use crate::types::{AgentStats, TimingStats};
+use serde::{Deserialize, Serialize};
+
+/// Combined output of the standard analyzers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisReport {
+    pub timing: TimingStats,
+    pub agents: Vec<AgentStats>,
+    pub patterns: PatternAnalysis,
+
+    #[serde(default)]
+    pub decisions: Vec<DecisionSummary>,
+
+    #[serde(default)]
+    pub warnings: Vec<WarningSummary>,
+}
@@ -0,0 +1,222 @@
+// Plain-text tables with auto-sized columns
+//
+// Every column is as wide as its widest cell, so long agent names push the
+// other columns over instead of breaking the alignment, e.g.
+//
+//   +-----------+-------------+
+//   | Agent     | Invocations |
+//   +===========+=============+
+//   | architect |           2 |
+//   +-----------+-------------+
+
+use crate::types::AgentStats;
+use std::fmt::Write;
+
+/// How cell text is placed within its column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// A table of text cells under a header row
+///
+/// Demonstrates:
+/// - Computing layout from owned data before rendering
+/// - Builder-style configuration with consuming methods
+#[derive(Debug, Clone)]
+pub struct Table {
+    headers: Vec<String>,
+    align: Vec<Align>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Create a table with the given header cells, all left-aligned
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| clean_cell(h)).collect(),
+            align: vec![Align::Left; headers.len()],
+            rows: Vec::new(),
+        }
+    }
+
+    /// Set the alignment of one column; out-of-range columns are ignored
+    pub fn with_align(mut self, column: usize, align: Align) -> Self {
+        if let Some(slot) = self.align.get_mut(column) {
+            *slot = align;
+        }
+        self
+    }
+
+    /// Append a row; missing cells are left blank and extra cells dropped
+    pub fn add_row(&mut self, cells: Vec<String>) {
+        let mut row: Vec<String> = cells.iter().map(|c| clean_cell(c)).collect();
+        row.resize(self.headers.len(), String::new());
+        self.rows.push(row);
+    }
+
+    /// Number of data rows (not counting the header)
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Render the table with a border around it and under the header
+    pub fn render(&self) -> String {
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|col| {
+                std::iter::once(&self.headers)
+                    .chain(&self.rows)
+                    .map(|row| row[col].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let rule = |fill: char| -> String {
+            let segments: Vec<String> = widths
+                .iter()
+                .map(|&width| fill.to_string().repeat(width + 2))
+                .collect();
+            format!("+{}+\n", segments.join("+"))
+        };
+
+        let mut out = String::new();
+        out.push_str(&rule('-'));
+        self.write_row(&mut out, &self.headers, &widths);
+        out.push_str(&rule('='));
+        for row in &self.rows {
+            self.write_row(&mut out, row, &widths);
+        }
+        if !self.rows.is_empty() {
+            out.push_str(&rule('-'));
+        }
+        out
+    }
+
+    fn write_row(&self, out: &mut String, cells: &[String], widths: &[usize]) {
+        // Writing to a String cannot fail, so results are ignored
+        for ((cell, &width), align) in cells.iter().zip(widths).zip(&self.align) {
+            let _ = match align {
+                Align::Left => write!(out, "| {:<width$} ", cell, width = width),
+                Align::Right => write!(out, "| {:>width$} ", cell, width = width),
+            };
+        }
+        out.push_str("|\n");
+    }
+}
+
+/// Keep a cell on one line so rows stay aligned
+fn clean_cell(text: &str) -> String {
+    text.replace(['\n', '\r', '\t'], " ")
+}
+
+/// Render agent statistics as a table, one row per agent
+///
+/// Min/max durations show `-` for agents without recorded durations.
+pub fn render_agent_table(agents: &[AgentStats]) -> String {
+    if agents.is_empty() {
+        return "No agent invocations found\n".to_string();
+    }
+
+    let mut table = Table::new(&[
+        "Agent",
+        "Invocations",
+        "Total (ms)",
+        "Avg (ms)",
+        "Min (ms)",
+        "Max (ms)",
+    ]);
+    for column in 1..6 {
+        table = table.with_align(column, Align::Right);
+    }
+
+    let duration = |ms: Option<u64>| ms.map_or_else(|| "-".to_string(), |ms| ms.to_string());
+
+    for stats in agents {
+        table.add_row(vec![
+            stats.name.clone(),
+            stats.invocation_count.to_string(),
+            stats.total_duration_ms.to_string(),
+            format!("{:.2}", stats.avg_duration_ms),
+            duration(stats.min_duration_ms),
+            duration(stats.max_duration_ms),
+        ]);
+    }
+
+    table.render()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(name: &str, durations: &[u64]) -> AgentStats {
+        let mut stats = AgentStats::new(name.to_string());
+        for &duration in durations {
+            stats.add_duration(duration);
+        }
+        stats
+    }
+
+    #[test]
+    fn test_agent_table_has_headers_and_aligned_rows() {
+        let agents = [
+            stats("architect", &[100, 300]),
+            stats("a-very-long-agent-name-that-would-break-fixed-columns", &[5]),
+            stats("builder", &[]),
+        ];
+
+        let rendered = render_agent_table(&agents);
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        for header in ["Agent", "Invocations", "Total (ms)", "Avg (ms)", "Min (ms)", "Max (ms)"] {
+            assert!(lines[1].contains(header), "missing header {}", header);
+        }
+
+        // Top rule, header, header rule, one row per agent, bottom rule
+        assert_eq!(lines.len(), 3 + agents.len() + 1);
+        assert!(lines[3].starts_with("| architect "));
+        assert!(lines[4].starts_with("| a-very-long-agent-name-that-would-break-fixed-columns |"));
+        assert!(lines[5].ends_with("|     0.00 |        - |        - |"));
+
+        // Every line is the same width and the column separators line up
+        let separators = |line: &str| -> Vec<usize> {
+            line.char_indices().filter(|&(_, c)| c == '|' || c == '+').map(|(i, _)| i).collect()
+        };
+        for line in &lines {
+            assert_eq!(separators(line), separators(lines[0]), "misaligned: {}", line);
+        }
+    }
+
+    #[test]
+    fn test_right_aligned_numbers() {
+        let rendered = render_agent_table(&[stats("architect", &[100, 300])]);
+        assert!(rendered.contains(
+            "| architect |           2 |        400 |   200.00 |      100 |      300 |\n"
+        ));
+    }
+
+    #[test]
+    fn test_rows_are_padded_and_cells_kept_on_one_line() {
+        let mut table = Table::new(&["a", "b"]);
+        table.add_row(vec!["multi\nline".to_string()]);
+        table.add_row(vec!["x".into(), "y".into(), "dropped".into()]);
+
+        assert_eq!(table.row_count(), 2);
+        assert_eq!(
+            table.render(),
+            "+------------+---+\n\
+             | a          | b |\n\
+             +============+===+\n\
+             | multi line |   |\n\
+             | x          | y |\n\
+             +------------+---+\n"
+        );
+    }
+
+    #[test]
+    fn test_no_agents() {
+        assert_eq!(render_agent_table(&[]), "No agent invocations found\n");
+    }
+}
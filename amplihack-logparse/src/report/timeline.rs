@@ -0,0 +1,156 @@
+// ASCII timeline of agent invocations
+//
+// Buckets a session's time span into fixed-width columns and marks the
+// columns in which agents ran, e.g.
+//
+//   |A....B...*.......|
+//   A = architect, B = builder, * = several agents
+
+use crate::types::LogSession;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Default number of timeline columns
+pub const DEFAULT_TIMELINE_COLUMNS: usize = 40;
+
+/// Marker for a column where more than one agent ran
+const MULTIPLE_MARKER: char = '*';
+
+/// Marker for a column with no agent invocations
+const EMPTY_MARKER: char = '.';
+
+/// Render an agent-invocation timeline with a legend
+///
+/// Each agent is assigned a letter in alphabetical order of name. Sessions
+/// shorter than the column count still span the full width; a session
+/// whose entries all share one timestamp marks only the first column.
+///
+/// Demonstrates:
+/// - Integer bucketing of time offsets
+/// - BTreeMap for stable letter assignment
+pub fn render_timeline(session: &LogSession, columns: usize) -> String {
+    let columns = columns.max(1);
+
+    let invocations: Vec<_> = session
+        .entries
+        .iter()
+        .filter_map(|e| e.agent_name.as_deref().map(|agent| (e.timestamp, agent)))
+        .collect();
+
+    if invocations.is_empty() {
+        return "Timeline: no agent invocations\n".to_string();
+    }
+
+    let start = session.entries.iter().map(|e| e.timestamp).min().unwrap_or(session.start_time);
+    let end = session.entries.iter().map(|e| e.timestamp).max().unwrap_or(start);
+    let span_ms = (end - start).num_milliseconds().max(1);
+
+    let mut letters: BTreeMap<&str, char> = BTreeMap::new();
+    for (_, agent) in &invocations {
+        letters.entry(agent).or_insert(' ');
+    }
+    for (agent_letter, letter) in letters.values_mut().zip(('A'..='Z').chain('a'..='z').cycle()) {
+        *agent_letter = letter;
+    }
+
+    let mut row = vec![EMPTY_MARKER; columns];
+    for (timestamp, agent) in &invocations {
+        let offset_ms = (*timestamp - start).num_milliseconds();
+        let column = ((offset_ms as i128 * columns as i128) / span_ms as i128)
+            .clamp(0, columns as i128 - 1) as usize;
+
+        let letter = letters[agent];
+        row[column] = match row[column] {
+            EMPTY_MARKER => letter,
+            existing if existing == letter => letter,
+            _ => MULTIPLE_MARKER,
+        };
+    }
+
+    let mut out = String::new();
+
+    // Writing to a String cannot fail, so results are ignored
+    let _ = writeln!(
+        out,
+        "Timeline ({} - {}):",
+        start.format("%Y-%m-%d %H:%M:%S"),
+        end.format("%Y-%m-%d %H:%M:%S")
+    );
+    let _ = writeln!(out, "|{}|", row.iter().collect::<String>());
+    for (agent, letter) in &letters {
+        let _ = writeln!(out, "  {} = {}", letter, agent);
+    }
+    if row.contains(&MULTIPLE_MARKER) {
+        let _ = writeln!(out, "  {} = several agents", MULTIPLE_MARKER);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EntryType, LogEntry};
+    use chrono::{Duration, Utc};
+    use std::collections::HashMap;
+
+    fn entry(secs: i64, agent: Option<&str>) -> LogEntry {
+        LogEntry {
+            timestamp: Utc::now() + Duration::seconds(secs),
+            entry_type: EntryType::Info,
+            message: "entry".to_string(),
+            agent_name: agent.map(String::from),
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        }
+    }
+
+    fn timeline_row(rendered: &str) -> &str {
+        rendered.lines().nth(1).unwrap()
+    }
+
+    #[test]
+    fn test_timeline_marks_expected_columns() {
+        let base = Utc::now();
+        let at = |secs: i64, agent: Option<&str>| LogEntry {
+            timestamp: base + Duration::seconds(secs),
+            ..entry(0, agent)
+        };
+
+        // 100 second session over 10 columns: 10 seconds per column
+        let session = LogSession::from_entries(
+            "timeline",
+            vec![
+                at(0, Some("architect")),
+                at(35, Some("builder")),
+                at(52, Some("architect")),
+                at(55, Some("builder")),
+                at(100, None),
+            ],
+        );
+
+        let rendered = render_timeline(&session, 10);
+
+        assert_eq!(timeline_row(&rendered), "|A..B.*....|");
+        assert!(rendered.contains("  A = architect\n"));
+        assert!(rendered.contains("  B = builder\n"));
+        assert!(rendered.contains("  * = several agents\n"));
+    }
+
+    #[test]
+    fn test_timeline_single_instant_session() {
+        let session = LogSession::from_entries("instant", vec![entry(0, Some("architect"))]);
+        let rendered = render_timeline(&session, 5);
+        assert_eq!(timeline_row(&rendered), "|A....|");
+    }
+
+    #[test]
+    fn test_timeline_without_agents() {
+        let session = LogSession::from_entries("quiet", vec![entry(0, None), entry(5, None)]);
+        assert_eq!(
+            render_timeline(&session, DEFAULT_TIMELINE_COLUMNS),
+            "Timeline: no agent invocations\n"
+        );
+    }
+}
@@ -0,0 +1,275 @@
+// YAML rendering of serializable results
+//
+// Pipeline configs consume the analysis report as YAML. Values go through
+// serde_json::Value first and are then written as block-style YAML:
+//
+//   timing:
+//     entry_count: 3
+//   agents:
+//     - name: "architect"
+//       invocation_count: 1
+//
+// Strings are always double-quoted with JSON escapes, which YAML accepts
+// verbatim, so values like `yes`, `null`, or `1.0` keep their string type.
+// Map keys come out in sorted order.
+//
+// Demonstrates:
+// - Recursive rendering over serde_json::Value
+// - Reusing one serialization path for several output formats
+
+use crate::error::ParseResult;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Width of each nesting level
+const INDENT: &str = "  ";
+
+/// Plain scalars YAML 1.1 readers would turn into booleans or null
+const RESERVED_WORDS: &[&str] = &["null", "true", "false", "yes", "no", "on", "off", "y", "n"];
+
+/// Serialize `value` as a block-style YAML document
+pub fn to_yaml<T: Serialize>(value: &T) -> ParseResult<String> {
+    let value = serde_json::to_value(value)?;
+
+    let mut lines = Vec::new();
+    render(&value, &mut lines);
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+/// Append the lines for `value`, unindented; callers indent nested output
+fn render(value: &Value, lines: &mut Vec<String>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let key = render_key(key);
+                match inline_scalar(value) {
+                    Some(scalar) => lines.push(format!("{}: {}", key, scalar)),
+                    None => {
+                        lines.push(format!("{}:", key));
+                        render_nested(value, lines, INDENT, INDENT);
+                    }
+                }
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for item in items {
+                match inline_scalar(item) {
+                    Some(scalar) => lines.push(format!("- {}", scalar)),
+                    // Nested collections start on the dash line (`- key: v`)
+                    None => render_nested(item, lines, "- ", INDENT),
+                }
+            }
+        }
+        scalar => lines.extend(inline_scalar(scalar)),
+    }
+}
+
+/// Render a nested collection, prefixing its first line with `first` and
+/// the remaining lines with `rest`
+fn render_nested(value: &Value, lines: &mut Vec<String>, first: &str, rest: &str) {
+    let mut nested = Vec::new();
+    render(value, &mut nested);
+
+    for (idx, line) in nested.into_iter().enumerate() {
+        let prefix = if idx == 0 { first } else { rest };
+        lines.push(format!("{}{}", prefix, line));
+    }
+}
+
+/// The single-line form of scalars and empty collections, if `value` has one
+fn inline_scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => Some("null".to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(quote(s)),
+        Value::Array(items) if items.is_empty() => Some("[]".to_string()),
+        Value::Object(map) if map.is_empty() => Some("{}".to_string()),
+        _ => None,
+    }
+}
+
+/// Leave identifier-like keys plain; quote everything else
+fn render_key(key: &str) -> String {
+    let plain = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && !RESERVED_WORDS.contains(&key.to_ascii_lowercase().as_str());
+
+    if plain {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+/// Double-quoted YAML string; JSON escapes are a subset of YAML's
+fn quote(s: &str) -> String {
+    Value::String(s.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::{
+        AgentAnalyzer, Analyzer, DecisionAnalyzer, PatternAnalyzer, TimingAnalyzer,
+        WarningAnalyzer,
+    };
+    use crate::report::AnalysisReport;
+    use crate::types::{EntryType, LogEntry, LogSession};
+    use chrono::{Duration, Utc};
+    use serde_json::{json, Map};
+    use std::collections::HashMap;
+
+    /// Parse the block-style subset `to_yaml` emits back into a Value
+    fn parse_yaml(text: &str) -> Value {
+        let mut lines: Vec<String> = text.lines().map(String::from).collect();
+        let mut idx = 0;
+        parse_block(&mut lines, &mut idx, 0)
+    }
+
+    fn indent_of(line: &str) -> usize {
+        line.len() - line.trim_start_matches(' ').len()
+    }
+
+    fn try_parse_scalar(raw: &str) -> Option<Value> {
+        match raw {
+            "[]" => Some(json!([])),
+            "{}" => Some(json!({})),
+            _ => serde_json::from_str(raw).ok(),
+        }
+    }
+
+    fn parse_scalar(raw: &str) -> Value {
+        try_parse_scalar(raw).unwrap()
+    }
+
+    fn parse_block(lines: &mut [String], idx: &mut usize, indent: usize) -> Value {
+        if lines[*idx][indent..].starts_with("- ") {
+            let mut items = Vec::new();
+            while *idx < lines.len()
+                && indent_of(&lines[*idx]) == indent
+                && lines[*idx][indent..].starts_with("- ")
+            {
+                // Re-indent the dash line so the item parses as a nested block
+                lines[*idx].replace_range(indent..indent + 2, INDENT);
+                match try_parse_scalar(&lines[*idx][indent + 2..]) {
+                    Some(scalar) => {
+                        items.push(scalar);
+                        *idx += 1;
+                    }
+                    None => items.push(parse_block(lines, idx, indent + 2)),
+                }
+            }
+            return Value::Array(items);
+        }
+
+        let mut map = Map::new();
+        while *idx < lines.len() && indent_of(&lines[*idx]) == indent {
+            let line = lines[*idx][indent..].to_string();
+            let (key, rest) = if line.starts_with('"') {
+                let end = line.find("\":").unwrap() + 1;
+                (serde_json::from_str(&line[..end]).unwrap(), &line[end + 1..])
+            } else {
+                let (key, rest) = line.split_once(':').unwrap();
+                (key.to_string(), rest)
+            };
+
+            *idx += 1;
+            let value = match rest.trim() {
+                "" => parse_block(lines, idx, indent + 2),
+                scalar => parse_scalar(scalar),
+            };
+            map.insert(key, value);
+        }
+        Value::Object(map)
+    }
+
+    fn sample_report() -> AnalysisReport {
+        let now = Utc::now();
+        let entry = |secs: i64, entry_type: EntryType, agent: Option<&str>, message: &str| {
+            LogEntry {
+                timestamp: now + Duration::seconds(secs),
+                entry_type,
+                message: message.to_string(),
+                agent_name: agent.map(String::from),
+                duration_ms: agent.map(|_| 1500),
+                fields: HashMap::new(),
+                byte_offset: None,
+            }
+        };
+        let session = LogSession::from_entries(
+            "yaml",
+            vec![
+                entry(0, EntryType::Info, None, "Starting: \"quoted\" yes"),
+                entry(2, EntryType::AgentInvocation, Some("architect"), "architect invoked"),
+                entry(5, EntryType::Warning, None, "disk: nearly \"full\""),
+                entry(900, EntryType::Error, None, "null"),
+            ],
+        );
+
+        AnalysisReport {
+            timing: TimingAnalyzer::new().analyze(&session).unwrap(),
+            agents: AgentAnalyzer::new().analyze(&session).unwrap(),
+            patterns: PatternAnalyzer::new().analyze(&session).unwrap(),
+            decisions: DecisionAnalyzer::new().analyze(&session).unwrap(),
+            warnings: WarningAnalyzer::new().analyze(&session).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_yaml_report_round_trips() {
+        let report = sample_report();
+
+        let yaml = to_yaml(&report).unwrap();
+        let parsed: AnalysisReport = serde_json::from_value(parse_yaml(&yaml)).unwrap();
+
+        let json: AnalysisReport =
+            serde_json::from_str(&serde_json::to_string_pretty(&report).unwrap()).unwrap();
+
+        assert_eq!(parsed.timing.entry_count, json.timing.entry_count);
+        assert_eq!(parsed.timing.total_duration_secs, json.timing.total_duration_secs);
+        assert_eq!(parsed.agents[0].name, json.agents[0].name);
+        assert_eq!(parsed.agents[0].total_duration_ms, json.agents[0].total_duration_ms);
+        assert_eq!(parsed.patterns.patterns, json.patterns.patterns);
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), serde_json::to_value(&json).unwrap());
+    }
+
+    #[test]
+    fn test_yaml_layout() {
+        let value = json!({
+            "name": "a",
+            "nested": { "count": 2, "empty": [] },
+            "items": [1, { "k": true, "v": null }, [2, 3]],
+            "on": "yes",
+            "a key": {}
+        });
+
+        assert_eq!(
+            to_yaml(&value).unwrap(),
+            "\"a key\": {}\n\
+             items:\n  \
+               - 1\n  \
+               - k: true\n    \
+                 v: null\n  \
+               - - 2\n    \
+                 - 3\n\
+             name: \"a\"\n\
+             nested:\n  \
+               count: 2\n  \
+               empty: []\n\
+             \"on\": \"yes\"\n"
+        );
+    }
+
+    #[test]
+    fn test_yaml_top_level_scalar() {
+        assert_eq!(to_yaml(&"text").unwrap(), "\"text\"\n");
+        assert_eq!(to_yaml(&Vec::<u8>::new()).unwrap(), "[]\n");
+    }
+}
@@ -0,0 +1,215 @@
+// JSON Schema for the serialized types
+//
+// Downstream tools validate our JSON output and generate bindings from
+// these schemas. They are written by hand to mirror the serde
+// representation in `types.rs`, so a field added there needs a matching
+// entry here (the tests below check the two stay in step).
+//
+// Demonstrates:
+// - Building JSON documents with serde_json::json!
+// - Keeping a hand-maintained contract honest with round-trip tests
+
+use serde_json::{json, Value};
+
+/// JSON Schema dialect the generated documents declare
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Schema document covering all serialized types under `$defs`
+pub fn schema() -> Value {
+    json!({
+        "$schema": SCHEMA_DIALECT,
+        "title": "amplihack-logparse",
+        "$defs": {
+            "EntryType": entry_type_schema(),
+            "LogEntry": log_entry_schema(),
+            "LogSession": log_session_schema(),
+            "AgentStats": agent_stats_schema(),
+            "TimingStats": timing_stats_schema(),
+        }
+    })
+}
+
+/// Schema for `EntryType`, serialized as its variant name
+pub fn entry_type_schema() -> Value {
+    json!({
+        "type": "string",
+        "enum": [
+            "AgentInvocation",
+            "Info",
+            "Warning",
+            "Error",
+            "Decision",
+            "Debug",
+            "Trace",
+            "Unknown"
+        ]
+    })
+}
+
+/// Schema for `LogEntry`
+pub fn log_entry_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "timestamp": { "type": "string", "format": "date-time" },
+            "entry_type": { "$ref": "#/$defs/EntryType" },
+            "message": { "type": "string" },
+            "agent_name": { "type": ["string", "null"] },
+            "duration_ms": { "type": ["integer", "null"], "minimum": 0 },
+            "fields": {
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+                "default": {}
+            },
+            "byte_offset": { "type": ["integer", "null"], "minimum": 0, "default": null }
+        },
+        "required": ["timestamp", "entry_type", "message", "agent_name", "duration_ms"]
+    })
+}
+
+/// Schema for `LogSession`
+pub fn log_session_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "id": { "type": "string" },
+            "entries": { "type": "array", "items": { "$ref": "#/$defs/LogEntry" } },
+            "start_time": { "type": "string", "format": "date-time" },
+            "end_time": { "type": ["string", "null"], "format": "date-time" }
+        },
+        "required": ["id", "entries", "start_time", "end_time"]
+    })
+}
+
+/// Schema for `AgentStats`
+pub fn agent_stats_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string" },
+            "invocation_count": { "type": "integer", "minimum": 0 },
+            "total_duration_ms": { "type": "integer", "minimum": 0 },
+            "avg_duration_ms": { "type": "number" },
+            "min_duration_ms": { "type": ["integer", "null"], "minimum": 0, "default": null },
+            "max_duration_ms": { "type": ["integer", "null"], "minimum": 0, "default": null },
+            "first_seen": { "type": ["string", "null"], "format": "date-time", "default": null },
+            "last_seen": { "type": ["string", "null"], "format": "date-time", "default": null }
+        },
+        "required": [
+            "name",
+            "invocation_count",
+            "total_duration_ms",
+            "avg_duration_ms"
+        ]
+    })
+}
+
+/// Schema for `TimingStats`
+pub fn timing_stats_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "total_duration_secs": { "type": "number" },
+            "entry_count": { "type": "integer", "minimum": 0 },
+            "avg_time_between_entries": { "type": "number" },
+            "p50_gap_secs": { "type": "number" },
+            "p95_gap_secs": { "type": "number" },
+            "p99_gap_secs": { "type": "number" },
+            "stddev_gap_secs": { "type": "number" },
+            "per_type_avg_duration": {
+                "type": "object",
+                "propertyNames": { "$ref": "#/$defs/EntryType" },
+                "additionalProperties": { "type": "number" },
+                "default": {}
+            }
+        },
+        "required": [
+            "total_duration_secs",
+            "entry_count",
+            "avg_time_between_entries",
+            "p50_gap_secs",
+            "p95_gap_secs",
+            "p99_gap_secs",
+            "stddev_gap_secs"
+        ]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AgentStats, EntryType, LogEntry, TimingStats};
+    use chrono::Utc;
+    use std::collections::{BTreeSet, HashMap};
+
+    fn property_names(schema: &Value) -> BTreeSet<String> {
+        schema["properties"].as_object().unwrap().keys().cloned().collect()
+    }
+
+    fn serialized_keys<T: serde::Serialize>(value: &T) -> BTreeSet<String> {
+        serde_json::to_value(value)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_log_entry_schema_properties_and_nullability() {
+        let schema = log_entry_schema();
+        let props = &schema["properties"];
+
+        for name in ["timestamp", "entry_type", "message"] {
+            assert!(props.get(name).is_some(), "missing property {}", name);
+        }
+
+        assert_eq!(props["agent_name"]["type"], json!(["string", "null"]));
+        assert_eq!(props["duration_ms"]["type"], json!(["integer", "null"]));
+        assert_eq!(props["message"]["type"], json!("string"));
+    }
+
+    #[test]
+    fn test_schemas_match_serialized_fields() {
+        let entry = LogEntry {
+            timestamp: Utc::now(),
+            entry_type: EntryType::Info,
+            message: "hello".to_string(),
+            agent_name: None,
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        };
+        let timing = TimingStats {
+            total_duration_secs: 0.0,
+            entry_count: 0,
+            avg_time_between_entries: 0.0,
+            p50_gap_secs: 0.0,
+            p95_gap_secs: 0.0,
+            p99_gap_secs: 0.0,
+            stddev_gap_secs: 0.0,
+            per_type_avg_duration: HashMap::new(),
+        };
+
+        assert_eq!(property_names(&log_entry_schema()), serialized_keys(&entry));
+        assert_eq!(
+            property_names(&agent_stats_schema()),
+            serialized_keys(&AgentStats::new("a".to_string()))
+        );
+        assert_eq!(property_names(&timing_stats_schema()), serialized_keys(&timing));
+    }
+
+    #[test]
+    fn test_schema_defs_resolve() {
+        let doc = schema();
+        assert_eq!(doc["$schema"], json!(SCHEMA_DIALECT));
+
+        for name in ["EntryType", "LogEntry", "LogSession", "AgentStats", "TimingStats"] {
+            assert!(doc["$defs"].get(name).is_some(), "missing $defs/{}", name);
+        }
+
+        let variants = doc["$defs"]["EntryType"]["enum"].as_array().unwrap();
+        assert!(variants.contains(&serde_json::to_value(EntryType::AgentInvocation).unwrap()));
+    }
+}
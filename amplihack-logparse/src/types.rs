@@ -2,8 +2,10 @@
 //
 // This module demonstrates Rust ownership and memory safety concepts
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
 
 /// Represents a single log entry
 ///
@@ -27,10 +29,40 @@ pub struct LogEntry {
 
     /// Optional duration in milliseconds
     pub duration_ms: Option<u64>,
+
+    /// Structured `key=value` fields found in the message
+    #[serde(default)]
+    pub fields: HashMap<String, String>,
+
+    /// Byte offset of the entry's first line in its source file, if the
+    /// source can report one (`None` for gzip and arbitrary readers)
+    #[serde(default)]
+    pub byte_offset: Option<u64>,
+}
+
+/// Canonical bracket-format line: `[TIMESTAMP] LEVEL: MESSAGE`
+///
+/// The timestamp is RFC 3339 in UTC with only as many fractional digits
+/// as it needs, so the line parses back into an equal entry. A message
+/// spanning several lines is written as-is, with its continuation lines
+/// following the first.
+///
+/// Demonstrates:
+/// - Implementing fmt::Display for a user-facing representation
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {}: {}",
+            self.timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            self.entry_type,
+            self.message
+        )
+    }
 }
 
 /// Types of log entries we can encounter
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum EntryType {
     /// Agent was invoked
     AgentInvocation,
@@ -47,10 +79,38 @@ pub enum EntryType {
     /// Decision record
     Decision,
 
+    /// Debug message
+    Debug,
+
+    /// Trace message (finer-grained than debug)
+    Trace,
+
     /// Unknown/other
     Unknown,
 }
 
+impl EntryType {
+    /// Level label as written in log lines, e.g. `INFO` or `AGENT`
+    pub fn label(self) -> &'static str {
+        match self {
+            EntryType::AgentInvocation => "AGENT",
+            EntryType::Info => "INFO",
+            EntryType::Warning => "WARNING",
+            EntryType::Error => "ERROR",
+            EntryType::Decision => "DECISION",
+            EntryType::Debug => "DEBUG",
+            EntryType::Trace => "TRACE",
+            EntryType::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+impl fmt::Display for EntryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
 /// A complete log session
 ///
 /// Demonstrates:
@@ -71,6 +131,81 @@ pub struct LogSession {
     pub end_time: Option<DateTime<Utc>>,
 }
 
+impl LogSession {
+    /// Build a session spanning the first and last of the given entries
+    ///
+    /// An empty session starts now and has no end time.
+    pub fn from_entries(id: &str, entries: Vec<LogEntry>) -> Self {
+        let start_time = entries
+            .first()
+            .map(|e| e.timestamp)
+            .unwrap_or_else(Utc::now);
+
+        let end_time = entries.last().map(|e| e.timestamp);
+
+        Self {
+            id: id.to_string(),
+            entries,
+            start_time,
+            end_time,
+        }
+    }
+
+    /// Whether the bounds agree with the entries: `end_time` is not before
+    /// `start_time`, and every entry falls inside `[start_time, end_time]`
+    pub fn bounds_are_consistent(&self) -> bool {
+        let end_ok = self.end_time.is_none_or(|end| end >= self.start_time);
+        let entries_ok = self.entries.iter().all(|e| {
+            e.timestamp >= self.start_time && self.end_time.is_none_or(|end| e.timestamp <= end)
+        });
+
+        end_ok && entries_ok
+    }
+}
+
+/// Builder for a `LogSession` whose bounds are derived from its entries
+///
+/// Demonstrates:
+/// - Consuming builder methods returning Self
+/// - Deriving invariants at build time instead of trusting callers
+#[derive(Debug, Clone, Default)]
+pub struct LogSessionBuilder {
+    id: String,
+    entries: Vec<LogEntry>,
+}
+
+impl LogSessionBuilder {
+    /// Start an empty builder with an empty id
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the session id
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    /// Add an entry; entries may be added in any order
+    pub fn add_entry(mut self, entry: LogEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Sort the entries by timestamp and build the session
+    ///
+    /// Entries sharing a timestamp keep the order they were added in.
+    /// `start_time`/`end_time` come from the first and last entry, so an
+    /// empty session starts now and has no end time.
+    pub fn build(mut self) -> LogSession {
+        self.entries.sort_by_key(|e| e.timestamp);
+
+        let session = LogSession::from_entries(&self.id, self.entries);
+        debug_assert!(session.bounds_are_consistent());
+        session
+    }
+}
+
 /// Statistics about agent usage
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentStats {
@@ -85,6 +220,24 @@ pub struct AgentStats {
 
     /// Average duration in milliseconds
     pub avg_duration_ms: f64,
+
+    /// Shortest recorded duration in milliseconds (`None` until a
+    /// duration is recorded)
+    #[serde(default)]
+    pub min_duration_ms: Option<u64>,
+
+    /// Longest recorded duration in milliseconds (`None` until a
+    /// duration is recorded)
+    #[serde(default)]
+    pub max_duration_ms: Option<u64>,
+
+    /// Timestamp of the earliest invocation seen
+    #[serde(default)]
+    pub first_seen: Option<DateTime<Utc>>,
+
+    /// Timestamp of the latest invocation seen
+    #[serde(default)]
+    pub last_seen: Option<DateTime<Utc>>,
 }
 
 impl AgentStats {
@@ -95,9 +248,21 @@ impl AgentStats {
             invocation_count: 0,
             total_duration_ms: 0,
             avg_duration_ms: 0.0,
+            min_duration_ms: None,
+            max_duration_ms: None,
+            first_seen: None,
+            last_seen: None,
         }
     }
 
+    /// Widen the first/last seen span to include an invocation at `timestamp`
+    ///
+    /// Invocations may be recorded in any order.
+    pub fn record_seen(&mut self, timestamp: DateTime<Utc>) {
+        self.first_seen = Some(self.first_seen.map_or(timestamp, |first| first.min(timestamp)));
+        self.last_seen = Some(self.last_seen.map_or(timestamp, |last| last.max(timestamp)));
+    }
+
     /// Add a duration measurement
     ///
     /// Demonstrates: Mutable borrowing (&mut self)
@@ -105,6 +270,37 @@ impl AgentStats {
         self.invocation_count += 1;
         self.total_duration_ms += duration_ms;
         self.avg_duration_ms = self.total_duration_ms as f64 / self.invocation_count as f64;
+        self.min_duration_ms = self.min_duration_ms.into_iter().chain([duration_ms]).min();
+        self.max_duration_ms = self.max_duration_ms.into_iter().chain([duration_ms]).max();
+    }
+
+    /// Whether any duration has been recorded (so min/max are set)
+    pub fn has_durations(&self) -> bool {
+        self.min_duration_ms.is_some()
+    }
+
+    /// Fold another set of stats for the same agent into this one
+    ///
+    /// Counts and totals are summed and the average recomputed from them;
+    /// min/max only move if `other` recorded durations. The names are not
+    /// compared, so callers must pair up stats for the same agent. The first
+    /// and last seen times widen to cover both.
+    ///
+    /// Demonstrates: Mutable borrow of self alongside a shared borrow of other
+    pub fn merge(&mut self, other: &AgentStats) {
+        self.invocation_count += other.invocation_count;
+        self.total_duration_ms += other.total_duration_ms;
+        self.avg_duration_ms = if self.invocation_count > 0 {
+            self.total_duration_ms as f64 / self.invocation_count as f64
+        } else {
+            0.0
+        };
+        self.min_duration_ms = self.min_duration_ms.into_iter().chain(other.min_duration_ms).min();
+        self.max_duration_ms = self.max_duration_ms.into_iter().chain(other.max_duration_ms).max();
+
+        for timestamp in other.first_seen.into_iter().chain(other.last_seen) {
+            self.record_seen(timestamp);
+        }
     }
 }
 
@@ -119,6 +315,23 @@ pub struct TimingStats {
 
     /// Average time between entries in seconds
     pub avg_time_between_entries: f64,
+
+    /// Median gap between entries in seconds
+    pub p50_gap_secs: f64,
+
+    /// 95th percentile gap between entries in seconds
+    pub p95_gap_secs: f64,
+
+    /// 99th percentile gap between entries in seconds
+    pub p99_gap_secs: f64,
+
+    /// Standard deviation of gaps between entries in seconds
+    pub stddev_gap_secs: f64,
+
+    /// Average `duration_ms` per entry type, over entries that have one
+    /// (types without any durations are omitted)
+    #[serde(default)]
+    pub per_type_avg_duration: HashMap<EntryType, f64>,
 }
 
 #[cfg(test)]
@@ -138,6 +351,71 @@ mod tests {
         assert_eq!(stats.invocation_count, 2);
         assert_eq!(stats.total_duration_ms, 300);
         assert_eq!(stats.avg_duration_ms, 150.0);
+        assert_eq!(stats.min_duration_ms, Some(100));
+        assert_eq!(stats.max_duration_ms, Some(200));
+    }
+
+    #[test]
+    fn test_agent_stats_min_max_single_sample() {
+        let mut stats = AgentStats::new("test-agent".to_string());
+        assert!(!stats.has_durations());
+        assert_eq!((stats.min_duration_ms, stats.max_duration_ms), (None, None));
+
+        stats.add_duration(100);
+
+        assert!(stats.has_durations());
+        assert_eq!(stats.min_duration_ms, Some(100));
+        assert_eq!(stats.max_duration_ms, Some(100));
+    }
+
+    fn entry_at(secs: i64, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: "2025-10-18T14:30:00Z".parse::<DateTime<Utc>>().unwrap()
+                + chrono::Duration::seconds(secs),
+            entry_type: EntryType::Info,
+            message: message.to_string(),
+            agent_name: None,
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        }
+    }
+
+    #[test]
+    fn test_session_builder_sorts_and_derives_bounds() {
+        let session = LogSessionBuilder::new()
+            .with_id("built")
+            .add_entry(entry_at(30, "third"))
+            .add_entry(entry_at(10, "first"))
+            .add_entry(entry_at(20, "second"))
+            .build();
+
+        let messages: Vec<_> = session.entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(session.id, "built");
+        assert_eq!(messages, vec!["first", "second", "third"]);
+        assert_eq!(session.start_time, entry_at(10, "").timestamp);
+        assert_eq!(session.end_time, Some(entry_at(30, "").timestamp));
+        assert!(session.bounds_are_consistent());
+    }
+
+    #[test]
+    fn test_session_builder_empty() {
+        let session = LogSessionBuilder::new().with_id("empty").build();
+
+        assert!(session.entries.is_empty());
+        assert_eq!(session.end_time, None);
+        assert!(session.bounds_are_consistent());
+    }
+
+    #[test]
+    fn test_bounds_consistency_detects_hand_built_mistakes() {
+        let mut session = LogSession::from_entries("manual", vec![entry_at(0, "a")]);
+        session.end_time = Some(session.start_time - chrono::Duration::seconds(1));
+        assert!(!session.bounds_are_consistent());
+
+        session.end_time = None;
+        session.start_time += chrono::Duration::seconds(5);
+        assert!(!session.bounds_are_consistent());
     }
 
     #[test]
@@ -150,9 +428,97 @@ mod tests {
             message, // Ownership moves here
             agent_name: None,
             duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
         };
 
         // message is no longer accessible here (moved)
         assert_eq!(entry.message, "Test message");
     }
+
+    #[test]
+    fn test_entry_type_serde_round_trip() {
+        for entry_type in [EntryType::Debug, EntryType::Trace] {
+            let json = serde_json::to_string(&entry_type).unwrap();
+            assert_eq!(serde_json::from_str::<EntryType>(&json).unwrap(), entry_type);
+        }
+
+        assert_eq!(serde_json::to_string(&EntryType::Trace).unwrap(), "\"Trace\"");
+    }
+
+    #[test]
+    fn test_agent_stats_merge() {
+        let mut a = AgentStats::new("architect".to_string());
+        a.add_duration(100);
+        a.add_duration(300);
+
+        let mut b = AgentStats::new("architect".to_string());
+        b.add_duration(50);
+
+        a.merge(&b);
+        assert_eq!(a.invocation_count, 3);
+        assert_eq!(a.total_duration_ms, 450);
+        assert_eq!(a.avg_duration_ms, 150.0);
+        assert_eq!((a.min_duration_ms, a.max_duration_ms), (Some(50), Some(300)));
+
+        // Stats without durations leave min/max alone
+        let mut untimed = AgentStats::new("architect".to_string());
+        untimed.invocation_count = 1;
+        a.merge(&untimed);
+        assert_eq!(a.invocation_count, 4);
+        assert_eq!((a.min_duration_ms, a.max_duration_ms), (Some(50), Some(300)));
+
+        let mut empty = AgentStats::new("builder".to_string());
+        empty.merge(&AgentStats::new("builder".to_string()));
+        assert_eq!(empty.avg_duration_ms, 0.0);
+        assert!(!empty.has_durations());
+    }
+
+    #[test]
+    fn test_agent_stats_seen_fields_default_when_missing() {
+        let json = r#"{"name":"architect","invocation_count":1,"total_duration_ms":5,
+            "avg_duration_ms":5.0,"min_duration_ms":5,"max_duration_ms":5}"#;
+        let stats: AgentStats = serde_json::from_str(json).unwrap();
+        assert_eq!((stats.first_seen, stats.last_seen), (None, None));
+
+        let mut stats = AgentStats::new("architect".to_string());
+        let at: DateTime<Utc> = "2025-10-18T14:30:00Z".parse().unwrap();
+        stats.record_seen(at);
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["first_seen"], "2025-10-18T14:30:00Z");
+        assert_eq!(json["last_seen"], "2025-10-18T14:30:00Z");
+    }
+
+    #[test]
+    fn test_entry_type_labels_parse_back() {
+        use crate::parser::parse_entry_type;
+
+        for entry_type in [
+            EntryType::AgentInvocation,
+            EntryType::Info,
+            EntryType::Warning,
+            EntryType::Error,
+            EntryType::Decision,
+            EntryType::Debug,
+            EntryType::Trace,
+            EntryType::Unknown,
+        ] {
+            assert_eq!(parse_entry_type(&entry_type.to_string()), entry_type);
+        }
+    }
+
+    #[test]
+    fn test_log_entry_display() {
+        let entry = LogEntry {
+            timestamp: "2025-10-18T14:30:45.250Z".parse().unwrap(),
+            entry_type: EntryType::Warning,
+            message: "Disk almost full".to_string(),
+            agent_name: None,
+            duration_ms: None,
+            fields: HashMap::new(),
+            byte_offset: None,
+        };
+
+        assert_eq!(entry.to_string(), "[2025-10-18T14:30:45.250Z] WARNING: Disk almost full");
+    }
 }